@@ -141,8 +141,14 @@ then
   echo "Gather all models on the local machine"
 
   mkdir -p $DIR/http/client/assets
-  ./get_local_models.py $DIR/http/client/assets
-  ./webify_models_v2.py $DIR/http/client/assets
+  cargo run --release --manifest-path $DIR/model_processing/crates/webify_models/Cargo.toml -- \
+    import $DIR/http/client/assets
+
+  RETVAL=$?
+  if [ $RETVAL -ne 0 ]; then
+    echo There are model import errors, exiting.
+    exit 1
+  fi
 
 else
   mkdir -p $DIR/http/client/assets
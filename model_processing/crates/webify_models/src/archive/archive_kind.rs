@@ -0,0 +1,68 @@
+//! Recognize archives from their file name
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+const EXTENSIONS: [(&str, ArchiveKind); 3] = [
+    (".zip", ArchiveKind::Zip),
+    (".tar.gz", ArchiveKind::TarGz),
+    (".tgz", ArchiveKind::TarGz),
+];
+
+impl ArchiveKind {
+    /// The kind of archive the path names, if any. The file doesn't need to exist.
+    pub fn from_path(path: &Path) -> Option<ArchiveKind> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        EXTENSIONS
+            .iter()
+            .find(|(extension, _)| name.ends_with(extension))
+            .map(|(_, kind)| *kind)
+    }
+
+    /// The file name of the archive without its extension, e.g. `gazebo_models` for
+    /// `gazebo_models.tar.gz`
+    pub fn stem(path: &Path) -> Option<String> {
+        let name = path.file_name()?.to_str()?;
+        EXTENSIONS
+            .iter()
+            .find(|(extension, _)| name.to_lowercase().ends_with(extension))
+            .map(|(extension, _)| name[..name.len() - extension.len()].to_string())
+    }
+}
+
+#[cfg(test)]
+mod archive_kind_tests {
+    use super::*;
+
+    #[test]
+    fn it_recognizes_archives() {
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("a/gazebo_models.zip")),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("gazebo_models.TAR.GZ")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("gazebo_models.tgz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(ArchiveKind::from_path(Path::new("gazebo_models")), None);
+        assert_eq!(ArchiveKind::from_path(Path::new("models.gz")), None);
+    }
+
+    #[test]
+    fn it_strips_the_extension() {
+        assert_eq!(
+            ArchiveKind::stem(Path::new("a/gazebo_models.tar.gz")),
+            Some(String::from("gazebo_models"))
+        );
+        assert_eq!(ArchiveKind::stem(Path::new("a/gazebo_models")), None);
+    }
+}
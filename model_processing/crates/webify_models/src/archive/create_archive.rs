@@ -0,0 +1,126 @@
+//! Pack a directory into a zip or tar.gz archive
+
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::archive::ArchiveKind;
+
+/// Write every file under `dir` into `archive`, with paths relative to `dir`. The kind
+/// of archive is picked from the extension of `archive`.
+pub fn create_archive(dir: &Path, archive: &Path) -> Result<(), Error> {
+    let kind = ArchiveKind::from_path(archive).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a zip or tar.gz archive", archive.display()),
+        )
+    })?;
+
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut files = vec![];
+    list_files(dir, &mut files)?;
+    files.sort();
+
+    let file = File::create(archive)?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = ZipWriter::new(file);
+            let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+            for path in files {
+                zip.start_file(entry_name(dir, &path), options)?;
+                io::copy(&mut File::open(&path)?, &mut zip)?;
+            }
+            zip.finish()?;
+        }
+        ArchiveKind::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+            for path in files {
+                tar.append_path_with_name(&path, entry_name(dir, &path))?;
+            }
+            tar.into_inner()?.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively collect the files under `dir`
+fn list_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The path of a file inside the archive, always with `/` separators
+fn entry_name(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod create_archive_tests {
+    use super::*;
+    use crate::archive::extract_archive;
+
+    fn round_trip(name: &str, archive_name: &str) -> Result<(), Error> {
+        let test_dir = Path::new("tests").join("archive").join(name);
+        let archive = test_dir.join(archive_name);
+        let extracted = test_dir.join("extracted");
+
+        create_archive(&Path::new("tests").join("import"), &archive)?;
+        extract_archive(&archive, &extracted)?;
+
+        assert_eq!(
+            fs::read_to_string(
+                extracted
+                    .join("user_models")
+                    .join("box")
+                    .join("model.config")
+            )?,
+            fs::read_to_string(
+                Path::new("tests")
+                    .join("import")
+                    .join("user_models")
+                    .join("box")
+                    .join("model.config")
+            )?
+        );
+        assert!(extracted
+            .join("gazebo_models")
+            .join("sphere")
+            .join("model.sdf")
+            .is_file());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_round_trips_zip() -> Result<(), Error> {
+        round_trip("test_run_it_round_trips_zip", "models.zip")
+    }
+
+    #[test]
+    fn it_round_trips_tar_gz() -> Result<(), Error> {
+        round_trip("test_run_it_round_trips_tar_gz", "models.tar.gz")
+    }
+}
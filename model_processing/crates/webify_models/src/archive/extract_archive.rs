@@ -0,0 +1,88 @@
+//! Extract a zip or tar.gz archive into a directory
+
+use std::{
+    fs::{self, File},
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+
+use crate::archive::ArchiveKind;
+
+/// Extract `archive` into `destination`, creating it if needed. Entries that would land
+/// outside of `destination` are skipped. When everything is wrapped in a single
+/// top-level directory, like GitHub's `gazebo_models-master/`, its contents are moved
+/// up so the models sit directly in `destination`.
+pub fn extract_archive(archive: &Path, destination: &Path) -> Result<(), Error> {
+    let kind = ArchiveKind::from_path(archive).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a zip or tar.gz archive", archive.display()),
+        )
+    })?;
+
+    fs::create_dir_all(destination)?;
+    let file = File::open(archive)?;
+    match kind {
+        ArchiveKind::Zip => zip::ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(destination))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?,
+        ArchiveKind::TarGz => tar::Archive::new(GzDecoder::new(file)).unpack(destination)?,
+    }
+
+    unwrap_single_directory(destination)
+}
+
+/// Move the contents of a lone top-level directory up into `dir`
+fn unwrap_single_directory(dir: &Path) -> Result<(), Error> {
+    let entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, Error>>()?;
+    let wrapper = match entries.as_slice() {
+        [entry] if entry.path().is_dir() => entry.path(),
+        _ => return Ok(()),
+    };
+
+    // Rename first in case the wrapper holds an entry with its own name
+    let moved = dir.join(".webify_models_unwrap");
+    fs::rename(&wrapper, &moved)?;
+    for entry in fs::read_dir(&moved)? {
+        let entry = entry?;
+        fs::rename(entry.path(), dir.join(entry.file_name()))?;
+    }
+
+    fs::remove_dir(&moved)
+}
+
+#[cfg(test)]
+mod extract_archive_tests {
+    use super::*;
+    use crate::archive::create_archive;
+
+    #[test]
+    fn it_unwraps_a_single_top_level_directory() -> Result<(), Error> {
+        let test_dir = Path::new("tests")
+            .join("archive")
+            .join("test_run_it_unwraps_a_single_top_level_directory");
+        let archive = test_dir.join("models.zip");
+        crate::import::copy_dir(
+            &Path::new("tests").join("import").join("gazebo_models"),
+            &test_dir.join("wrapped").join("gazebo_models-master"),
+        )?;
+        create_archive(&test_dir.join("wrapped"), &archive)?;
+
+        let extracted = test_dir.join("extracted");
+        extract_archive(&archive, &extracted)?;
+
+        assert!(extracted.join("box").join("model.config").is_file());
+        assert!(extracted.join("sphere").join("model.config").is_file());
+        assert!(!extracted.join("gazebo_models-master").exists());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_errors_on_unknown_archives() {
+        assert!(extract_archive(Path::new("tests/README.md"), Path::new("unused")).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+//! Set of functions to read models from, and write them back to, zip and tar.gz archives
+
+mod archive_kind;
+mod create_archive;
+mod extract_archive;
+mod open_workspace;
+
+pub use self::archive_kind::ArchiveKind;
+pub use self::create_archive::create_archive;
+pub use self::extract_archive::extract_archive;
+pub use self::open_workspace::open_workspace;
@@ -0,0 +1,279 @@
+//! Prepare the directory a command works in, so archives (and directories, when an
+//! output is given) are processed in a fresh copy and the originals are left untouched
+
+use std::{
+    env, fs,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    process,
+};
+
+use console::style;
+
+use crate::archive::{create_archive, extract_archive, ArchiveKind};
+use crate::progress::print_message;
+
+/// The directory a command works in, and the archive to pack it into when done
+#[derive(Debug)]
+pub struct Workspace {
+    root: PathBuf,
+    archive: Option<PathBuf>,
+    /// Whether the root is a temporary directory, removed once done with it
+    temporary: bool,
+}
+
+impl Workspace {
+    /// A workspace in `root`, which is removed if it's dropped before being kept
+    fn temporary(root: PathBuf, archive: Option<PathBuf>) -> Workspace {
+        Workspace {
+            root,
+            archive,
+            temporary: true,
+        }
+    }
+
+    /// The models directory to run the command on
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Pack the results into the output archive, if there is one, and clean up
+    pub fn finish(self) -> Result<(), Error> {
+        if let Some(archive) = &self.archive {
+            print_message(format!(
+                "{} {}",
+                style("Packing").bold(),
+                style(archive.display()).dim()
+            ));
+            create_archive(&self.root, archive)?;
+        }
+        if self.temporary {
+            fs::remove_dir_all(&self.root)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Workspace {
+    /// Remove the temporary directory when the command failed before finishing, so
+    /// failed runs don't pile up extracted models
+    fn drop(&mut self) {
+        if self.temporary && self.root.exists() {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+}
+
+/// Set up the workspace for `input`, a models directory or a zip/tar.gz archive.
+///
+/// -   A directory without `output` is worked on in place, like before.
+/// -   Anything else is copied or extracted into `output`, which must not exist yet.
+///     When `output` is itself an archive, the work happens in a temporary directory
+///     that is packed into it by [`Workspace::finish`].
+/// -   An archive without `output` goes to `<name>_webified/` next to it, or to a
+///     temporary directory when the command is `read_only`.
+pub fn open_workspace(
+    input: &Path,
+    output: Option<&Path>,
+    read_only: bool,
+) -> Result<Workspace, Error> {
+    let is_archive = ArchiveKind::from_path(input).is_some() && input.is_file();
+    let output = match (output, is_archive) {
+        (Some(output), _) => output.to_path_buf(),
+        (None, true) if read_only => {
+            let root = temp_dir(input);
+            return fill(input, true, Workspace::temporary(root, None));
+        }
+        (None, true) => input.with_file_name(format!(
+            "{}_webified",
+            ArchiveKind::stem(input).unwrap_or_default()
+        )),
+        (None, false) => {
+            return Ok(Workspace {
+                root: input.to_path_buf(),
+                archive: None,
+                temporary: false,
+            })
+        }
+    };
+
+    if input.is_dir() && resolve(&output)?.starts_with(input.canonicalize()?) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The output can't be inside the models directory.",
+        ));
+    }
+    if output.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists, please provide a fresh output.",
+                output.display()
+            ),
+        ));
+    }
+
+    match ArchiveKind::from_path(&output) {
+        Some(_) => fill(
+            input,
+            is_archive,
+            Workspace::temporary(temp_dir(&output), Some(output)),
+        ),
+        None => {
+            let filled = fill(
+                input,
+                is_archive,
+                Workspace::temporary(output.clone(), None),
+            );
+            // The output is kept from here on, with whatever the command leaves in it
+            filled.map(|mut workspace| {
+                workspace.temporary = false;
+                workspace
+            })
+        }
+    }
+}
+
+/// Extract or copy `input` into the root of the workspace, which is removed again when
+/// that fails
+fn fill(input: &Path, is_archive: bool, workspace: Workspace) -> Result<Workspace, Error> {
+    let root = workspace.root();
+    if root.exists() {
+        fs::remove_dir_all(root)?;
+    }
+
+    if is_archive {
+        print_message(format!(
+            "{} {}",
+            style("Extracting").bold(),
+            style(input.display()).dim()
+        ));
+        extract_archive(input, root)?;
+    } else {
+        crate::import::copy_dir(input, root)?;
+    }
+
+    Ok(workspace)
+}
+
+/// Temporary directory to work on the models of the archive at `path` in
+fn temp_dir(path: &Path) -> PathBuf {
+    env::temp_dir().join(format!(
+        "webify_models_{}_{}",
+        process::id(),
+        ArchiveKind::stem(path).unwrap_or_default()
+    ))
+}
+
+/// `path` made absolute, with the part of it that exists canonicalized, so it can be
+/// compared with other paths before it's created
+fn resolve(path: &Path) -> Result<PathBuf, Error> {
+    if path.exists() {
+        return path.canonicalize();
+    }
+
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            Ok(resolve(parent)?.join(name))
+        }
+        _ => Ok(env::current_dir()?.canonicalize()?.join(path)),
+    }
+}
+
+#[cfg(test)]
+mod open_workspace_tests {
+    use super::*;
+
+    #[test]
+    fn it_works_on_directories_in_place() -> Result<(), Error> {
+        let input = Path::new("tests").join("import");
+        let workspace = open_workspace(&input, None, false)?;
+
+        assert_eq!(workspace.root(), input);
+        workspace.finish()
+    }
+
+    #[test]
+    fn it_extracts_archives_next_to_them() -> Result<(), Error> {
+        let test_dir = Path::new("tests")
+            .join("archive")
+            .join("test_run_it_extracts_archives_next_to_them");
+        let archive = test_dir.join("models.tar.gz");
+        create_archive(&Path::new("tests").join("import"), &archive)?;
+
+        let workspace = open_workspace(&archive, None, false)?;
+        assert_eq!(workspace.root(), test_dir.join("models_webified"));
+        assert!(workspace.root().join("gazebo_models").join("box").is_dir());
+        workspace.finish()?;
+
+        // The output must be fresh
+        assert!(open_workspace(&archive, None, false).is_err());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_packs_into_output_archives() -> Result<(), Error> {
+        let test_dir = Path::new("tests")
+            .join("archive")
+            .join("test_run_it_packs_into_output_archives");
+        let input = test_dir.join("models.zip");
+        let output = test_dir.join("out").join("models.tar.gz");
+        create_archive(&Path::new("tests").join("import"), &input)?;
+        let original = fs::read(&input)?;
+
+        let workspace = open_workspace(&input, Some(&output), false)?;
+        let root = workspace.root().to_path_buf();
+        fs::write(root.join("index.json"), "[]")?;
+        workspace.finish()?;
+
+        assert!(!root.exists());
+        assert_eq!(fs::read(&input)?, original);
+
+        let extracted = test_dir.join("extracted");
+        extract_archive(&output, &extracted)?;
+        assert!(extracted.join("index.json").is_file());
+        assert!(extracted.join("user_models").join("box").is_dir());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_extracts_for_read_only_commands_to_a_temporary_dir() -> Result<(), Error> {
+        let test_dir = Path::new("tests")
+            .join("archive")
+            .join("test_run_it_extracts_for_read_only_commands_to_a_temporary_dir");
+        let archive = test_dir.join("models.zip");
+        create_archive(&Path::new("tests").join("import"), &archive)?;
+
+        let workspace = open_workspace(&archive, None, true)?;
+        let root = workspace.root().to_path_buf();
+        assert!(root.starts_with(env::temp_dir()));
+        assert!(root.join("gazebo_models").join("box").is_dir());
+        workspace.finish()?;
+
+        assert!(!root.exists());
+        assert!(!test_dir.join("models_webified").exists());
+
+        // A failed command doesn't finish its workspace, which is removed all the same
+        let root = open_workspace(&archive, None, true)?.root().to_path_buf();
+        assert!(!root.exists());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_refuses_outputs_inside_the_input() -> Result<(), Error> {
+        let input = Path::new("tests").join("import");
+
+        let error = open_workspace(&input, Some(&input.join("webified")), false).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(!input.join("webified").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+//! Hash the content of a directory
+
+use std::{fs, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::walk::walk_all_assets;
+
+/// Hash the relative path and content of every file under `dir`, along with `salt`
+/// (typically the settings the files are processed with), as a hex SHA-256
+pub fn hash_dir(dir: &Path, salt: &str) -> std::result::Result<String, std::io::Error> {
+    let files = walk_all_assets(dir)?.into_iter().map(|asset| asset.path);
+
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file);
+        // Separators and lengths keep `a` + `bc` from hashing like `ab` + `c`
+        for component in relative.components() {
+            hasher.update(component.as_os_str().to_string_lossy().as_bytes());
+            hasher.update(b"/");
+        }
+        let content = fs::read(&file)?;
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod hash_dir_tests {
+    use super::*;
+
+    #[test]
+    fn it_hashes_content_and_salt() -> std::result::Result<(), std::io::Error> {
+        let models = Path::new("tests").join("import");
+        let box_1 = models.join("gazebo_models").join("box");
+        let box_2 = models.join("user_models").join("box");
+
+        let hash = hash_dir(&box_1, "normalize=false")?;
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_dir(&box_1, "normalize=false")?);
+        assert_ne!(hash, hash_dir(&box_1, "normalize=true")?);
+        assert_ne!(hash, hash_dir(&box_2, "normalize=false")?);
+
+        Ok(())
+    }
+}
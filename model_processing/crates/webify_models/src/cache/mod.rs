@@ -0,0 +1,12 @@
+//! Set of functions to skip the models that were already webified, keyed by a hash of
+//! their content and the settings they were webified with
+
+mod hash_dir;
+mod model_cache;
+mod process;
+mod staging;
+
+pub use self::hash_dir::hash_dir;
+pub use self::model_cache::ModelCache;
+pub use self::process::{process, STAGING_DIR};
+pub use self::staging::Staging;
@@ -0,0 +1,162 @@
+//! A directory of webified models, keyed by the hash of the models they came from
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::hash_dir;
+use crate::import::copy_dir;
+use crate::report::FileAction;
+
+/// What a cached input turned into
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    model: String,
+    output: String,
+}
+
+/// A cache directory holding a `manifest.json`, which maps the hash of a model before
+/// webifying to the hash after, and an `outputs/<hash>/` copy of every webified model
+#[derive(Debug)]
+pub struct ModelCache {
+    dir: PathBuf,
+    settings: String,
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl ModelCache {
+    /// Open the cache in `dir`, creating it if needed. `settings` describes everything
+    /// besides the models themselves that affects the output, e.g. `--normalize`.
+    pub fn open(dir: &Path, settings: &str) -> std::result::Result<ModelCache, Error> {
+        fs::create_dir_all(dir.join("outputs"))?;
+
+        let manifest = dir.join("manifest.json");
+        let entries = if manifest.is_file() {
+            serde_json::from_str(&fs::read_to_string(&manifest)?)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(ModelCache {
+            dir: dir.to_path_buf(),
+            settings: settings.to_string(),
+            entries,
+        })
+    }
+
+    /// Hash the content of a model along with its name and the settings
+    pub fn key(&self, model_dir: &Path) -> std::result::Result<String, Error> {
+        let name = model_dir.file_name().unwrap_or_default().to_string_lossy();
+        hash_dir(model_dir, &format!("{}\n{}", self.settings, name))
+    }
+
+    /// Whether a model with this key is already the webified output of a cached model
+    pub fn is_output(&self, key: &str) -> bool {
+        self.entries.values().any(|entry| entry.output == key)
+    }
+
+    /// Replace the model with its webified copy, returning false when there is none
+    pub fn restore(&self, key: &str, model_dir: &Path) -> std::result::Result<bool, Error> {
+        let output = self.dir.join("outputs").join(key);
+        if !self.entries.contains_key(key) || !output.is_dir() {
+            return Ok(false);
+        }
+
+        remove_dir(model_dir)?;
+        copy_dir(&output, model_dir)?;
+
+        Ok(true)
+    }
+
+    /// Save a copy of the webified model under the key it had before webifying
+    pub fn store(&mut self, key: &str, model_dir: &Path) -> std::result::Result<(), Error> {
+        let output = self.dir.join("outputs").join(key);
+        if output.exists() {
+            remove_dir(&output)?;
+        }
+        copy_dir(model_dir, &output)?;
+
+        let entry = CacheEntry {
+            model: model_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            output: self.key(model_dir)?,
+        };
+        self.entries.insert(key.to_string(), entry);
+
+        Ok(())
+    }
+
+    /// Write the manifest, so the stored models are found next time
+    pub fn save(&self) -> std::result::Result<(), Error> {
+        fs::write(
+            self.dir.join("manifest.json"),
+            serde_json::to_string_pretty(&self.entries)?,
+        )
+    }
+}
+
+/// Remove a directory and record it
+fn remove_dir(dir: &Path) -> std::result::Result<(), Error> {
+    let action = FileAction::start("remove", dir);
+    match fs::remove_dir_all(dir) {
+        Ok(()) => {
+            action.finish(dir);
+            Ok(())
+        }
+        Err(e) => {
+            action.fail(&e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod model_cache_tests {
+    use super::*;
+
+    #[test]
+    fn it_stores_and_restores_models() -> std::result::Result<(), Error> {
+        let test_dir = Path::new("tests")
+            .join("cache")
+            .join("test_run_it_stores_and_restores_models");
+        let model_dir = test_dir.join("models").join("box");
+        copy_dir(
+            &Path::new("tests")
+                .join("import")
+                .join("gazebo_models")
+                .join("box"),
+            &model_dir,
+        )?;
+
+        let mut cache = ModelCache::open(&test_dir.join("cache"), "normalize=false")?;
+        let key = cache.key(&model_dir)?;
+        assert!(!cache.restore(&key, &model_dir)?);
+
+        fs::write(model_dir.join("webified.txt"), "yes")?;
+        cache.store(&key, &model_dir)?;
+        assert!(cache.is_output(&cache.key(&model_dir)?));
+        cache.save()?;
+
+        // A fresh copy of the original model is swapped for the webified one
+        fs::remove_file(model_dir.join("webified.txt"))?;
+        let cache = ModelCache::open(&test_dir.join("cache"), "normalize=false")?;
+        assert!(cache.restore(&key, &model_dir)?);
+        assert!(model_dir.join("webified.txt").is_file());
+
+        // But not when the settings differ
+        let cache = ModelCache::open(&test_dir.join("cache"), "normalize=true")?;
+        assert!(!cache.is_output(&cache.key(&model_dir)?));
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! Orchestrator to webify only the models that changed since they were last cached
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+
+use crate::cache::{ModelCache, Staging};
+use crate::model_config::list_model_dirs;
+use crate::progress::{create_progress_bar, print_message};
+
+/// Directory inside the models directory where models that need webifying are gathered
+pub const STAGING_DIR: &str = ".webify-staging";
+
+/// Orchestrator to webify the models directly inside `dir` using the cache in
+/// `cache_dir`. Models already webified are left alone and models cached with the same
+/// content and `settings` are restored, then `convert` runs on a directory holding only
+/// the rest, and the directories that aren't models. `finish` runs on the whole of `dir` afterwards, for the steps that need to
+/// see every model, and the converted models are cached as they are at the end.
+pub fn process<C, F>(
+    dir: &Path,
+    cache_dir: &Path,
+    settings: &str,
+    convert: C,
+    finish: F,
+) -> std::result::Result<(), std::io::Error>
+where
+    C: FnOnce(&Path) -> std::result::Result<(), std::io::Error>,
+    F: FnOnce(&Path) -> std::result::Result<(), std::io::Error>,
+{
+    let mut cache = ModelCache::open(cache_dir, settings)?;
+
+    let model_dirs = list_model_dirs(dir)?;
+
+    let cache_bar = create_progress_bar(model_dirs.len() as u64);
+    cache_bar.set_prefix("Cache Lookup");
+
+    let (mut up_to_date, mut restored) = (0, 0);
+    let mut pending = vec![];
+    for model_dir in model_dirs.iter() {
+        cache_bar.inc(1);
+        cache_bar.set_message(&format!("Hashing {:?}...", &model_dir));
+
+        let key = cache.key(model_dir)?;
+        if cache.is_output(&key) {
+            up_to_date += 1;
+        } else if cache.restore(&key, model_dir)? {
+            restored += 1;
+        } else {
+            pending.push((key, model_dir.clone()));
+        }
+    }
+    cache_bar.finish_with_message(&format!(
+        "{} up to date, {} restored, {} to webify",
+        up_to_date,
+        restored,
+        pending.len()
+    ));
+
+    // Directories that aren't models, like Gazebo's media, aren't cached, so they're
+    // webified again along with the pending models
+    let mut staged: Vec<PathBuf> = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if path.is_dir() && !hidden && !model_dirs.contains(&path) {
+            staged.push(path);
+        }
+    }
+    staged.extend(pending.iter().map(|(_, model_dir)| model_dir.clone()));
+
+    if !staged.is_empty() {
+        // The stages treat the top-level directories as models, so they run on a
+        // directory holding only the staged ones
+        let mut staging = Staging::create(&dir.join(STAGING_DIR))?;
+        for staged_dir in &staged {
+            staging.stage(staged_dir)?;
+        }
+
+        let converted = convert(staging.path());
+        staging.finish()?;
+        converted?;
+    }
+
+    finish(dir)?;
+
+    for (key, model_dir) in &pending {
+        cache.store(key, model_dir)?;
+    }
+    cache.save()?;
+    if !pending.is_empty() {
+        print_message(format!(
+            "{} {} model(s) in {}",
+            style("Cached").bold(),
+            pending.len(),
+            style(cache_dir.display()).dim()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::import::copy_dir;
+    use std::cell::RefCell;
+
+    /// Webify by adding a marker file to every model, recording which ones were seen
+    fn run(dir: &Path, cache_dir: &Path) -> std::result::Result<Vec<String>, std::io::Error> {
+        let converted = RefCell::new(vec![]);
+        process(
+            dir,
+            cache_dir,
+            "normalize=false",
+            |staging| {
+                for entry in fs::read_dir(staging)? {
+                    let model_dir = entry?.path();
+                    fs::write(model_dir.join("webified.txt"), "yes")?;
+                    converted
+                        .borrow_mut()
+                        .push(model_dir.file_name().unwrap().to_string_lossy().to_string());
+                }
+                Ok(())
+            },
+            |dir| fs::write(dir.join("index.json"), "[]"),
+        )?;
+
+        let mut converted = converted.into_inner();
+        converted.sort();
+        Ok(converted)
+    }
+
+    #[test]
+    fn it_only_webifies_changed_models() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests")
+            .join("cache")
+            .join("test_run_it_only_webifies_changed_models");
+        let source = Path::new("tests").join("import").join("gazebo_models");
+        let cache_dir = test_dir.join(".webify-cache");
+        let models = test_dir.join("models");
+        copy_dir(&source, &models)?;
+
+        assert_eq!(run(&models, &cache_dir)?, vec!["box", "sphere"]);
+        assert!(models.join("box").join("webified.txt").is_file());
+        assert!(models.join("index.json").is_file());
+        assert!(!models.join(STAGING_DIR).exists());
+
+        // Already webified
+        assert!(run(&models, &cache_dir)?.is_empty());
+
+        // A fresh copy is restored from the cache
+        let fresh = test_dir.join("fresh");
+        copy_dir(&source, &fresh)?;
+        assert!(run(&fresh, &cache_dir)?.is_empty());
+        assert!(fresh.join("sphere").join("webified.txt").is_file());
+
+        // Only the changed model is webified again
+        let changed = test_dir.join("changed");
+        copy_dir(&source, &changed)?;
+        fs::write(changed.join("sphere").join("notes.txt"), "changed")?;
+        assert_eq!(run(&changed, &cache_dir)?, vec!["sphere"]);
+        assert!(changed.join("box").join("webified.txt").is_file());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//! A directory holding the models being webified, which puts them back when dropped
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::import::copy_dir;
+
+/// The `STAGING_DIR` of a models directory, with the models moved or copied into it and
+/// where each of them goes once webified. Dropping it without `finish` still puts them
+/// there, so a failed or panicking conversion doesn't leave models hidden in it.
+#[derive(Debug)]
+pub struct Staging {
+    dir: PathBuf,
+    staged: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Staging {
+    /// Create an empty staging directory at `dir`, removing what's left of a previous one
+    pub fn create(dir: &Path) -> std::result::Result<Staging, std::io::Error> {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        fs::create_dir_all(dir)?;
+
+        Ok(Staging {
+            dir: dir.to_path_buf(),
+            staged: vec![],
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Move the directory `from` in, to be moved back where it was
+    pub fn stage(&mut self, from: &Path) -> std::result::Result<(), std::io::Error> {
+        let staged = self.dir.join(from.file_name().unwrap_or_default());
+        fs::rename(from, &staged)?;
+        self.staged.push((staged, from.to_path_buf()));
+
+        Ok(())
+    }
+
+    /// Copy the directory `from` in, to be moved to `to`
+    pub fn copy(&mut self, from: &Path, to: &Path) -> std::result::Result<(), std::io::Error> {
+        let staged = self.dir.join(to.file_name().unwrap_or_default());
+        copy_dir(from, &staged)?;
+        self.staged.push((staged, to.to_path_buf()));
+
+        Ok(())
+    }
+
+    /// Move every staged directory where it goes and remove the staging directory
+    pub fn finish(mut self) -> std::result::Result<(), std::io::Error> {
+        self.release()
+    }
+
+    fn release(&mut self) -> std::result::Result<(), std::io::Error> {
+        while let Some((staged, to)) = self.staged.pop() {
+            fs::rename(&staged, &to)?;
+        }
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        // Errors were already returned by `finish`, or a conversion is failing anyway
+        let _ = self.release();
+    }
+}
+
+#[cfg(test)]
+mod staging_tests {
+    use super::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn it_puts_the_models_back_on_panic() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests")
+            .join("cache")
+            .join("test_run_it_puts_the_models_back_on_panic");
+        copy_dir(
+            &Path::new("tests").join("import").join("gazebo_models"),
+            &dir,
+        )?;
+
+        let panicked = catch_unwind(AssertUnwindSafe(|| {
+            let mut staging = Staging::create(&dir.join(".staging")).unwrap();
+            staging.stage(&dir.join("box")).unwrap();
+            assert!(!dir.join("box").exists());
+            panic!("conversion failed");
+        }));
+
+        assert!(panicked.is_err());
+        assert!(dir.join("box").join("model.config").is_file());
+        assert!(!dir.join(".staging").exists());
+
+        fs::remove_dir_all(&dir)
+    }
+
+    #[test]
+    fn it_moves_the_copies_where_they_go() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests")
+            .join("cache")
+            .join("test_run_it_moves_the_copies_where_they_go");
+        let source = Path::new("tests").join("import").join("gazebo_models");
+        fs::create_dir_all(&dir)?;
+
+        let mut staging = Staging::create(&dir.join(".staging"))?;
+        staging.copy(&source.join("box"), &dir.join("box"))?;
+        assert!(staging.path().join("box").join("model.config").is_file());
+        staging.finish()?;
+
+        assert!(dir.join("box").join("model.config").is_file());
+        assert!(!dir.join(".staging").exists());
+
+        fs::remove_dir_all(&dir)
+    }
+}
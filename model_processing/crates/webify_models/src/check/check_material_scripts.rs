@@ -0,0 +1,99 @@
+//! Check that the textures material scripts refer to exist
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::check::{Problem, ProblemKind};
+use crate::image_processing::Image;
+use crate::materials::{parse_material_script, OgreMaterial};
+
+/// Check that every `texture` of the material scripts is one of the `images`. OGRE finds
+/// textures by file name in any of its resource directories, so the texture may be in
+/// another model, and webifying may have changed its extension to PNG.
+pub fn check_material_scripts(scripts: &[PathBuf], images: &[Image]) -> Vec<Problem> {
+    let mut known: HashSet<String> = HashSet::new();
+    for image in images {
+        known.extend(file_name_and_stem(&image.path));
+    }
+
+    let mut problems = vec![];
+    for script in scripts {
+        let nodes = match fs::read_to_string(script).and_then(|s| parse_material_script(&s)) {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                problems.push(Problem::new(ProblemKind::Unreadable, script, e.to_string()));
+                continue;
+            }
+        };
+
+        for node in nodes.iter().filter(|node| node.name == "material") {
+            let texture = match OgreMaterial::from_node(node).and_then(|m| m.texture) {
+                Some(texture) => texture,
+                None => continue,
+            };
+            if !file_name_and_stem(Path::new(&texture))
+                .iter()
+                .any(|name| known.contains(name))
+            {
+                problems.push(Problem::new(
+                    ProblemKind::MissingScriptTexture,
+                    script,
+                    format!(
+                        "Material {} uses {}, which isn't in any textures directory",
+                        node.values.first().map(String::as_str).unwrap_or_default(),
+                        texture
+                    ),
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+/// The file name, and the stem with a `.png` extension, which is what it's called once
+/// converted
+fn file_name_and_stem(path: &Path) -> Vec<String> {
+    let mut names = vec![];
+    if let Some(file_name) = path.file_name() {
+        names.push(file_name.to_string_lossy().to_string());
+    }
+    if let Some(stem) = path.file_stem() {
+        names.push(format!("{}.png", stem.to_string_lossy()));
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod check_material_scripts_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_missing_textures() {
+        let model_dir = Path::new("tests")
+            .join("check")
+            .join("models")
+            .join("broken");
+        let script = model_dir
+            .join("materials")
+            .join("scripts")
+            .join("broken.material");
+        let wall = model_dir.join("textures").join("wall.png");
+
+        let problems = check_material_scripts(
+            &[script],
+            &[Image {
+                path: wall,
+                extension: String::from("png"),
+            }],
+        );
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, ProblemKind::MissingScriptTexture);
+        assert!(problems[0].message.contains("Broken/Absent"));
+    }
+}
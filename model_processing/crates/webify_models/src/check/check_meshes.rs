@@ -0,0 +1,78 @@
+//! Check that meshes find their textures and stay within the triangle budget
+
+use std::path::{Path, PathBuf};
+
+use crate::check::{Problem, ProblemKind};
+use crate::collada::{read_dae, read_images};
+use crate::stats::{collect_mesh_stats, resolve_mesh_texture};
+
+/// Check that every `<init_from>` of the meshes points at a file, and that no mesh
+/// draws more than `max_triangles` triangles. `dir` is the models directory, so that
+/// textures can also be found in the `materials/textures` of the mesh's model.
+pub fn check_meshes(meshes: &[PathBuf], dir: &Path, max_triangles: usize) -> Vec<Problem> {
+    let mut problems = vec![];
+    for mesh in meshes {
+        let model_dir = mesh
+            .strip_prefix(dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map_or_else(|| dir.to_path_buf(), |model| dir.join(model));
+
+        let root = match read_dae(mesh) {
+            Ok(root) => root,
+            Err(e) => {
+                problems.push(Problem::new(ProblemKind::Unreadable, mesh, e.to_string()));
+                continue;
+            }
+        };
+        for image in read_images(&root) {
+            if resolve_mesh_texture(&image, mesh, &model_dir).is_none() {
+                problems.push(Problem::new(
+                    ProblemKind::MissingMeshTexture,
+                    mesh,
+                    format!("<init_from>{}</init_from> doesn't exist", image),
+                ));
+            }
+        }
+
+        match collect_mesh_stats(mesh, &model_dir) {
+            Ok(stats) if stats.triangles > max_triangles => problems.push(Problem::new(
+                ProblemKind::TooManyTriangles,
+                mesh,
+                format!(
+                    "{} triangles is over the budget of {}",
+                    stats.triangles, max_triangles
+                ),
+            )),
+            Ok(_) => {}
+            Err(e) => problems.push(Problem::new(ProblemKind::Unreadable, mesh, e.to_string())),
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod check_meshes_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_missing_textures_and_heavy_meshes() {
+        let dir = Path::new("tests").join("check").join("models");
+        let cube = dir.join("cube").join("meshes").join("cube.dae");
+        let broken = dir.join("broken").join("meshes").join("broken.dae");
+
+        assert!(check_meshes(std::slice::from_ref(&cube), &dir, 12).is_empty());
+
+        let problems = check_meshes(&[cube, broken], &dir, 11);
+        assert_eq!(
+            problems.iter().map(|p| p.kind).collect::<Vec<_>>(),
+            vec![
+                ProblemKind::TooManyTriangles,
+                ProblemKind::MissingMeshTexture,
+                ProblemKind::TooManyTriangles
+            ]
+        );
+        assert!(problems[1].message.contains("missing.png"));
+    }
+}
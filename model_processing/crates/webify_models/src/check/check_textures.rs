@@ -0,0 +1,113 @@
+//! Check that textures are PNGs of a reasonable size, where the web client expects them
+
+use std::path::{Path, PathBuf};
+
+use crate::check::{Problem, ProblemKind};
+use crate::image_processing::Image;
+
+/// Check the format, location and dimensions of every texture. The largest side may be
+/// at most `max_size` pixels, and both sides must be powers of two for WebGL 1 to mip
+/// map them.
+pub fn check_textures(images: &[Image], max_size: u32) -> Vec<Problem> {
+    let mut problems = vec![];
+    for image in images {
+        let path = &image.path;
+        if image.extension.to_lowercase() != "png" {
+            problems.push(Problem::new(
+                ProblemKind::NonPngTexture,
+                path,
+                format!(
+                    "{} texture, convert it to PNG",
+                    image.extension.to_uppercase()
+                ),
+            ));
+        }
+
+        let file_name = PathBuf::from(path.file_name().unwrap_or_default());
+        let in_place = path.ends_with(Path::new("materials").join("textures").join(&file_name))
+            || path.ends_with(Path::new("meshes").join(&file_name));
+        if !in_place {
+            problems.push(Problem::new(
+                ProblemKind::MisplacedTexture,
+                path,
+                "Texture outside of materials/textures",
+            ));
+        }
+
+        let (width, height) = match image::image_dimensions(path) {
+            Ok(dimensions) => dimensions,
+            Err(e) => {
+                problems.push(Problem::new(ProblemKind::Unreadable, path, e.to_string()));
+                continue;
+            }
+        };
+        if width.max(height) > max_size {
+            problems.push(Problem::new(
+                ProblemKind::OversizedTexture,
+                path,
+                format!("{}x{} is over {} pixels", width, height, max_size),
+            ));
+        }
+        if !width.is_power_of_two() || !height.is_power_of_two() {
+            problems.push(Problem::new(
+                ProblemKind::NonPowerOfTwoTexture,
+                path,
+                format!("{}x{} isn't a power of two", width, height),
+            ));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod check_textures_tests {
+    use super::*;
+
+    fn image(path: PathBuf) -> Image {
+        let extension = path.extension().unwrap().to_string_lossy().to_string();
+        Image { path, extension }
+    }
+
+    #[test]
+    fn it_accepts_web_ready_textures() {
+        let png = Path::new("tests")
+            .join("check")
+            .join("models")
+            .join("cube")
+            .join("materials")
+            .join("textures")
+            .join("cube_diffuse.png");
+
+        assert!(check_textures(&[image(png.clone())], 2048).is_empty());
+        assert_eq!(
+            check_textures(&[image(png)], 4)
+                .iter()
+                .map(|p| p.kind)
+                .collect::<Vec<_>>(),
+            vec![ProblemKind::OversizedTexture]
+        );
+    }
+
+    #[test]
+    fn it_finds_misplaced_non_png_textures() {
+        let jpg = Path::new("tests")
+            .join("check")
+            .join("models")
+            .join("broken")
+            .join("textures")
+            .join("wall.jpg");
+
+        assert_eq!(
+            check_textures(&[image(jpg)], 2048)
+                .iter()
+                .map(|p| p.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                ProblemKind::NonPngTexture,
+                ProblemKind::MisplacedTexture,
+                ProblemKind::NonPowerOfTwoTexture
+            ]
+        );
+    }
+}
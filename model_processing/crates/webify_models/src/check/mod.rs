@@ -0,0 +1,13 @@
+//! Find what keeps models from being web ready, without changing anything
+
+mod check_material_scripts;
+mod check_meshes;
+mod check_textures;
+mod problem;
+mod process;
+
+pub use self::check_material_scripts::check_material_scripts;
+pub use self::check_meshes::check_meshes;
+pub use self::check_textures::check_textures;
+pub use self::problem::{Problem, ProblemKind};
+pub use self::process::{process, Budget};
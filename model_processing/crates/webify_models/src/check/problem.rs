@@ -0,0 +1,48 @@
+//! Structure that represents something that isn't web ready
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProblemKind {
+    /// A file that couldn't be read or parsed
+    Unreadable,
+    /// A texture in a format other than PNG
+    NonPngTexture,
+    /// A texture outside of `materials/textures` (or next to the meshes)
+    MisplacedTexture,
+    /// A texture bigger than the budget
+    OversizedTexture,
+    NonPowerOfTwoTexture,
+    /// An `<init_from>` pointing at a file that doesn't exist
+    MissingMeshTexture,
+    /// A material script `texture` that isn't in any textures directory
+    MissingScriptTexture,
+    /// A mesh with more triangles than the budget
+    TooManyTriangles,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(kind: ProblemKind, path: &Path, message: impl Into<String>) -> Problem {
+        Problem {
+            kind,
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
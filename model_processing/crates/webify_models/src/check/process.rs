@@ -0,0 +1,96 @@
+//! Orchestrator to check that every model of a directory is web ready
+
+use std::path::{Component, Path};
+
+use console::style;
+use tracing::info_span;
+
+use crate::check::{check_material_scripts, check_meshes, check_textures, Problem};
+use crate::image_processing::scan_dir_for_image_types;
+use crate::logging::model_span;
+use crate::materials::scan_dir_for_material_scripts;
+use crate::mesh_update::scan_dir_for_meshes;
+use crate::progress::{print_message, print_warning};
+
+/// Limits past which textures and meshes are too heavy for the web client
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Largest side of a texture, in pixels
+    pub max_texture_size: u32,
+    /// Triangles drawn by a single mesh
+    pub max_triangles: usize,
+}
+
+impl Default for Budget {
+    fn default() -> Budget {
+        Budget {
+            max_texture_size: 2048,
+            max_triangles: 100_000,
+        }
+    }
+}
+
+/// Orchestrator to run every check, log the problems found with the file they're in,
+/// and return how many there are. Textures are the images with one of the extensions
+/// `texture_types`, as configured in `webify.toml`. Nothing is changed, and thumbnails
+/// aren't textures so they're left out.
+pub fn process(
+    dir: &Path,
+    budget: &Budget,
+    texture_types: &[&str],
+) -> std::result::Result<usize, std::io::Error> {
+    let _stage = info_span!("stage", name = "check").entered();
+    let mut images = scan_dir_for_image_types(dir, texture_types)?;
+    images.retain(|image| {
+        !image
+            .path
+            .components()
+            .any(|c| c == Component::Normal("thumbnails".as_ref()))
+    });
+    let meshes = scan_dir_for_meshes(dir)?;
+    let scripts = scan_dir_for_material_scripts(dir)?;
+
+    let mut problems: Vec<Problem> = check_textures(&images, budget.max_texture_size);
+    problems.extend(check_meshes(&meshes, dir, budget.max_triangles));
+    problems.extend(check_material_scripts(&scripts, &images));
+    problems.sort_by(|a, b| a.path.cmp(&b.path));
+
+    for problem in problems.iter() {
+        let _model = model_span(dir, &problem.path).entered();
+        print_warning(
+            format!("{:?} {}", problem.kind, problem),
+            Some(&problem.path),
+        );
+    }
+    if problems.is_empty() {
+        print_message(style("Models are web ready!").green());
+    } else {
+        print_message(style(format!("{} problem(s) found", problems.len())).red());
+    }
+
+    Ok(problems.len())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::image_processing::scan_dir_for_images::TEXTURE_IMAGE_TYPES;
+
+    #[test]
+    fn it_counts_the_problems() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("check").join("models");
+
+        assert_eq!(
+            process(&dir.join("cube"), &Budget::default(), &TEXTURE_IMAGE_TYPES)?,
+            0
+        );
+        // wall.jpg is a misplaced, non power of two JPEG, odd.png isn't a power of two,
+        // and broken.dae and broken.material refer to missing textures
+        assert_eq!(process(&dir, &Budget::default(), &TEXTURE_IMAGE_TYPES)?, 6);
+        // Unless JPEGs aren't textures in webify.toml, then the material script using
+        // wall.jpg is what's wrong
+        assert_eq!(process(&dir, &Budget::default(), &["png"])?, 4);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,19 @@
 //! Set of functions related to the command-line interface for webify_models
 
-mod create_progress_bar;
+mod parse_args_for_command;
+mod parse_args_for_flag;
+mod parse_args_for_option;
 mod parse_args_for_path;
+mod parse_args_for_positionals;
+mod parse_args_for_sources;
+mod parse_args_for_verbosity;
 
-pub use self::create_progress_bar::create_progress_bar;
+pub use self::parse_args_for_command::{parse_args_for_action, parse_args_for_command, Command};
+pub use self::parse_args_for_flag::parse_args_for_flag;
+pub use self::parse_args_for_option::{
+    parse_args_for_option, parse_args_for_options, VALUE_OPTIONS,
+};
 pub use self::parse_args_for_path::parse_args_for_path;
+pub use self::parse_args_for_positionals::parse_args_for_positionals;
+pub use self::parse_args_for_sources::parse_args_for_sources;
+pub use self::parse_args_for_verbosity::parse_args_for_verbosity;
@@ -0,0 +1,150 @@
+//! Work out which subcommand to run from the first argument, defaulting to processing
+
+use crate::cli::parse_args_for_flag;
+
+/// Subcommands of webify_models
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    /// Webify the models in place (the default when no subcommand is given)
+    Process,
+    /// Report mesh statistics per model
+    Stats,
+    /// Check meshes for problems that crash the web client, and optionally repair them
+    Validate,
+    /// Report what keeps the models from being web ready, without changing anything
+    Check,
+    /// List the references between model files that lead nowhere
+    References,
+    /// List the assets nothing refers to, and delete them with `--apply`
+    Prune,
+    /// Convert the material scripts and SDF materials alone, without touching textures
+    /// or meshes
+    Materials,
+    /// Copy models from the local model paths into the assets directory, then webify it
+    Import,
+    /// Write the `index.json` listing the models in the assets directory
+    Index,
+    /// Render a thumbnail of every model without gzserver
+    Thumbnails,
+    /// Webify the models into an output directory, then again whenever one changes
+    Watch,
+    /// Write gzip and brotli versions of the text assets and report the ratios
+    Compress,
+    /// Serve the assets directory over HTTP for previewing it with the web client
+    Serve,
+}
+
+impl Command {
+    /// Look up a subcommand by the name typed on the command line
+    pub fn from_name(name: &str) -> Option<Command> {
+        match name {
+            "process" => Some(Command::Process),
+            "stats" => Some(Command::Stats),
+            "validate" => Some(Command::Validate),
+            "check" => Some(Command::Check),
+            "references" => Some(Command::References),
+            "prune" => Some(Command::Prune),
+            "materials" => Some(Command::Materials),
+            "import" => Some(Command::Import),
+            "index" => Some(Command::Index),
+            "thumbnails" => Some(Command::Thumbnails),
+            "watch" => Some(Command::Watch),
+            "serve" => Some(Command::Serve),
+            "compress" => Some(Command::Compress),
+            _ => None,
+        }
+    }
+
+    /// Whether the subcommand only reads the models with the flags in `args`, so an
+    /// archive given to it doesn't need to be extracted anywhere but a temporary directory
+    pub fn is_read_only(&self, args: &[String]) -> bool {
+        match self {
+            Command::Stats | Command::Check | Command::References | Command::Serve => true,
+            Command::Validate => !parse_args_for_flag(args, "--repair"),
+            Command::Prune => !parse_args_for_flag(args, "--apply"),
+            _ => false,
+        }
+    }
+
+    /// Words that can follow the subcommand to pick a variant of it, like
+    /// `thumbnails postprocess`
+    pub fn actions(&self) -> &'static [&'static str] {
+        match self {
+            Command::Thumbnails => &["postprocess"],
+            _ => &[],
+        }
+    }
+}
+
+/// Return the subcommand named by the first argument, or `Process` when it isn't one
+pub fn parse_args_for_command(args: &[String]) -> Command {
+    args.get(1)
+        .and_then(|arg| Command::from_name(arg))
+        .unwrap_or(Command::Process)
+}
+
+/// Return the action following the subcommand, if it has one
+pub fn parse_args_for_action(args: &[String]) -> Option<&str> {
+    let command = args.get(1).and_then(|arg| Command::from_name(arg))?;
+    let action = args.get(2)?;
+
+    command
+        .actions()
+        .iter()
+        .find(|a| **a == action.as_str())
+        .copied()
+}
+
+#[cfg(test)]
+mod parse_args_for_command_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_subcommand() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("stats"),
+            String::from("/"),
+        ];
+
+        assert_eq!(parse_args_for_command(&args), Command::Stats);
+    }
+
+    #[test]
+    fn it_finds_the_action() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("thumbnails"),
+            String::from("postprocess"),
+            String::from("/"),
+        ];
+
+        assert_eq!(parse_args_for_command(&args), Command::Thumbnails);
+        assert_eq!(parse_args_for_action(&args), Some("postprocess"));
+        assert_eq!(parse_args_for_action(&args[..2]), None);
+    }
+
+    #[test]
+    fn it_tells_read_only_commands() {
+        let args = |args: &[&str]| -> Vec<String> {
+            std::iter::once("webify_models")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect()
+        };
+
+        assert!(Command::Check.is_read_only(&args(&["check", "models.zip"])));
+        assert!(Command::Validate.is_read_only(&args(&["validate", "models.zip"])));
+        assert!(!Command::Validate.is_read_only(&args(&["validate", "models.zip", "--repair"])));
+        assert!(Command::Prune.is_read_only(&args(&["prune", "models.zip"])));
+        assert!(!Command::Prune.is_read_only(&args(&["prune", "models.zip", "--apply"])));
+        assert!(!Command::Process.is_read_only(&args(&["models.zip"])));
+    }
+
+    #[test]
+    fn it_defaults_to_process() {
+        let args: Vec<String> = vec![String::from("foo/bar"), String::from("/")];
+
+        assert_eq!(parse_args_for_command(&args), Command::Process);
+    }
+}
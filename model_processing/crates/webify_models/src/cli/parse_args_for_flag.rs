@@ -0,0 +1,29 @@
+//! Check whether an optional `--flag` was passed on the command line
+
+/// Return true when the specified flag is present anywhere after the program name
+pub fn parse_args_for_flag(args: &[String], flag: &str) -> bool {
+    args.iter().skip(1).any(|arg| arg == flag)
+}
+
+#[cfg(test)]
+mod parse_args_for_flag_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_flag() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("/"),
+            String::from("--normalize"),
+        ];
+
+        assert!(parse_args_for_flag(&args, "--normalize"));
+    }
+
+    #[test]
+    fn it_ignores_missing_flags() {
+        let args: Vec<String> = vec![String::from("foo/bar"), String::from("/")];
+
+        assert!(!parse_args_for_flag(&args, "--normalize"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Read the value of an optional `--option value` (or `--option=value`) argument
+
+/// Options that take a value, so their value isn't mistaken for a path
+pub const VALUE_OPTIONS: [&str; 13] = [
+    "--json",
+    "--output",
+    "--cache",
+    "--host",
+    "--port",
+    "--min-size",
+    "--report",
+    "--max-texture-size",
+    "--max-triangles",
+    "--include",
+    "--exclude",
+    "--progress",
+    "--log-file",
+];
+
+/// Return the value given to the specified option, if any
+pub fn parse_args_for_option<'a>(args: &'a [String], option: &str) -> Option<&'a str> {
+    parse_args_for_options(args, option).into_iter().next()
+}
+
+/// Return every value given to an option that can be repeated, like `--exclude`
+pub fn parse_args_for_options<'a>(args: &'a [String], option: &str) -> Vec<&'a str> {
+    let mut values = vec![];
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == option {
+            values.extend(iter.next().map(String::as_str));
+        } else if let Some(value) = arg
+            .strip_prefix(option)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            values.push(value);
+        }
+    }
+
+    values
+}
+
+#[cfg(test)]
+mod parse_args_for_option_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_space_separated_values() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("--json"),
+            String::from("stats.json"),
+        ];
+
+        assert_eq!(parse_args_for_option(&args, "--json"), Some("stats.json"));
+    }
+
+    #[test]
+    fn it_reads_equals_separated_values() {
+        let args: Vec<String> = vec![String::from("foo/bar"), String::from("--json=stats.json")];
+
+        assert_eq!(parse_args_for_option(&args, "--json"), Some("stats.json"));
+    }
+
+    #[test]
+    fn it_reads_repeated_values() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("--exclude"),
+            String::from("*.psd"),
+            String::from("--exclude=build"),
+        ];
+
+        assert_eq!(
+            parse_args_for_options(&args, "--exclude"),
+            vec!["*.psd", "build"]
+        );
+        assert_eq!(parse_args_for_option(&args, "--exclude"), Some("*.psd"));
+    }
+
+    #[test]
+    fn it_ignores_missing_options() {
+        let args: Vec<String> = vec![String::from("foo/bar"), String::from("--jsonish")];
+
+        assert_eq!(parse_args_for_option(&args, "--json"), None);
+    }
+}
@@ -1,5 +1,5 @@
 //! Extract the path from the arguments provided, or return errors when there is none
-//! or if a file is provided
+//! or if a file other than a zip or tar.gz archive is provided
 
 use std::{
     io::{Error, ErrorKind},
@@ -7,19 +7,26 @@ use std::{
     result::Result,
 };
 
-pub fn parse_args_for_path<'a>(args: &'a Vec<String>) -> Result<&'a Path, Error> {
-    if args.len() <= 1 {
-        return Err(Error::new(
-            ErrorKind::Other,
-            "Path not provided, no work to do.",
-        ));
-    }
+use crate::cli::parse_args_for_positionals;
+use webify_models::archive::ArchiveKind;
 
-    let path = Path::new(&args[1]);
-    if !path.is_dir() {
+pub fn parse_args_for_path(args: &[String]) -> Result<&Path, Error> {
+    // Flags like --normalize can come before or after the path
+    let path = match parse_args_for_positionals(args).first().copied() {
+        Some(arg) => Path::new(arg),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Path not provided, no work to do.",
+            ))
+        }
+    };
+
+    let is_archive = path.is_file() && ArchiveKind::from_path(path).is_some();
+    if !path.is_dir() && !is_archive {
         return Err(Error::new(
-            ErrorKind::Other,
-            "Path provided is a file, please provide a directory.",
+            ErrorKind::InvalidInput,
+            "Path provided is a file, please provide a directory or a zip/tar.gz archive.",
         ));
     }
 
@@ -32,19 +39,45 @@ mod parse_args_for_path_tests {
 
     #[test]
     fn it_returns_the_correct_arg() {
-        let mut args: Vec<String> = Vec::new();
-        args.push(String::from("foo/bar")); // Test base path
-        args.push(String::from("/")); // Test provided arg path
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("/"),       // Test provided arg path
+        ];
 
         // Return the first argument as a path
         let arg = parse_args_for_path(&args).unwrap();
         assert_eq!(arg, Path::new("/"));
     }
 
+    #[test]
+    fn it_skips_flags() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("--normalize"),
+            String::from("/"),
+        ];
+
+        let arg = parse_args_for_path(&args).unwrap();
+        assert_eq!(arg, Path::new("/"));
+    }
+
+    #[test]
+    fn it_skips_the_subcommand() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("stats"),
+            String::from("/"),
+        ];
+
+        let arg = parse_args_for_path(&args).unwrap();
+        assert_eq!(arg, Path::new("/"));
+    }
+
     #[test]
     fn it_errors_on_no_path() {
-        let mut args: Vec<String> = Vec::new();
-        args.push(String::from("foo/bar")); // Test base path
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+        ];
 
         let arg = parse_args_for_path(&args);
         assert!(arg.is_err());
@@ -52,11 +85,23 @@ mod parse_args_for_path_tests {
 
     #[test]
     fn it_errors_when_path_is_file() {
-        let mut args: Vec<String> = Vec::new();
-        args.push(String::from("foo/bar")); // Test base path
-        args.push(String::from("tests/README.md"));
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("tests/README.md"),
+        ];
 
         let arg = parse_args_for_path(&args);
         assert!(arg.is_err());
     }
+
+    #[test]
+    fn it_accepts_archives() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("tests/archive/cube.zip"),
+        ];
+
+        let arg = parse_args_for_path(&args).unwrap();
+        assert_eq!(arg, Path::new("tests/archive/cube.zip"));
+    }
 }
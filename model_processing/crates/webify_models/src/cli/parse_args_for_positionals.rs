@@ -0,0 +1,57 @@
+//! Extract the positional arguments, skipping the subcommand, flags and option values
+
+use crate::cli::{parse_args_for_action, Command, VALUE_OPTIONS};
+
+/// Return the arguments that are neither the subcommand and its action, a `--flag` or
+/// `-f`lag, nor the value of an option
+pub fn parse_args_for_positionals(args: &[String]) -> Vec<&str> {
+    let skip = match args.get(1).and_then(|arg| Command::from_name(arg)) {
+        Some(_) if parse_args_for_action(args).is_some() => 3,
+        Some(_) => 2,
+        None => 1,
+    };
+
+    let mut positionals = Vec::new();
+    let mut iter = args.iter().skip(skip);
+    while let Some(arg) = iter.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            positionals.push(arg.as_str());
+        }
+    }
+
+    positionals
+}
+
+#[cfg(test)]
+mod parse_args_for_positionals_tests {
+    use super::*;
+
+    #[test]
+    fn it_skips_commands_flags_and_options() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("stats"),
+            String::from("--json"),
+            String::from("stats.json"),
+            String::from("--normalize"),
+            String::from("-vv"),
+            String::from("/"),
+        ];
+
+        assert_eq!(parse_args_for_positionals(&args), vec!["/"]);
+    }
+
+    #[test]
+    fn it_skips_the_action() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("thumbnails"),
+            String::from("postprocess"),
+            String::from("/"),
+        ];
+
+        assert_eq!(parse_args_for_positionals(&args), vec!["/"]);
+    }
+}
@@ -0,0 +1,48 @@
+//! Extract the directories to import models from
+
+use std::{env, path::PathBuf};
+
+use crate::cli::parse_args_for_positionals;
+
+/// Return the positional arguments after the assets directory, or the non-empty entries
+/// of `GAZEBO_MODEL_PATH` when there are none
+pub fn parse_args_for_sources(args: &[String]) -> Vec<PathBuf> {
+    let sources: Vec<PathBuf> = parse_args_for_positionals(args)
+        .iter()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect();
+    if !sources.is_empty() {
+        return sources;
+    }
+
+    env::var_os("GAZEBO_MODEL_PATH")
+        .map(|paths| {
+            env::split_paths(&paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod parse_args_for_sources_tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_sources_after_the_assets_dir() {
+        let args: Vec<String> = vec![
+            String::from("foo/bar"), // Test base path
+            String::from("import"),
+            String::from("assets"),
+            String::from("models"),
+            String::from("--normalize"),
+            String::from("more_models"),
+        ];
+
+        assert_eq!(
+            parse_args_for_sources(&args),
+            vec![PathBuf::from("models"), PathBuf::from("more_models")]
+        );
+    }
+}
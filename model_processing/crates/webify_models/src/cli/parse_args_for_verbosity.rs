@@ -0,0 +1,65 @@
+//! Work out how much to log from `-q`, `-v` and `-vv`
+
+use tracing_subscriber::filter::LevelFilter;
+
+/// Return the level to log from: errors with `-q`, warnings by default, then info with
+/// `-v` and debug with `-vv`. Every `v` counts, so `-v -v` is `-vv`, and `-vvv` logs
+/// everything.
+pub fn parse_args_for_verbosity(args: &[String]) -> LevelFilter {
+    let mut verbosity = 0;
+    for arg in args.iter().skip(1) {
+        if arg == "-q" || arg == "--quiet" {
+            return LevelFilter::ERROR;
+        } else if arg == "--verbose" {
+            verbosity += 1;
+        } else if let Some(vs) = arg.strip_prefix('-') {
+            if !vs.is_empty() && vs.chars().all(|c| c == 'v') {
+                verbosity += vs.len();
+            }
+        }
+    }
+
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+#[cfg(test)]
+mod parse_args_for_verbosity_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        std::iter::once("webify_models")
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn it_counts_the_vs() {
+        assert_eq!(parse_args_for_verbosity(&args(&["/"])), LevelFilter::WARN);
+        assert_eq!(
+            parse_args_for_verbosity(&args(&["/", "-v"])),
+            LevelFilter::INFO
+        );
+        assert_eq!(
+            parse_args_for_verbosity(&args(&["-vv", "/"])),
+            LevelFilter::DEBUG
+        );
+        assert_eq!(
+            parse_args_for_verbosity(&args(&["-v", "/", "--verbose", "-v"])),
+            LevelFilter::TRACE
+        );
+    }
+
+    #[test]
+    fn it_quiets_down() {
+        assert_eq!(
+            parse_args_for_verbosity(&args(&["-vv", "-q", "/"])),
+            LevelFilter::ERROR
+        );
+    }
+}
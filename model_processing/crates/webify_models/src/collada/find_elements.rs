@@ -0,0 +1,94 @@
+//! Depth-first lookups of elements by name anywhere below a root element
+
+use xmltree::{Element, XMLNode};
+
+/// Collect every descendant of `root` (including itself) with the given name, in document order
+pub fn find_elements<'a>(root: &'a Element, name: &str) -> Vec<&'a Element> {
+    let mut found = Vec::new();
+    collect(root, name, &mut found);
+
+    found
+}
+
+/// Call `visit` on every descendant of `root` (including itself) with the given name
+pub fn find_elements_mut(root: &mut Element, name: &str, visit: &mut dyn FnMut(&mut Element)) {
+    if root.name == name {
+        visit(root);
+    }
+    for child in root.children.iter_mut() {
+        if let XMLNode::Element(element) = child {
+            find_elements_mut(element, name, visit);
+        }
+    }
+}
+
+/// Iterate over the direct children of `element` with the given name
+pub fn child_elements<'a>(
+    element: &'a Element,
+    name: &'a str,
+) -> impl Iterator<Item = &'a Element> {
+    element.children.iter().filter_map(move |c| match c {
+        XMLNode::Element(e) if e.name == name => Some(e),
+        _ => None,
+    })
+}
+
+fn collect<'a>(element: &'a Element, name: &str, found: &mut Vec<&'a Element>) {
+    if element.name == name {
+        found.push(element);
+    }
+    for child in element.children.iter() {
+        if let XMLNode::Element(e) = child {
+            collect(e, name, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod find_elements_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_nested_elements_in_order() {
+        let root =
+            Element::parse(r#"<a><b id="1"><b id="2"/></b><c><b id="3"/></c></a>"#.as_bytes())
+                .unwrap();
+
+        let ids: Vec<&str> = find_elements(&root, "b")
+            .iter()
+            .map(|b| b.attributes["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+    }
+}
+
+#[cfg(test)]
+mod child_elements_tests {
+    use super::*;
+
+    #[test]
+    fn it_only_returns_direct_children() {
+        let root = Element::parse(r#"<a><b/><c><b/></c><b/></a>"#.as_bytes()).unwrap();
+
+        assert_eq!(child_elements(&root, "b").count(), 2);
+    }
+}
+
+#[cfg(test)]
+mod find_elements_mut_tests {
+    use super::*;
+
+    #[test]
+    fn it_visits_every_match() {
+        let mut root = Element::parse(r#"<a><b/><c><b/></c></a>"#.as_bytes()).unwrap();
+
+        find_elements_mut(&mut root, "b", &mut |b| {
+            b.attributes.insert("seen".to_string(), "yes".to_string());
+        });
+        assert!(find_elements(&root, "b").iter().all(|b| b
+            .attributes
+            .get("seen")
+            .map(String::as_str)
+            == Some("yes")));
+    }
+}
@@ -0,0 +1,58 @@
+//! Conversions between whitespace separated number lists and vectors of floats
+
+use std::io::{Error, ErrorKind};
+
+/// Parse the text content of a `<float_array>`, `<matrix>`, `<translate>`... element
+pub fn parse_floats(text: &str) -> Result<Vec<f64>, Error> {
+    text.split_whitespace()
+        .map(|value| {
+            value.parse::<f64>().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Expected a number, found {:?}", value),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Format floats back into a whitespace separated list
+pub fn format_floats(values: &[f64]) -> String {
+    values
+        .iter()
+        // Adding zero folds -0 into 0, which sign flips from axis conversions produce a lot of
+        .map(|v| (v + 0.0).to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod parse_floats_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_whitespace_separated_floats() {
+        let result = parse_floats(" 1 -2.5\n 3e2\t0 ").unwrap();
+        assert_eq!(result, vec![1.0, -2.5, 300.0, 0.0]);
+    }
+
+    #[test]
+    fn it_errors_on_garbage() {
+        assert!(parse_floats("1 two 3").is_err());
+    }
+}
+
+#[cfg(test)]
+mod format_floats_tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_floats() {
+        assert_eq!(format_floats(&[1.0, -0.5, 0.0254]), "1 -0.5 0.0254");
+    }
+
+    #[test]
+    fn it_drops_negative_zero() {
+        assert_eq!(format_floats(&[-0.0, 2.0]), "0 2");
+    }
+}
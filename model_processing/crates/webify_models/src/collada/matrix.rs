@@ -0,0 +1,225 @@
+//! Row-major 4x4 matrices for COLLADA node transforms
+
+use std::io::{Error, ErrorKind};
+
+use xmltree::{Element, XMLNode};
+
+use crate::collada::parse_floats;
+
+/// A row-major 4x4 transform, in the same order COLLADA writes `<matrix>`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix(pub [f64; 16]);
+
+impl Matrix {
+    pub fn identity() -> Matrix {
+        Matrix([
+            1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ])
+    }
+
+    pub fn translation(x: f64, y: f64, z: f64) -> Matrix {
+        let mut m = Matrix::identity();
+        m.0[3] = x;
+        m.0[7] = y;
+        m.0[11] = z;
+        m
+    }
+
+    pub fn scale(x: f64, y: f64, z: f64) -> Matrix {
+        let mut m = Matrix::identity();
+        m.0[0] = x;
+        m.0[5] = y;
+        m.0[10] = z;
+        m
+    }
+
+    /// Rotation of `degrees` around the (not necessarily unit) axis
+    pub fn rotation(axis: [f64; 3], degrees: f64) -> Matrix {
+        let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        if length == 0.0 {
+            return Matrix::identity();
+        }
+        let [x, y, z] = [axis[0] / length, axis[1] / length, axis[2] / length];
+        let (s, c) = degrees.to_radians().sin_cos();
+        let t = 1.0 - c;
+
+        Matrix([
+            t * x * x + c,
+            t * x * y - s * z,
+            t * x * z + s * y,
+            0.0,
+            t * x * y + s * z,
+            t * y * y + c,
+            t * y * z - s * x,
+            0.0,
+            t * x * z - s * y,
+            t * y * z + s * x,
+            t * z * z + c,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ])
+    }
+
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        let mut result = [0.0; 16];
+        for row in 0..4 {
+            for col in 0..4 {
+                result[row * 4 + col] = (0..4)
+                    .map(|k| self.0[row * 4 + k] * other.0[k * 4 + col])
+                    .sum();
+            }
+        }
+
+        Matrix(result)
+    }
+
+    pub fn transform_point(&self, p: [f64; 3]) -> [f64; 3] {
+        let m = &self.0;
+        [
+            m[0] * p[0] + m[1] * p[1] + m[2] * p[2] + m[3],
+            m[4] * p[0] + m[5] * p[1] + m[6] * p[2] + m[7],
+            m[8] * p[0] + m[9] * p[1] + m[10] * p[2] + m[11],
+        ]
+    }
+
+    /// Compose the `<translate>`, `<rotate>`, `<scale>`, `<matrix>` and `<lookat>`
+    /// children of a `<node>`, in document order
+    pub fn from_node(node: &Element) -> Result<Matrix, Error> {
+        let mut result = Matrix::identity();
+        for child in node.children.iter() {
+            let transform = match child {
+                XMLNode::Element(e) => e,
+                _ => continue,
+            };
+            let v = match transform.name.as_str() {
+                "translate" | "rotate" | "scale" | "matrix" | "lookat" => {
+                    parse_floats(&transform.get_text().unwrap_or_default())?
+                }
+                _ => continue,
+            };
+            let expected = match transform.name.as_str() {
+                "translate" | "scale" => 3,
+                "rotate" => 4,
+                "lookat" => 9,
+                _ => 16,
+            };
+            if v.len() != expected {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "<{}> expects {} values, found {}",
+                        transform.name,
+                        expected,
+                        v.len()
+                    ),
+                ));
+            }
+
+            let local = match transform.name.as_str() {
+                "translate" => Matrix::translation(v[0], v[1], v[2]),
+                "scale" => Matrix::scale(v[0], v[1], v[2]),
+                "rotate" => Matrix::rotation([v[0], v[1], v[2]], v[3]),
+                "lookat" => look_at(&v),
+                _ => {
+                    let mut m = [0.0; 16];
+                    m.copy_from_slice(&v);
+                    Matrix(m)
+                }
+            };
+            result = result.multiply(&local);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Build the camera-to-parent transform described by `<lookat>` (eye, interest, up)
+fn look_at(v: &[f64]) -> Matrix {
+    let eye = [v[0], v[1], v[2]];
+    let forward = normalize([v[3] - v[0], v[4] - v[1], v[5] - v[2]]);
+    let side = normalize(cross(forward, [v[6], v[7], v[8]]));
+    let up = cross(side, forward);
+
+    Matrix([
+        side[0],
+        up[0],
+        -forward[0],
+        eye[0],
+        side[1],
+        up[1],
+        -forward[1],
+        eye[1],
+        side[2],
+        up[2],
+        -forward[2],
+        eye[2],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+    ])
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length == 0.0 {
+        v
+    } else {
+        [v[0] / length, v[1] / length, v[2] / length]
+    }
+}
+
+#[cfg(test)]
+mod matrix_tests {
+    use super::*;
+
+    fn assert_close(a: [f64; 3], b: [f64; 3]) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn it_rotates_around_z() {
+        let m = Matrix::rotation([0.0, 0.0, 1.0], 90.0);
+        assert_close(m.transform_point([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn it_applies_transforms_in_document_order() -> Result<(), Error> {
+        let node = Element::parse(
+            r#"<node><translate>1 0 0</translate><scale>2 2 2</scale></node>"#.as_bytes(),
+        )
+        .unwrap();
+        let m = Matrix::from_node(&node)?;
+
+        // Scaled first, then translated
+        assert_close(m.transform_point([1.0, 1.0, 1.0]), [3.0, 2.0, 2.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_reads_row_major_matrices() -> Result<(), Error> {
+        let node = Element::parse(
+            r#"<node><matrix>1 0 0 5 0 1 0 6 0 0 1 7 0 0 0 1</matrix></node>"#.as_bytes(),
+        )
+        .unwrap();
+        let m = Matrix::from_node(&node)?;
+
+        assert_close(m.transform_point([0.0, 0.0, 0.0]), [5.0, 6.0, 7.0]);
+
+        Ok(())
+    }
+}
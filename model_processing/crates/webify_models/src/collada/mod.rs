@@ -0,0 +1,24 @@
+//! Shared helpers to read, walk and write COLLADA (DAE) meshes as an XML tree
+
+mod find_elements;
+mod floats;
+mod matrix;
+mod read_dae;
+mod read_geometries;
+mod read_images;
+mod read_materials;
+mod read_scene;
+mod write_dae;
+
+pub use self::find_elements::{child_elements, find_elements, find_elements_mut};
+pub use self::floats::{format_floats, parse_floats};
+pub use self::matrix::Matrix;
+pub use self::read_dae::read_dae;
+pub use self::read_geometries::{
+    read_geometries, read_geometries_unchecked, read_inputs, read_polygons, triangulate,
+    Geometry, PRIMITIVE_ELEMENTS,
+};
+pub use self::read_images::read_images;
+pub use self::read_materials::read_materials;
+pub use self::read_scene::read_scene;
+pub use self::write_dae::write_dae;
@@ -0,0 +1,49 @@
+//! Parse a DAE mesh from disk into an XML element tree
+
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::Element;
+
+/// Read and parse the specified DAE mesh, returning the root `<COLLADA>` element
+pub fn read_dae(mesh: &Path) -> Result<Element, Error> {
+    let file = BufReader::new(File::open(mesh)?);
+
+    Element::parse(file).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {:?} as COLLADA: {}", mesh, e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod read_dae_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_the_mesh() -> Result<(), Error> {
+        let mesh = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+        let root = read_dae(&mesh)?;
+
+        assert_eq!(root.name, "COLLADA");
+        assert!(root.get_child("library_geometries").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_errors_on_invalid_xml() {
+        let mesh = Path::new("tests").join("README.md");
+
+        let result = read_dae(&mesh);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,409 @@
+//! Extract the triangles of every `<geometry>` in a COLLADA document
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+};
+
+use xmltree::Element;
+
+use crate::collada::{child_elements, find_elements, parse_floats};
+
+/// Primitive elements that describe filled faces; `<lines>` and `<linestrips>` are skipped
+pub const PRIMITIVE_ELEMENTS: [&str; 5] = ["triangles", "polylist", "polygons", "tristrips", "trifans"];
+
+/// A `<geometry>` element with its vertex positions and primitive groups
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    /// Value of the `id` attribute, what `<instance_geometry url>` points at
+    pub id: String,
+    pub positions: Vec<[f64; 3]>,
+    pub primitives: Vec<Primitive>,
+}
+
+/// A group of faces sharing one material, which the web client draws in one call
+#[derive(Debug, Clone, PartialEq)]
+pub struct Primitive {
+    /// Name of the element it came from, e.g. `triangles` or `polylist`
+    pub element: String,
+    /// Material symbol, bound to an actual material by `<instance_material>`
+    pub material: Option<String>,
+    pub normals: Vec<[f64; 3]>,
+    pub texcoords: Vec<[f64; 2]>,
+    /// Faces triangulated as fans
+    pub triangles: Vec<[Corner; 3]>,
+}
+
+/// Indices of a triangle corner into the position, normal and texcoord arrays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corner {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub texcoord: Option<usize>,
+}
+
+/// A `<source>` flattened to its values and accessor stride
+struct Source {
+    values: Vec<f64>,
+    stride: usize,
+}
+
+impl Source {
+    fn vec3s(&self) -> Vec<[f64; 3]> {
+        self.values
+            .chunks(self.stride.max(1))
+            .filter(|c| c.len() >= 3)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect()
+    }
+
+    fn vec2s(&self) -> Vec<[f64; 2]> {
+        self.values
+            .chunks(self.stride.max(1))
+            .filter(|c| c.len() >= 2)
+            .map(|c| [c[0], c[1]])
+            .collect()
+    }
+}
+
+/// Read every mesh geometry in the document. Index lists are checked against
+/// the arrays they point into, so consumers can index without bounds checks.
+pub fn read_geometries(root: &Element) -> Result<Vec<Geometry>, Error> {
+    read_all_geometries(root, true)
+}
+
+/// Read every mesh geometry in the document, keeping out of range indices as they are
+/// so that they can be reported
+pub fn read_geometries_unchecked(root: &Element) -> Result<Vec<Geometry>, Error> {
+    read_all_geometries(root, false)
+}
+
+fn read_all_geometries(root: &Element, check_ranges: bool) -> Result<Vec<Geometry>, Error> {
+    let mut geometries = Vec::new();
+    for geometry in find_elements(root, "geometry") {
+        let id = geometry.attributes.get("id").cloned().unwrap_or_default();
+        if let Some(mesh) = geometry.get_child("mesh") {
+            geometries.push(read_mesh(id, mesh, check_ranges)?);
+        }
+    }
+
+    Ok(geometries)
+}
+
+fn read_mesh(id: String, mesh: &Element, check_ranges: bool) -> Result<Geometry, Error> {
+    let invalid = |message: String| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("geometry {:?}: {}", id, message),
+        )
+    };
+
+    let mut sources = HashMap::new();
+    for source in child_elements(mesh, "source") {
+        if let Some(source_id) = source.attributes.get("id") {
+            sources.insert(source_id.clone(), read_source(source)?);
+        }
+    }
+
+    // <vertices> maps the VERTEX input to a POSITION source, and sometimes NORMAL/TEXCOORD
+    let vertices = mesh
+        .get_child("vertices")
+        .ok_or_else(|| invalid(String::from("missing <vertices>")))?;
+    let vertices_id = vertices.attributes.get("id").cloned().unwrap_or_default();
+    let vertex_inputs = read_inputs(vertices);
+    let source_for = |semantic: &str, inputs: &[Input]| -> Result<Option<&Source>, Error> {
+        match inputs.iter().find(|i| i.semantic == semantic) {
+            Some(input) => sources
+                .get(&input.source)
+                .map(Some)
+                .ok_or_else(|| invalid(format!("missing <source id={:?}>", input.source))),
+            None => Ok(None),
+        }
+    };
+    let positions = source_for("POSITION", &vertex_inputs)?
+        .ok_or_else(|| invalid(String::from("<vertices> has no POSITION input")))?
+        .vec3s();
+    let vertex_normals = source_for("NORMAL", &vertex_inputs)?.map(|s| s.vec3s());
+    let vertex_texcoords = source_for("TEXCOORD", &vertex_inputs)?.map(|s| s.vec2s());
+
+    let mut primitives = Vec::new();
+    for element in mesh.children.iter().filter_map(|c| c.as_element()) {
+        if !PRIMITIVE_ELEMENTS.contains(&element.name.as_str()) {
+            continue;
+        }
+        let location = format!("<{}> #{}", element.name, primitives.len());
+        let inputs = read_inputs(element);
+        let stride = inputs.iter().map(|i| i.offset + 1).max().unwrap_or(1);
+
+        let vertex_offset = inputs
+            .iter()
+            .find(|i| i.semantic == "VERTEX" && i.source == vertices_id)
+            .map(|i| i.offset)
+            .ok_or_else(|| invalid(format!("{} has no VERTEX input", location)))?;
+        let normal_input = inputs.iter().find(|i| i.semantic == "NORMAL");
+        let texcoord_input = inputs
+            .iter()
+            .filter(|i| i.semantic == "TEXCOORD")
+            .min_by_key(|i| i.set);
+
+        let normals = match normal_input {
+            Some(_) => source_for("NORMAL", &inputs)?.unwrap().vec3s(),
+            None => vertex_normals.clone().unwrap_or_default(),
+        };
+        let texcoords = match texcoord_input {
+            Some(input) => sources
+                .get(&input.source)
+                .ok_or_else(|| invalid(format!("missing <source id={:?}>", input.source)))?
+                .vec2s(),
+            None => vertex_texcoords.clone().unwrap_or_default(),
+        };
+
+        let mut triangles = Vec::new();
+        for polygon in read_polygons(element, stride)? {
+            let corners = polygon
+                .chunks_exact(stride)
+                .map(|index| {
+                    let position = index[vertex_offset];
+                    let corner = Corner {
+                        position,
+                        normal: match normal_input {
+                            Some(input) => Some(index[input.offset]),
+                            None => vertex_normals.as_ref().map(|_| position),
+                        },
+                        texcoord: match texcoord_input {
+                            Some(input) => Some(index[input.offset]),
+                            None => vertex_texcoords.as_ref().map(|_| position),
+                        },
+                    };
+                    if check_ranges
+                        && (corner.position >= positions.len()
+                        || corner.normal.is_some_and(|n| n >= normals.len())
+                        || corner.texcoord.is_some_and(|t| t >= texcoords.len()))
+                    {
+                        return Err(invalid(format!(
+                            "{} has an index out of range: {:?}",
+                            location, corner
+                        )));
+                    }
+                    Ok(corner)
+                })
+                .collect::<Result<Vec<Corner>, Error>>()?;
+            triangles.extend(triangulate(&element.name, &corners));
+        }
+
+        primitives.push(Primitive {
+            element: element.name.clone(),
+            material: element.attributes.get("material").cloned(),
+            normals,
+            texcoords,
+            triangles,
+        });
+    }
+
+    Ok(Geometry {
+        id,
+        positions,
+        primitives,
+    })
+}
+
+/// Split a primitive's `<p>` lists into one index list per polygon, strip or fan.
+/// Each list holds `stride` indices per corner, one for each input offset.
+pub fn read_polygons(element: &Element, stride: usize) -> Result<Vec<Vec<usize>>, Error> {
+    let mut lists = Vec::new();
+    for p in child_elements(element, "p") {
+        let indices = parse_floats(&p.get_text().unwrap_or_default())?
+            .into_iter()
+            // Negative indices are as out of range as indices past the end
+            .map(|i| if i < 0.0 { usize::MAX } else { i as usize })
+            .collect::<Vec<usize>>();
+        lists.push(indices);
+    }
+
+    match element.name.as_str() {
+        "triangles" => Ok(lists
+            .concat()
+            .chunks(stride * 3)
+            .filter(|c| c.len() == stride * 3)
+            .map(|c| c.to_vec())
+            .collect()),
+        "polylist" => {
+            let vcount = element
+                .get_child("vcount")
+                .map(|v| parse_floats(&v.get_text().unwrap_or_default()))
+                .transpose()?
+                .unwrap_or_default();
+            let indices = lists.concat();
+            let mut polygons = Vec::new();
+            let mut start = 0;
+            for count in vcount {
+                let end = start + count as usize * stride;
+                if end > indices.len() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "<polylist> <vcount> exceeds the length of <p>",
+                    ));
+                }
+                polygons.push(indices[start..end].to_vec());
+                start = end;
+            }
+            Ok(polygons)
+        }
+        _ => Ok(lists),
+    }
+}
+
+/// Turn a polygon, strip or fan into triangles
+pub fn triangulate<T: Copy>(element: &str, corners: &[T]) -> Vec<[T; 3]> {
+    if corners.len() < 3 {
+        return Vec::new();
+    }
+
+    (1..corners.len() - 1)
+        .map(|i| match element {
+            // Every other strip triangle is flipped to keep a consistent winding
+            "tristrips" if i % 2 == 0 => [corners[i], corners[i - 1], corners[i + 1]],
+            "tristrips" => [corners[i - 1], corners[i], corners[i + 1]],
+            _ => [corners[0], corners[i], corners[i + 1]],
+        })
+        .collect()
+}
+
+fn read_source(source: &Element) -> Result<Source, Error> {
+    let values = match source.get_child("float_array") {
+        Some(array) => parse_floats(&array.get_text().unwrap_or_default())?,
+        None => Vec::new(),
+    };
+    let stride = source
+        .get_child("technique_common")
+        .and_then(|t| t.get_child("accessor"))
+        .and_then(|a| a.attributes.get("stride"))
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(1);
+
+    Ok(Source { values, stride })
+}
+
+/// An `<input>` of `<vertices>` or of a primitive, with the `#` stripped from its source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    pub semantic: String,
+    pub source: String,
+    pub offset: usize,
+    pub set: usize,
+}
+
+pub fn read_inputs(element: &Element) -> Vec<Input> {
+    child_elements(element, "input")
+        .map(|input| {
+            let attribute = |name: &str| input.attributes.get(name).cloned().unwrap_or_default();
+            Input {
+                semantic: attribute("semantic"),
+                source: attribute("source").trim_start_matches('#').to_string(),
+                offset: attribute("offset").parse().unwrap_or(0),
+                set: attribute("set").parse().unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod read_geometries_tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::collada::read_dae;
+
+    #[test]
+    fn it_reads_the_cube() -> Result<(), Error> {
+        let mesh = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+        let geometries = read_geometries(&read_dae(&mesh)?)?;
+
+        assert_eq!(geometries.len(), 1);
+        assert_eq!(geometries[0].id, "Cube-mesh");
+        assert_eq!(geometries[0].positions.len(), 8);
+
+        let primitive = &geometries[0].primitives[0];
+        assert_eq!(primitive.material.as_deref(), Some("Material-material"));
+        assert_eq!(primitive.triangles.len(), 12);
+        assert_eq!(
+            primitive.triangles[0][1],
+            Corner {
+                position: 2,
+                normal: Some(0),
+                texcoord: Some(1),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_triangulates_polylists() -> Result<(), Error> {
+        let root = Element::parse(
+            r##"<COLLADA><library_geometries><geometry id="quad"><mesh>
+              <source id="p"><float_array>0 0 0 1 0 0 1 1 0 0 1 0</float_array>
+                <technique_common><accessor stride="3"/></technique_common></source>
+              <vertices id="v"><input semantic="POSITION" source="#p"/></vertices>
+              <polylist count="1"><input semantic="VERTEX" source="#v" offset="0"/>
+                <vcount>4</vcount><p>0 1 2 3</p></polylist>
+            </mesh></geometry></library_geometries></COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+        let geometries = read_geometries(&root)?;
+        let positions: Vec<Vec<usize>> = geometries[0].primitives[0]
+            .triangles
+            .iter()
+            .map(|t| t.iter().map(|c| c.position).collect())
+            .collect();
+
+        assert_eq!(positions, vec![vec![0, 1, 2], vec![0, 2, 3]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_keeps_out_of_range_indices_when_unchecked() -> Result<(), Error> {
+        let root = Element::parse(
+            r##"<COLLADA><library_geometries><geometry id="bad"><mesh>
+              <source id="p"><float_array>0 0 0 1 0 0 1 1 0</float_array>
+                <technique_common><accessor stride="3"/></technique_common></source>
+              <vertices id="v"><input semantic="POSITION" source="#p"/></vertices>
+              <triangles count="1"><input semantic="VERTEX" source="#v" offset="0"/>
+                <p>0 -1 7</p></triangles>
+            </mesh></geometry></library_geometries></COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+        let geometries = read_geometries_unchecked(&root)?;
+        let triangle = geometries[0].primitives[0].triangles[0];
+
+        assert_eq!(triangle[1].position, usize::MAX);
+        assert_eq!(triangle[2].position, 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_errors_on_out_of_range_indices() {
+        let root = Element::parse(
+            r##"<COLLADA><library_geometries><geometry id="bad"><mesh>
+              <source id="p"><float_array>0 0 0 1 0 0 1 1 0</float_array>
+                <technique_common><accessor stride="3"/></technique_common></source>
+              <vertices id="v"><input semantic="POSITION" source="#p"/></vertices>
+              <triangles count="1"><input semantic="VERTEX" source="#v" offset="0"/>
+                <p>0 1 7</p></triangles>
+            </mesh></geometry></library_geometries></COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let error = read_geometries(&root).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
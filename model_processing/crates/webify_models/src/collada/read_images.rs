@@ -0,0 +1,41 @@
+//! List the image files a COLLADA document references from `<library_images>`
+
+use xmltree::Element;
+
+use crate::collada::find_elements;
+
+/// Return the `<init_from>` of every `<image>`, as written in the document
+pub fn read_images(root: &Element) -> Vec<String> {
+    find_elements(root, "image")
+        .iter()
+        .filter_map(|image| image.get_child("init_from"))
+        .filter_map(|init_from| {
+            // COLLADA 1.5 nests the path in <ref>
+            match init_from.get_child("ref") {
+                Some(r) => r.get_text(),
+                None => init_from.get_text(),
+            }
+        })
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod read_images_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_both_collada_versions() {
+        let root = Element::parse(
+            r#"<COLLADA><library_images>
+              <image id="a"><init_from>a.png</init_from></image>
+              <image id="b"><init_from><ref>b.png</ref></init_from></image>
+            </library_images></COLLADA>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(read_images(&root), vec!["a.png", "b.png"]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Resolve the diffuse color or texture of every COLLADA material through its effect
+
+use std::collections::HashMap;
+
+use xmltree::Element;
+
+use crate::collada::{child_elements, find_elements, parse_floats};
+
+/// Shading techniques of `<profile_COMMON>` that have a `<diffuse>`
+const TECHNIQUES: [&str; 3] = ["phong", "blinn", "lambert"];
+
+/// Diffuse part of a material, what a preview needs to draw it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DaeMaterial {
+    pub diffuse: Option<[f64; 4]>,
+    /// `<init_from>` of the diffuse texture's image, as written in the document
+    pub texture: Option<String>,
+}
+
+/// Map every `<material>` id to its diffuse color or texture. The texture is followed
+/// from the sampler to the surface to the image, with the shortcuts exporters take
+/// (a texture naming the image directly, or a COLLADA 1.5 `<instance_image>`).
+pub fn read_materials(root: &Element) -> HashMap<String, DaeMaterial> {
+    let images: HashMap<&str, String> = find_elements(root, "image")
+        .into_iter()
+        .filter_map(|image| {
+            let id = image.attributes.get("id")?;
+            let init_from = image.get_child("init_from")?;
+            let path = match init_from.get_child("ref") {
+                Some(r) => r.get_text()?,
+                None => init_from.get_text()?,
+            };
+            Some((id.as_str(), path.trim().to_string()))
+        })
+        .collect();
+
+    let effects: HashMap<&str, DaeMaterial> = find_elements(root, "effect")
+        .into_iter()
+        .filter_map(|effect| {
+            let id = effect.attributes.get("id")?;
+            Some((id.as_str(), read_effect(effect, &images)))
+        })
+        .collect();
+
+    find_elements(root, "material")
+        .into_iter()
+        .filter_map(|material| {
+            let id = material.attributes.get("id")?;
+            let url = material
+                .get_child("instance_effect")?
+                .attributes
+                .get("url")?;
+            let effect = effects.get(url.trim_start_matches('#'))?;
+            Some((id.clone(), effect.clone()))
+        })
+        .collect()
+}
+
+fn read_effect(effect: &Element, images: &HashMap<&str, String>) -> DaeMaterial {
+    let diffuse = find_elements(effect, "technique")
+        .into_iter()
+        .flat_map(|technique| {
+            TECHNIQUES
+                .iter()
+                .filter_map(move |name| technique.get_child(*name))
+        })
+        .find_map(|shading| shading.get_child("diffuse"));
+    let diffuse = match diffuse {
+        Some(d) => d,
+        None => return DaeMaterial::default(),
+    };
+
+    let color = diffuse
+        .get_child("color")
+        .and_then(|color| color.get_text())
+        .and_then(|text| parse_floats(&text).ok())
+        .filter(|values| values.len() >= 3)
+        .map(|v| [v[0], v[1], v[2], v.get(3).copied().unwrap_or(1.0)]);
+    let texture = diffuse
+        .get_child("texture")
+        .and_then(|texture| texture.attributes.get("texture"))
+        .and_then(|sampler| resolve_sampler(effect, sampler, images));
+
+    DaeMaterial {
+        diffuse: color,
+        texture,
+    }
+}
+
+/// Follow a `<texture texture="...">` to the path of its image
+fn resolve_sampler(
+    effect: &Element,
+    sampler: &str,
+    images: &HashMap<&str, String>,
+) -> Option<String> {
+    if let Some(path) = images.get(sampler) {
+        return Some(path.clone());
+    }
+
+    let newparam = |sid: &str| {
+        find_elements(effect, "newparam")
+            .into_iter()
+            .find(|param| param.attributes.get("sid").is_some_and(|s| s == sid))
+    };
+    let sampler2d = newparam(sampler)?.get_child("sampler2D")?;
+    if let Some(instance) = sampler2d.get_child("instance_image") {
+        let url = instance.attributes.get("url")?;
+        return images.get(url.trim_start_matches('#')).cloned();
+    }
+
+    let surface_sid = sampler2d.get_child("source")?.get_text()?;
+    let surface = newparam(surface_sid.trim())?.get_child("surface")?;
+    let image_id = child_elements(surface, "init_from").next()?.get_text()?;
+
+    images.get(image_id.trim()).cloned()
+}
+
+#[cfg(test)]
+mod read_materials_tests {
+    use super::*;
+    use crate::collada::read_dae;
+    use std::path::Path;
+
+    #[test]
+    fn it_follows_samplers_to_images() -> std::result::Result<(), std::io::Error> {
+        let root = read_dae(
+            &Path::new("tests")
+                .join("collada")
+                .join("cube")
+                .join("meshes")
+                .join("cube.dae"),
+        )?;
+        let materials = read_materials(&root);
+
+        assert_eq!(
+            materials["Material-material"].texture.as_deref(),
+            Some("../materials/textures/cube_diffuse.png")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_reads_diffuse_colors() {
+        let root = Element::parse(
+            r##"<COLLADA>
+              <library_effects><effect id="red-fx"><profile_COMMON><technique sid="common">
+                <phong><diffuse><color>1 0 0 1</color></diffuse></phong>
+              </technique></profile_COMMON></effect></library_effects>
+              <library_materials><material id="red">
+                <instance_effect url="#red-fx"/>
+              </material></library_materials>
+            </COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_materials(&root)["red"],
+            DaeMaterial {
+                diffuse: Some([1.0, 0.0, 0.0, 1.0]),
+                texture: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Walk the visual scene of a COLLADA document to find where each geometry is placed
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+};
+
+use xmltree::Element;
+
+use crate::collada::{child_elements, find_elements, Matrix};
+
+/// Deepest `<node>`/`<instance_node>` nesting followed, to stop on reference cycles
+const MAX_DEPTH: usize = 64;
+
+/// One `<instance_geometry>` placed in the scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometryInstance {
+    /// Id of the instanced `<geometry>`, without the leading `#`
+    pub geometry: String,
+    /// Accumulated transform from the geometry to the scene root
+    pub transform: Matrix,
+    /// Material symbol to material id, from `<bind_material>`
+    pub materials: HashMap<String, String>,
+}
+
+/// List every geometry instance of the scene's visual scene (or the first visual scene
+/// when `<scene>` is missing). Documents with no visual scene at all get one instance
+/// of each geometry, untransformed, which is how most loaders treat them.
+pub fn read_scene(root: &Element) -> Result<Vec<GeometryInstance>, Error> {
+    let visual_scenes = find_elements(root, "visual_scene");
+    let scene_url = root
+        .get_child("scene")
+        .and_then(|s| s.get_child("instance_visual_scene"))
+        .and_then(|i| i.attributes.get("url"))
+        .map(|url| url.trim_start_matches('#').to_string());
+    let visual_scene = match scene_url {
+        Some(url) => visual_scenes
+            .iter()
+            .find(|v| v.attributes.get("id") == Some(&url))
+            .copied(),
+        None => visual_scenes.first().copied(),
+    };
+
+    let visual_scene = match visual_scene {
+        Some(v) => v,
+        None => {
+            return Ok(find_elements(root, "geometry")
+                .iter()
+                .filter_map(|g| g.attributes.get("id"))
+                .map(|id| GeometryInstance {
+                    geometry: id.clone(),
+                    transform: Matrix::identity(),
+                    materials: HashMap::new(),
+                })
+                .collect())
+        }
+    };
+
+    let library_nodes: HashMap<String, &Element> = find_elements(root, "node")
+        .into_iter()
+        .filter_map(|n| n.attributes.get("id").map(|id| (id.clone(), n)))
+        .collect();
+
+    let mut instances = Vec::new();
+    for node in child_elements(visual_scene, "node") {
+        walk_node(node, Matrix::identity(), &library_nodes, 0, &mut instances)?;
+    }
+
+    Ok(instances)
+}
+
+fn walk_node(
+    node: &Element,
+    parent: Matrix,
+    library_nodes: &HashMap<String, &Element>,
+    depth: usize,
+    instances: &mut Vec<GeometryInstance>,
+) -> Result<(), Error> {
+    if depth > MAX_DEPTH {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Node hierarchy is too deep, is there an <instance_node> cycle?",
+        ));
+    }
+    let transform = parent.multiply(&Matrix::from_node(node)?);
+
+    for instance in child_elements(node, "instance_geometry") {
+        let geometry = instance
+            .attributes
+            .get("url")
+            .map(|url| url.trim_start_matches('#').to_string())
+            .unwrap_or_default();
+        let materials = find_elements(instance, "instance_material")
+            .iter()
+            .filter_map(|m| {
+                let symbol = m.attributes.get("symbol")?;
+                let target = m.attributes.get("target")?;
+                Some((symbol.clone(), target.trim_start_matches('#').to_string()))
+            })
+            .collect();
+        instances.push(GeometryInstance {
+            geometry,
+            transform,
+            materials,
+        });
+    }
+
+    for child in child_elements(node, "node") {
+        walk_node(child, transform, library_nodes, depth + 1, instances)?;
+    }
+    for instance in child_elements(node, "instance_node") {
+        let url = instance
+            .attributes
+            .get("url")
+            .map(|url| url.trim_start_matches('#'))
+            .unwrap_or_default();
+        if let Some(target) = library_nodes.get(url) {
+            walk_node(target, transform, library_nodes, depth + 1, instances)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod read_scene_tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::collada::read_dae;
+
+    #[test]
+    fn it_reads_instances_with_transforms() -> Result<(), Error> {
+        let mesh = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+        let instances = read_scene(&read_dae(&mesh)?)?;
+
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].geometry, "Cube-mesh");
+        assert_eq!(instances[0].transform, Matrix::translation(0.0, 10.0, 0.0));
+        assert_eq!(
+            instances[0]
+                .materials
+                .get("Material-material")
+                .map(String::as_str),
+            Some("Material-material")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_follows_instance_nodes() -> Result<(), Error> {
+        let root = Element::parse(
+            r##"<COLLADA>
+              <library_nodes><node id="shared"><instance_geometry url="#g"/></node></library_nodes>
+              <library_visual_scenes><visual_scene id="s">
+                <node><translate>1 2 3</translate><instance_node url="#shared"/></node>
+              </visual_scene></library_visual_scenes>
+            </COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+        let instances = read_scene(&root)?;
+
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].transform, Matrix::translation(1.0, 2.0, 3.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_errors_on_cycles() {
+        let root = Element::parse(
+            r##"<COLLADA><library_visual_scenes><visual_scene id="s">
+                <node id="loop"><instance_node url="#loop"/></node>
+              </visual_scene></library_visual_scenes></COLLADA>"##
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert!(read_scene(&root).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Serialize an XML element tree back to a DAE mesh on disk
+
+use std::{
+    fs::File,
+    io::{BufWriter, Error},
+    path::Path,
+};
+
+use xmltree::{Element, EmitterConfig};
+
+/// Write the root `<COLLADA>` element to the specified path, replacing its contents
+pub fn write_dae(mesh: &Path, root: &Element) -> Result<(), Error> {
+    let file = BufWriter::new(File::create(mesh)?);
+    let config = EmitterConfig::new().perform_indent(true);
+
+    root.write_with_config(file, config)
+        .map_err(|e| Error::other(format!("Failed to write COLLADA to {:?}: {}", mesh, e)))
+}
+
+#[cfg(test)]
+mod write_dae_tests {
+    use std::fs;
+
+    use super::*;
+    use crate::collada::read_dae;
+
+    #[test]
+    fn it_round_trips_the_mesh() -> Result<(), Error> {
+        let source = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+        let destination_path = Path::new("tests")
+            .join("collada")
+            .join("test_run_it_round_trips_the_mesh");
+        fs::create_dir_all(&destination_path)?;
+
+        let root = read_dae(&source)?;
+        write_dae(&destination_path.join("cube.dae"), &root)?;
+        let written = read_dae(&destination_path.join("cube.dae"))?;
+        assert_eq!(root, written);
+
+        fs::remove_dir_all(destination_path)?;
+        Ok(())
+    }
+}
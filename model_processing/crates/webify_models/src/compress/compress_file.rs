@@ -0,0 +1,119 @@
+//! Write the `.gz` and `.br` siblings of a file
+
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+
+use crate::report::FileAction;
+
+/// Brotli's densest setting, which is slow, but it only runs once per asset
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+
+/// Sizes in bytes of a file and of its compressed versions
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CompressedSizes {
+    pub original: u64,
+    pub gzip: u64,
+    pub brotli: u64,
+}
+
+/// Write `<file>.gz` and `<file>.br` next to the file, unless they're already newer
+/// than it, and return the sizes of all three
+pub fn compress_file(path: &Path) -> std::result::Result<CompressedSizes, io::Error> {
+    let gzip_path = sibling(path, "gz");
+    let brotli_path = sibling(path, "br");
+
+    if !is_newer(&gzip_path, path)? {
+        let action = FileAction::start("gzip", path);
+        let mut encoder = GzEncoder::new(File::create(&gzip_path)?, Compression::best());
+        io::copy(&mut File::open(path)?, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        action.finish(&gzip_path);
+    }
+    if !is_newer(&brotli_path, path)? {
+        let action = FileAction::start("brotli", path);
+        let mut encoder = brotli::CompressorWriter::new(
+            File::create(&brotli_path)?,
+            4096,
+            BROTLI_QUALITY,
+            BROTLI_WINDOW,
+        );
+        io::copy(&mut File::open(path)?, &mut encoder)?;
+        // Only taking the file back writes the end of the stream
+        encoder.into_inner().flush()?;
+        action.finish(&brotli_path);
+    }
+
+    Ok(CompressedSizes {
+        original: fs::metadata(path)?.len(),
+        gzip: fs::metadata(&gzip_path)?.len(),
+        brotli: fs::metadata(&brotli_path)?.len(),
+    })
+}
+
+/// The path with an extra extension, e.g. `box.dae.gz`
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(".");
+    sibling.push(extension);
+    PathBuf::from(sibling)
+}
+
+/// Whether `path` exists and was modified after `than`
+fn is_newer(path: &Path, than: &Path) -> std::result::Result<bool, io::Error> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+
+    Ok(fs::metadata(path)?.modified()? >= fs::metadata(than)?.modified()?)
+}
+
+#[cfg(test)]
+mod compress_file_tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn it_writes_both_siblings() -> std::result::Result<(), io::Error> {
+        let test_dir = Path::new("tests")
+            .join("compress")
+            .join("test_run_it_writes_both_siblings");
+        fs::create_dir_all(&test_dir)?;
+        let dae = test_dir.join("cube.dae");
+        fs::copy(
+            Path::new("tests")
+                .join("index")
+                .join("cube")
+                .join("meshes")
+                .join("cube.dae"),
+            &dae,
+        )?;
+        let original = fs::read_to_string(&dae)?;
+
+        let sizes = compress_file(&dae)?;
+        assert_eq!(sizes.original, original.len() as u64);
+        assert!(sizes.gzip < sizes.original);
+        assert!(sizes.brotli < sizes.original);
+
+        let mut gunzipped = String::new();
+        GzDecoder::new(File::open(test_dir.join("cube.dae.gz"))?).read_to_string(&mut gunzipped)?;
+        assert_eq!(gunzipped, original);
+
+        let mut unbrotlied = String::new();
+        brotli::Decompressor::new(File::open(test_dir.join("cube.dae.br"))?, 4096)
+            .read_to_string(&mut unbrotlied)?;
+        assert_eq!(unbrotlied, original);
+
+        // Up to date siblings are left alone
+        assert_eq!(compress_file(&dae)?, sizes);
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+}
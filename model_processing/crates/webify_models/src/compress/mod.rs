@@ -0,0 +1,14 @@
+//! Set of functions to write gzip and brotli versions of the text assets, for servers
+//! that can send them with a `Content-Encoding`
+
+mod compress_file;
+mod model_compression;
+mod print_compression_table;
+mod process;
+mod scan_dir_for_text_assets;
+
+pub use self::compress_file::{compress_file, CompressedSizes};
+pub use self::model_compression::ModelCompression;
+pub use self::print_compression_table::print_compression_table;
+pub use self::process::{process, process_assets, DEFAULT_MIN_SIZE};
+pub use self::scan_dir_for_text_assets::scan_dir_for_text_assets;
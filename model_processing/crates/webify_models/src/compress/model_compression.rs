@@ -0,0 +1,73 @@
+//! Compression totals of a model
+
+use serde::Serialize;
+
+use crate::compress::CompressedSizes;
+
+/// Sizes of the compressed text assets of a model, summed up
+#[derive(Debug, Default, Serialize)]
+pub struct ModelCompression {
+    pub name: String,
+    pub files: usize,
+    pub original: u64,
+    pub gzip: u64,
+    pub brotli: u64,
+}
+
+impl ModelCompression {
+    pub fn new(name: &str) -> ModelCompression {
+        ModelCompression {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Count a compressed file towards the model
+    pub fn add(&mut self, sizes: CompressedSizes) {
+        self.files += 1;
+        self.original += sizes.original;
+        self.gzip += sizes.gzip;
+        self.brotli += sizes.brotli;
+    }
+
+    /// Compressed size over original size, e.g. 0.25 when gzip saves 75%
+    pub fn gzip_ratio(&self) -> f64 {
+        ratio(self.gzip, self.original)
+    }
+
+    pub fn brotli_ratio(&self) -> f64 {
+        ratio(self.brotli, self.original)
+    }
+}
+
+fn ratio(compressed: u64, original: u64) -> f64 {
+    if original == 0 {
+        1.0
+    } else {
+        compressed as f64 / original as f64
+    }
+}
+
+#[cfg(test)]
+mod model_compression_tests {
+    use super::*;
+
+    #[test]
+    fn it_sums_up_files() {
+        let mut model = ModelCompression::new("box");
+        assert_eq!(model.gzip_ratio(), 1.0);
+
+        for _ in 0..2 {
+            model.add(CompressedSizes {
+                original: 1000,
+                gzip: 250,
+                brotli: 200,
+            });
+        }
+
+        assert_eq!(model.files, 2);
+        assert_eq!(model.original, 2000);
+        assert_eq!(model.gzip_ratio(), 0.25);
+        assert_eq!(model.brotli_ratio(), 0.2);
+    }
+}
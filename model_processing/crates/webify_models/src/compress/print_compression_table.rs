@@ -0,0 +1,58 @@
+//! Print the compression ratios as an aligned table on the terminal
+
+use console::style;
+
+use crate::compress::ModelCompression;
+use crate::progress::print_message;
+use crate::stats::format_bytes;
+
+/// Print one row per model, then the totals
+pub fn print_compression_table(models: &[ModelCompression]) {
+    let name_width = models
+        .iter()
+        .map(|m| m.name.len())
+        .chain(std::iter::once(5))
+        .max()
+        .unwrap_or(5);
+
+    print_message(
+        style(format!(
+            "{:<name_width$} {:>6} {:>10} {:>10} {:>6} {:>10} {:>6}",
+            "Model",
+            "Files",
+            "Original",
+            "Gzip",
+            "Ratio",
+            "Brotli",
+            "Ratio",
+            name_width = name_width
+        ))
+        .bold(),
+    );
+
+    let mut total = ModelCompression::new("Total");
+    for model in models {
+        print_row(model, name_width);
+        total.files += model.files;
+        total.original += model.original;
+        total.gzip += model.gzip;
+        total.brotli += model.brotli;
+    }
+    if models.len() > 1 {
+        print_row(&total, name_width);
+    }
+}
+
+fn print_row(model: &ModelCompression, name_width: usize) {
+    print_message(format!(
+        "{:<name_width$} {:>6} {:>10} {:>10} {:>5.1}% {:>10} {:>5.1}%",
+        model.name,
+        model.files,
+        format_bytes(model.original),
+        format_bytes(model.gzip),
+        model.gzip_ratio() * 100.0,
+        format_bytes(model.brotli),
+        model.brotli_ratio() * 100.0,
+        name_width = name_width
+    ));
+}
@@ -0,0 +1,79 @@
+//! Orchestrator to pre-compress the text assets of a directory
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::compress::{
+    compress_file, print_compression_table, scan_dir_for_text_assets, ModelCompression,
+};
+use crate::progress::create_progress_bar;
+
+/// Text assets smaller than this aren't worth a request header, let alone compressing
+pub const DEFAULT_MIN_SIZE: u64 = 1024;
+
+/// Orchestrator to write `.gz` and `.br` siblings for the text assets of at least
+/// `min_size` bytes in `dir`, then print the compression ratio of every model
+pub fn process(dir: &Path, min_size: u64) -> std::result::Result<(), std::io::Error> {
+    process_assets(dir, scan_dir_for_text_assets(dir, min_size)?)
+}
+
+/// Compress the text assets found in `dir`, then print the compression ratio of every
+/// model
+pub fn process_assets(dir: &Path, assets: Vec<PathBuf>) -> std::result::Result<(), std::io::Error> {
+    let compress_bar = create_progress_bar(assets.len() as u64);
+    compress_bar.set_prefix("Compression");
+
+    // Assets are grouped by the top-level directory they're in, which is the model
+    let mut models: BTreeMap<String, ModelCompression> = BTreeMap::new();
+    for asset in assets {
+        compress_bar.inc(1);
+        compress_bar.set_message(&format!("Compressing {:?}...", &asset));
+
+        let relative = asset.strip_prefix(dir).unwrap_or(&asset);
+        let name = match relative.components().count() {
+            1 => String::from("(top level)"),
+            _ => relative
+                .components()
+                .next()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        models
+            .entry(name.clone())
+            .or_insert_with(|| ModelCompression::new(&name))
+            .add(compress_file(&asset)?);
+    }
+    compress_bar.finish_with_message("Text assets compressed!");
+
+    print_compression_table(&models.into_values().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::import::copy_dir;
+    use std::fs;
+
+    #[test]
+    fn it_compresses_text_assets_above_the_threshold() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests")
+            .join("compress")
+            .join("test_run_it_compresses_text_assets_above_the_threshold");
+        copy_dir(&Path::new("tests").join("index"), &test_dir)?;
+
+        process(&test_dir, DEFAULT_MIN_SIZE)?;
+
+        let cube = test_dir.join("cube");
+        assert!(cube.join("meshes").join("cube.dae.gz").is_file());
+        assert!(cube.join("meshes").join("cube.dae.br").is_file());
+        assert!(!cube.join("model.sdf.gz").exists());
+        assert!(!cube.join("thumbnails").join("0.png.gz").exists());
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Find the text assets worth compressing
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::walk::walk_assets;
+
+/// Extensions of the text files the web client downloads
+pub const TEXT_ASSET_TYPES: [&str; 10] = [
+    "dae", "sdf", "json", "gltf", "material", "config", "world", "urdf", "obj", "xml",
+];
+
+/// Recursively find the text assets of at least `min_size` bytes in the directory that
+/// the scan filter doesn't exclude
+pub fn scan_dir_for_text_assets(
+    dir: &Path,
+    min_size: u64,
+) -> std::result::Result<Vec<PathBuf>, std::io::Error> {
+    let mut assets = vec![];
+    for asset in walk_assets(dir)? {
+        let is_text = TEXT_ASSET_TYPES.contains(&asset.extension().to_lowercase().as_str());
+        if is_text && fs::metadata(&asset.path)?.len() >= min_size {
+            assets.push(asset.path);
+        }
+    }
+
+    Ok(assets)
+}
+
+#[cfg(test)]
+mod scan_dir_for_text_assets_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_large_enough_text_assets() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("index");
+        let cube = dir.join("cube");
+
+        assert_eq!(
+            scan_dir_for_text_assets(&dir, 0)?,
+            vec![
+                cube.join("meshes").join("cube.dae"),
+                cube.join("model.config"),
+                cube.join("model.sdf"),
+            ]
+        );
+        assert_eq!(
+            scan_dir_for_text_assets(&dir, 1024)?,
+            vec![cube.join("meshes").join("cube.dae")]
+        );
+
+        Ok(())
+    }
+}
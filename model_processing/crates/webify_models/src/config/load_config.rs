@@ -0,0 +1,222 @@
+//! Read the settings of a models directory and of its models
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use toml::{value::Table, Value};
+
+use crate::config::Settings;
+use crate::model_config::model_name;
+use crate::progress::print_warning;
+
+/// Name of the settings file, at the root of the models directory or of a model
+pub const CONFIG_FILE: &str = "webify.toml";
+
+/// Settings of the models directory, and of the models that override some of them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    global: Settings,
+    models: BTreeMap<String, Settings>,
+}
+
+impl Config {
+    /// Settings of the root `webify.toml`
+    pub fn global(&self) -> &Settings {
+        &self.global
+    }
+
+    pub fn for_model(&self, name: &str) -> &Settings {
+        self.models.get(name).unwrap_or(&self.global)
+    }
+
+    /// Settings of the model a file of the models directory `dir` is in
+    pub fn for_path(&self, dir: &Path, path: &Path) -> &Settings {
+        match model_name(dir, path) {
+            Some(name) => self.for_model(&name),
+            None => &self.global,
+        }
+    }
+
+    /// Whether a file of the models directory `dir` is excluded by the settings of its
+    /// model
+    pub fn is_excluded(&self, dir: &Path, path: &Path) -> bool {
+        let mut components = match path.strip_prefix(dir) {
+            Ok(relative) => relative.components(),
+            Err(_) => return false,
+        };
+        components.next();
+
+        self.for_path(dir, path).is_excluded(components.as_path())
+    }
+
+    /// Every extension treated as a texture by any model
+    pub fn texture_types(&self) -> Vec<String> {
+        let types: BTreeSet<&String> = std::iter::once(&self.global)
+            .chain(self.models.values())
+            .flat_map(|settings| settings.texture_types.iter())
+            .collect();
+
+        types.into_iter().cloned().collect()
+    }
+}
+
+/// Read the `webify.toml` at the root of the models directory, if any, then for each
+/// model its `[models.<name>]` section and its own `webify.toml`, each overriding what
+/// comes before it
+pub fn load_config(dir: &Path) -> std::result::Result<Config, Error> {
+    let config_path = dir.join(CONFIG_FILE);
+    let mut root = read_table(&config_path)?;
+    let mut sections = match root.remove("models") {
+        None => Table::new(),
+        Some(Value::Table(sections)) => sections,
+        Some(_) => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid {:?}: models must be a table", config_path),
+            ))
+        }
+    };
+
+    let mut config = Config {
+        global: parse_settings(root.clone(), &config_path.to_string_lossy())?,
+        models: BTreeMap::new(),
+    };
+
+    for entry in fs::read_dir(dir)? {
+        let model_dir = entry?.path();
+        if !model_dir.is_dir() {
+            continue;
+        }
+        let name = model_dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let section = sections.remove(&name);
+        let own = read_table(&model_dir.join(CONFIG_FILE))?;
+        if section.is_none() && own.is_empty() {
+            continue;
+        }
+
+        let mut merged = root.clone();
+        match section {
+            Some(Value::Table(section)) => merge(&mut merged, section),
+            Some(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid {:?}: models.{} must be a table", config_path, name),
+                ))
+            }
+            None => {}
+        }
+        merge(&mut merged, own);
+
+        let settings = parse_settings(merged, &format!("settings of {}", name))?;
+        config.models.insert(name, settings);
+    }
+
+    for name in sections.keys() {
+        print_warning(
+            format!("Ignoring [models.{}], it doesn't match any model", name),
+            Some(&config_path),
+        );
+    }
+
+    Ok(config)
+}
+
+/// Parse a TOML file into a table, or an empty one when there's no file
+fn read_table(path: &Path) -> std::result::Result<Table, Error> {
+    if !path.is_file() {
+        return Ok(Table::new());
+    }
+
+    toml::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {:?}: {}", path, e),
+        )
+    })
+}
+
+/// Override the keys of `base` with those of `overrides`, merging nested tables
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn parse_settings(table: Table, name: &str) -> std::result::Result<Settings, Error> {
+    Value::Table(table)
+        .try_into()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Invalid {}: {}", name, e)))
+}
+
+#[cfg(test)]
+mod load_config_tests {
+    use super::*;
+    use crate::config::TextureFormat;
+
+    #[test]
+    fn it_defaults_without_a_config() -> std::result::Result<(), Error> {
+        let dir = Path::new("tests").join("index");
+
+        assert_eq!(load_config(&dir)?, Config::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_overrides_per_model() -> std::result::Result<(), Error> {
+        let dir = Path::new("tests").join("config").join("models");
+        let config = load_config(&dir)?;
+
+        assert_eq!(config.global().max_triangles, 50_000);
+        assert!(config.global().stages.compress);
+        assert_eq!(config.global().texture_format, TextureFormat::Png);
+
+        // From [models.big_box]
+        let big_box = config.for_model("big_box");
+        assert_eq!(big_box.max_triangles, 500_000);
+        assert!(!big_box.stages.normalize);
+        assert!(big_box.stages.compress);
+
+        // From its own webify.toml, over [models.old_box]
+        let old_box = config.for_model("old_box");
+        assert_eq!(old_box.texture_format, TextureFormat::Keep);
+        assert_eq!(old_box.max_triangles, 1_000);
+        assert!(!old_box.stages.images);
+        assert!(config.is_excluded(&dir, &dir.join("old_box").join("source").join("wood.psd")));
+        assert!(!config.is_excluded(&dir, &dir.join("big_box").join("source").join("wood.psd")));
+
+        assert_eq!(config.for_model("box"), config.global());
+        assert_eq!(
+            config.for_path(&dir, &dir.join("big_box").join("model.sdf")),
+            big_box
+        );
+        assert_eq!(
+            config.for_path(&dir, &dir.join("webify.toml")),
+            config.global()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_unknown_settings() {
+        let dir = Path::new("tests").join("config").join("invalid");
+
+        let result = load_config(&dir);
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
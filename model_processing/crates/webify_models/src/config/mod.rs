@@ -0,0 +1,7 @@
+//! Settings read from the `webify.toml` of the models directory and of each model
+
+mod load_config;
+mod settings;
+
+pub use self::load_config::{load_config, Config};
+pub use self::settings::{Settings, TextureFormat};
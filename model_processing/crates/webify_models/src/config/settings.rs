@@ -0,0 +1,147 @@
+//! Structure holding everything a `webify.toml` can configure, with the defaults used
+//! without one
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::image_processing::scan_dir_for_images::TEXTURE_IMAGE_TYPES;
+
+/// What textures are converted to
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextureFormat {
+    /// Convert textures to PNG, and rename the references to them
+    Png,
+    /// Leave textures in the format they're in
+    Keep,
+}
+
+/// Which stages of the pipeline run, and in what order
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Stages {
+    /// Move and convert the textures
+    pub images: bool,
+    /// Update the texture references of the meshes
+    pub meshes: bool,
+    /// Convert the meshes to meters and Z up, like `--normalize`
+    pub normalize: bool,
+    /// Write `materials.json` and `materials.gltf` (only read from the root settings)
+    pub materials: bool,
+    /// Write `index.json` (only read from the root settings)
+    pub index: bool,
+    /// Pre-compress the text assets, like `--compress` (only read from the root settings)
+    pub compress: bool,
+    /// Names of the stages to run, in order, leaving the others out (only read from the
+    /// root settings). Stages converting single models still run before the ones working
+    /// on the whole directory.
+    pub order: Option<Vec<String>>,
+}
+
+impl Default for Stages {
+    fn default() -> Stages {
+        Stages {
+            images: true,
+            meshes: true,
+            normalize: false,
+            materials: true,
+            index: true,
+            compress: false,
+            order: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Extensions of the images treated as textures
+    pub texture_types: Vec<String>,
+    pub texture_format: TextureFormat,
+    /// Where stray textures are moved to, relative to the model directory
+    pub textures_dir: PathBuf,
+    /// Largest side of a texture, in pixels, before `check` complains
+    pub max_texture_size: u32,
+    /// Triangles a single mesh can draw before `check` complains
+    pub max_triangles: usize,
+    /// Paths, relative to the model directory, that are left alone
+    pub exclude: Vec<PathBuf>,
+    pub stages: Stages,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            texture_types: TEXTURE_IMAGE_TYPES.iter().map(|t| t.to_string()).collect(),
+            texture_format: TextureFormat::Png,
+            textures_dir: Path::new("materials").join("textures"),
+            max_texture_size: 2048,
+            max_triangles: 100_000,
+            exclude: vec![],
+            stages: Stages::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Whether a path, relative to the model directory, is in an excluded path
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude
+            .iter()
+            .any(|excluded| path.starts_with(excluded))
+    }
+
+    /// Extensions of the textures that are converted, and so whose references are
+    /// renamed. TIFF images can't be converted, so they're left as is.
+    pub fn converted_types(&self) -> Vec<&str> {
+        match self.texture_format {
+            TextureFormat::Keep => vec![],
+            TextureFormat::Png => self
+                .texture_types
+                .iter()
+                .map(String::as_str)
+                .filter(|t| !["png", "tif", "tiff"].contains(t))
+                .collect(),
+        }
+    }
+
+    /// Extensions of the textures once converted, which mesh references point at
+    pub fn output_types(&self) -> Vec<&str> {
+        match self.texture_format {
+            TextureFormat::Keep => self.texture_types.iter().map(String::as_str).collect(),
+            TextureFormat::Png => vec!["png"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_the_converted_types() {
+        let mut settings = Settings::default();
+
+        assert_eq!(
+            settings.converted_types(),
+            vec!["tga", "jpeg", "jpg", "gif"]
+        );
+        assert_eq!(settings.output_types(), vec!["png"]);
+
+        settings.texture_format = TextureFormat::Keep;
+        assert!(settings.converted_types().is_empty());
+        assert_eq!(settings.output_types().len(), TEXTURE_IMAGE_TYPES.len());
+    }
+
+    #[test]
+    fn it_excludes_whole_directories() {
+        let settings = Settings {
+            exclude: vec![PathBuf::from("source")],
+            ..Settings::default()
+        };
+
+        assert!(settings.is_excluded(&Path::new("source").join("wood.psd")));
+        assert!(!settings.is_excluded(&Path::new("sources").join("wood.psd")));
+    }
+}
@@ -0,0 +1,6 @@
+//! Options of a run that every scanner and stage it goes through reads, so that each
+//! pipeline can have its own
+
+mod run_context;
+
+pub use self::run_context::{EnteredContext, RunContext};
@@ -0,0 +1,115 @@
+//! Structure holding what a run shares with every step of it
+
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::filter::PathFilter;
+use crate::progress::ProgressMode;
+use crate::report::Recorder;
+
+thread_local! {
+    /// The context entered last on this thread, or the default one
+    static CURRENT: RefCell<Rc<RunContext>> = RefCell::new(Rc::new(RunContext::default()));
+}
+
+/// Which files the scanners look at, how progress is shown, and where the file actions
+/// are recorded. A `Pipeline` enters its own while it runs, and what runs outside of any
+/// uses the default filter, shows progress the way that suits the terminal and records
+/// nothing.
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    pub filter: PathFilter,
+    pub progress: ProgressMode,
+    /// Without one, nobody asked for a report
+    pub recorder: Option<Recorder>,
+    /// Models directory the globs of the filter are relative to, like those of its
+    /// `.webifyignore`. Without one, they're relative to the directory being scanned.
+    pub dir: Option<PathBuf>,
+}
+
+impl RunContext {
+    /// Use this context on this thread until the returned guard is dropped, which brings
+    /// back the context that was there before
+    pub fn enter(&self) -> EnteredContext {
+        let entered = Rc::new(self.clone());
+
+        EnteredContext {
+            previous: Some(CURRENT.with(|current| current.replace(entered))),
+        }
+    }
+
+    /// Use this context for the models directory `dir` until the returned guard is dropped
+    pub fn enter_dir(&self, dir: &Path) -> EnteredContext {
+        RunContext {
+            dir: Some(dir.to_path_buf()),
+            ..self.clone()
+        }
+        .enter()
+    }
+
+    /// The context entered last on this thread, or the default one
+    pub fn current() -> Rc<RunContext> {
+        CURRENT.with(|current| Rc::clone(&current.borrow()))
+    }
+}
+
+/// Guard of an entered context, leaving it when dropped
+#[must_use = "the context is left as soon as the guard is dropped"]
+pub struct EnteredContext {
+    previous: Option<Rc<RunContext>>,
+}
+
+impl Drop for EnteredContext {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CURRENT.with(|current| current.replace(previous));
+        }
+    }
+}
+
+#[cfg(test)]
+mod run_context_tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn it_brings_back_the_previous_context() {
+        let json = RunContext {
+            progress: ProgressMode::Json,
+            ..RunContext::default()
+        };
+        let plain = RunContext {
+            progress: ProgressMode::Plain,
+            ..RunContext::default()
+        };
+
+        {
+            let _json = json.enter();
+            {
+                let _plain = plain.enter();
+                assert_eq!(RunContext::current().progress, ProgressMode::Plain);
+            }
+            assert_eq!(RunContext::current().progress, ProgressMode::Json);
+        }
+        assert_eq!(RunContext::current().progress, ProgressMode::Auto);
+    }
+
+    #[test]
+    fn it_keeps_contexts_to_their_thread() {
+        let json = RunContext {
+            progress: ProgressMode::Json,
+            ..RunContext::default()
+        };
+
+        let _json = json.enter();
+        let elsewhere = thread::spawn(|| RunContext::current().progress)
+            .join()
+            .unwrap();
+
+        assert_eq!(elsewhere, ProgressMode::Auto);
+        assert_eq!(RunContext::current().progress, ProgressMode::Json);
+    }
+}
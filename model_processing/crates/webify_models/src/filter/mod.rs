@@ -0,0 +1,9 @@
+//! Include and exclude globs deciding which files the scanners look at
+
+mod path_filter;
+mod read_ignore_file;
+mod scan_filter;
+
+pub use self::path_filter::PathFilter;
+pub use self::read_ignore_file::read_ignore_file;
+pub use self::scan_filter::{is_dir_excluded, is_excluded};
@@ -0,0 +1,146 @@
+//! Structure that matches relative paths against include and exclude globs
+
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Directories nobody wants webified: thumbnails aren't textures, and version control
+/// directories aren't models
+pub const DEFAULT_EXCLUDES: [&str; 5] = ["thumbnails", ".git", ".svn", ".hg", ".bzr"];
+
+/// Globs of the files to scan, and of the files and directories to leave out.
+///
+/// Like in a `.gitignore`, a glob without a `/` matches at any depth, and a glob with one
+/// is relative to the models directory. Excluding a directory excludes everything in it.
+#[derive(Debug, Clone)]
+pub struct PathFilter {
+    /// Without includes, every file that isn't excluded is scanned
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    /// The include and exclude globs it was built from
+    globs: (Vec<String>, Vec<String>),
+}
+
+impl Default for PathFilter {
+    fn default() -> PathFilter {
+        PathFilter::new(&[], &[]).expect("the default excludes are valid globs")
+    }
+}
+
+impl PathFilter {
+    /// Filter keeping the files matching any of `include` (or all of them when empty) that
+    /// don't match any of `exclude` or the `DEFAULT_EXCLUDES`
+    pub fn new(include: &[String], exclude: &[String]) -> std::result::Result<PathFilter, Error> {
+        let defaults = DEFAULT_EXCLUDES.iter().map(|glob| glob.to_string());
+
+        Ok(PathFilter {
+            include: if include.is_empty() {
+                None
+            } else {
+                Some(build_glob_set(include.iter().cloned())?)
+            },
+            exclude: build_glob_set(defaults.chain(exclude.iter().cloned()))?,
+            globs: (include.to_vec(), exclude.to_vec()),
+        })
+    }
+
+    /// The include and exclude globs given to `new`, which tell filters apart
+    pub fn globs(&self) -> (&[String], &[String]) {
+        (&self.globs.0, &self.globs.1)
+    }
+
+    /// Whether a file, relative to the models directory, is left out
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if self.is_dir_excluded(path) {
+            return true;
+        }
+
+        match &self.include {
+            Some(include) => !include.is_match(path),
+            None => false,
+        }
+    }
+
+    /// Whether a directory, relative to the models directory, is left out with everything
+    /// in it. Includes only pick files, so directories are only left out by excludes.
+    pub fn is_dir_excluded(&self, path: &Path) -> bool {
+        path.ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .any(|ancestor| self.exclude.is_match(ancestor))
+    }
+}
+
+fn build_glob_set<I: IntoIterator<Item = String>>(globs: I) -> std::result::Result<GlobSet, Error> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let trimmed = glob.trim().trim_end_matches('/');
+        let anchored = match trimmed.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if trimmed.contains('/') => trimmed.to_string(),
+            None => format!("**/{}", trimmed),
+        };
+
+        builder.add(
+            GlobBuilder::new(&anchored)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid glob {:?}: {}", glob, e),
+                    )
+                })?,
+        );
+    }
+
+    builder
+        .build()
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))
+}
+
+#[cfg(test)]
+mod path_filter_tests {
+    use super::*;
+
+    #[test]
+    fn it_excludes_thumbnails_and_vcs_by_default() {
+        let filter = PathFilter::default();
+
+        assert!(filter.is_excluded(&Path::new("box").join("thumbnails").join("0.png")));
+        assert!(filter.is_excluded(&Path::new(".git").join("config")));
+        assert!(!filter.is_excluded(&Path::new("box").join("meshes").join("box.dae")));
+    }
+
+    #[test]
+    fn it_matches_globs_like_gitignore() -> std::result::Result<(), Error> {
+        let filter = PathFilter::new(&[], &[String::from("*.psd"), String::from("/box/build/")])?;
+
+        assert!(filter.is_excluded(&Path::new("box").join("wood.psd")));
+        assert!(filter.is_excluded(&Path::new("box").join("build").join("box.dae")));
+        // Anchored globs only match from the models directory
+        assert!(!filter.is_excluded(&Path::new("crate").join("box").join("build").join("box.dae")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_only_keeps_included_files() -> std::result::Result<(), Error> {
+        let filter = PathFilter::new(&[String::from("*.dae")], &[])?;
+
+        assert!(!filter.is_excluded(&Path::new("box").join("meshes").join("box.dae")));
+        assert!(filter.is_excluded(&Path::new("box").join("model.sdf")));
+        assert!(!filter.is_dir_excluded(&Path::new("box").join("meshes")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_invalid_globs() {
+        let result = PathFilter::new(&[], &[String::from("box/[")]);
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,40 @@
+//! Read the globs of a `.webifyignore`
+
+use std::{fs, path::Path};
+
+/// Name of the file listing globs to exclude, at the root of the models directory
+pub const IGNORE_FILE: &str = ".webifyignore";
+
+/// Return the globs of the `.webifyignore` of a directory, one per line, skipping blank
+/// lines and `#` comments, or none when there's no such file
+pub fn read_ignore_file(dir: &Path) -> std::result::Result<Vec<String>, std::io::Error> {
+    let path = dir.join(IGNORE_FILE);
+    if !path.is_file() {
+        return Ok(vec![]);
+    }
+
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod read_ignore_file_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_the_globs() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("filter").join("models");
+
+        assert_eq!(
+            read_ignore_file(&dir)?,
+            vec![String::from("*.psd"), String::from("/box/source")]
+        );
+        assert!(read_ignore_file(&dir.join("box"))?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! The filter every scanner of the run honors
+
+use std::path::Path;
+
+use crate::context::RunContext;
+
+/// Whether a file found scanning `dir` is left out of the scan by the filter of the run
+/// going on. The file is matched relative to the models directory of the run when it's
+/// in it, even when `dir` is a model or a directory further down, and relative to `dir`
+/// otherwise.
+pub fn is_excluded(dir: &Path, path: &Path) -> bool {
+    let context = RunContext::current();
+    match path.strip_prefix(root(&context, dir, path)) {
+        Ok(relative) => context.filter.is_excluded(relative),
+        Err(_) => false,
+    }
+}
+
+/// Whether a directory found scanning `dir` is left out of the scan, with everything in
+/// it, like `is_excluded` does for files
+pub fn is_dir_excluded(dir: &Path, path: &Path) -> bool {
+    let context = RunContext::current();
+    match path.strip_prefix(root(&context, dir, path)) {
+        Ok(relative) => context.filter.is_dir_excluded(relative),
+        Err(_) => false,
+    }
+}
+
+/// The directory the globs of the filter are matched from for `path`
+fn root<'a>(context: &'a RunContext, dir: &'a Path, path: &Path) -> &'a Path {
+    context
+        .dir
+        .as_deref()
+        .filter(|root| path.starts_with(root))
+        .unwrap_or(dir)
+}
+
+#[cfg(test)]
+mod scan_filter_tests {
+    use super::*;
+    use crate::filter::{read_ignore_file, PathFilter};
+
+    #[test]
+    fn it_filters_relative_to_the_scanned_dir() {
+        let dir = Path::new("tests").join("filter").join("models");
+
+        assert!(is_excluded(
+            &dir,
+            &dir.join("box").join("thumbnails").join("0.png")
+        ));
+        assert!(!is_excluded(&dir, &dir.join("box").join("model.sdf")));
+        // The models directory itself could be in a directory called thumbnails
+        assert!(!is_excluded(
+            &dir.join("box").join("thumbnails"),
+            &dir.join("box").join("thumbnails").join("0.png")
+        ));
+    }
+
+    #[test]
+    fn it_filters_relative_to_the_models_dir() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("filter").join("models");
+        let model_dir = dir.join("box");
+        let context = RunContext {
+            filter: PathFilter::new(&[], &read_ignore_file(&dir)?)?,
+            ..RunContext::default()
+        };
+
+        let _context = context.enter_dir(&dir);
+        // /box/source is anchored at the models directory, even scanning a single model
+        assert!(is_excluded(
+            &model_dir,
+            &model_dir.join("source").join("box_diffuse.jpg")
+        ));
+        assert!(!is_excluded(&model_dir, &model_dir.join("model.config")));
+        // Outside of the models directory, globs are relative to the scanned directory
+        let other_dir = Path::new("tests").join("index");
+        assert!(is_excluded(
+            &other_dir,
+            &other_dir.join("box").join("source").join("box_diffuse.jpg")
+        ));
+
+        Ok(())
+    }
+}
@@ -1,40 +1,49 @@
 //! Converts an image file to PNG, or skips if it's already PNG
 
-use std::{fs, io::Error, panic, result::Result};
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    result::Result,
+};
 
 use image::io::Reader as ImageReader;
 use image::ImageFormat::Tiff;
+use tracing::{debug, info};
 
 use crate::image_processing::Image;
 
-/// Convert the specified image to a PNG version
+/// Convert the specified image to a PNG version, failing with `InvalidData` when it can't
+/// be decoded or saved as one
 pub fn convert_to_png(mut image: Image) -> Result<Image, Error> {
     let Image { ref extension, ref path } = image;
 
     if extension == "tif" {
+        debug!(?path, "Skipped the conversion of a TIFF image");
         return Ok(image); // Skip tif!
     }
-    let image_reader = match ImageReader::open(path) {
-        Ok(img) => img,
-        Err(e) => panic!("Failed to open image during PNG conversion: {:?}", e),
-    };
+    let image_reader = ImageReader::open(path)?;
 
     // Somehow, Tiff conversion is problematic, so we'll skip that
     if image_reader.format().is_some() && image_reader.format() != Some(Tiff) {
-        let img = match image_reader.decode() {
-            Ok(i) => i,
-            Err(e) => panic!("Failed to open image during PNG conversion: {:?}", e),
-        };
-
-        match img.save(path.with_extension("png")) {
-            Ok(_) => "",
-            Err(e) => panic!("Could not convert {:?} to PNG: {:?}", path, e),
-        };
+        let img = image_reader.decode().map_err(|e| {
+            Error::new(ErrorKind::InvalidData, format!("Failed to decode the image: {}", e))
+        })?;
+        debug!(?path, "Decoded the image");
+
+        img.save(path.with_extension("png")).map_err(|e| {
+            Error::new(ErrorKind::InvalidData, format!("Failed to save the image as PNG: {}", e))
+        })?;
+        info!(
+            source = ?path,
+            destination = ?path.with_extension("png"),
+            "Converted the image to PNG"
+        );
 
         fs::remove_file(path)?;
+        debug!(?path, "Removed the original image");
         image.path = path.with_extension("png");
     } else {
-        panic!("Failed to convert provided image: {:?}", path);
+        return Err(Error::new(ErrorKind::InvalidData, "Unsupported image format"));
     }
 
     Ok(image)
@@ -110,8 +119,8 @@ mod convert_tests {
     }
 
     #[test]
-    fn it_panics_on_non_images() {
-        let test_run_name = "test_run_it_panics_on_non_images";
+    fn it_fails_on_non_images() {
+        let test_run_name = "test_run_it_fails_on_non_images";
         setup(test_run_name).unwrap();
 
         let test_image_path = Path::new("tests")
@@ -125,12 +134,8 @@ mod convert_tests {
             extension: String::from("jpg"),
         };
 
-        // Catch the panic here so we can teardown after, otherwise
-        // just using should_panic will leave use with no teardown
-        let result = panic::catch_unwind(|| {
-            convert_to_png(non_image).unwrap();
-        });
-        assert!(result.is_err());
+        let error = convert_to_png(non_image).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
 
         teardown(test_run_name).unwrap();
     }
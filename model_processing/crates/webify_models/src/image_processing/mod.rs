@@ -10,5 +10,5 @@ pub use self::image::Image;
 
 pub use self::convert_to_png::convert_to_png;
 pub use self::move_to_textures_dir::move_to_textures_dir;
-pub use self::process::process;
-pub use self::scan_dir_for_images::scan_dir_for_images;
+pub use self::process::{plan_images, process, process_images};
+pub use self::scan_dir_for_images::{scan_dir_for_image_types, scan_dir_for_images};
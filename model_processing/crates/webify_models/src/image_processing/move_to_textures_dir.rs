@@ -6,30 +6,47 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use tracing::{debug, info, trace};
+
 use crate::image_processing::Image;
 
 /// Move any stray textures to the textures path (typically materials/textures)
 pub fn move_to_textures_dir<'a>(
     mut image: Image,
     base_path: &Path,
+    textures_dir: &Path,
 ) -> std::result::Result<Image, std::io::Error> {
     let file_name = image
         .path
         .file_name()
         .and_then(|f| f.to_str())
         .ok_or(Error::new(ErrorKind::Other, "Path not provided, no work to do"))?;
-    let textures_path: PathBuf = Path::new("materials").join("textures").join(file_name);
+    let textures_path: PathBuf = textures_dir.join(file_name);
     let meshes_path: PathBuf = Path::new("meshes").join(file_name);
 
     if !image.path.ends_with(textures_path) && !image.path.ends_with(meshes_path) {
-        let new_textures_path = get_new_textures_path(&image, base_path)?;
+        let new_textures_path = get_new_textures_path(&image, base_path, textures_dir)?;
         fs::create_dir_all(&new_textures_path)?;
+        debug!(path = ?new_textures_path, "Created the textures directory");
         fs::copy(&image.path, new_textures_path.join(file_name))?;
+        debug!(
+            source = ?image.path,
+            destination = ?new_textures_path.join(file_name),
+            "Copied the texture"
+        );
         fs::remove_file(&image.path)?;
+        debug!(path = ?image.path, "Removed the stray texture");
 
         let new_textures_path_with_ext = new_textures_path.join(file_name).clone();
 
+        info!(
+            source = ?image.path,
+            destination = ?new_textures_path_with_ext,
+            "Moved the texture to the textures directory"
+        );
         image.path = new_textures_path_with_ext;
+    } else {
+        trace!(path = ?image.path, "Texture already in place");
     }
 
     Ok(image)
@@ -39,15 +56,13 @@ pub fn move_to_textures_dir<'a>(
 fn get_new_textures_path(
     image: &Image,
     base_path: &Path,
+    textures_dir: &Path,
 ) -> std::result::Result<PathBuf, std::io::Error> {
     let mut model_path_ancestors = image.path.strip_prefix(base_path).unwrap().ancestors();
     let model_path = model_path_ancestors
         .nth(model_path_ancestors.count() - 2)
         .unwrap(); // There must be a better way to do this...
-    let new_textures_path = base_path
-        .join(model_path)
-        .join("materials")
-        .join("textures");
+    let new_textures_path = base_path.join(model_path).join(textures_dir);
 
     Ok(new_textures_path)
 }
@@ -73,7 +88,8 @@ mod get_new_textures_path_tests {
             path: base_path.join("foo_test").join("foo.jpg"),
             extension: String::from("jpg"),
         };
-        let result = get_new_textures_path(&img, &base_path)?;
+        let textures_dir = Path::new("materials").join("textures");
+        let result = get_new_textures_path(&img, &base_path, &textures_dir)?;
         let new_textures_path = &base_path
             .join("foo_test")
             .join("materials")
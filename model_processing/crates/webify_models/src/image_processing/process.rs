@@ -4,30 +4,90 @@ use std::path::Path;
 
 use console::style;
 
-use crate::cli::create_progress_bar;
-use crate::image_processing::{convert_to_png, move_to_textures_dir, scan_dir_for_images};
+use crate::config::{Config, TextureFormat};
+use crate::image_processing::{
+    convert_to_png, move_to_textures_dir, scan_dir_for_image_types, Image,
+};
+use crate::logging::model_span;
+use crate::progress::{create_progress_bar, print_warning};
+use crate::report::FileAction;
 
-/// Orchestrator to convert texture images from whatever format they're in to PNG
-pub fn process(dir: &Path) -> std::result::Result<(), std::io::Error> {
-    let images = scan_dir_for_images(dir).unwrap();
+/// Orchestrator to convert texture images from whatever format they're in to PNG, with
+/// the settings of the model each image is in
+pub fn process(dir: &Path, config: &Config) -> std::result::Result<(), std::io::Error> {
+    let images = plan_images(dir, config)?;
+    process_images(dir, config, images)
+}
+
+/// Find the images to move and convert, leaving out those of the models whose settings
+/// disable the images stage or exclude them
+pub fn plan_images(dir: &Path, config: &Config) -> std::result::Result<Vec<Image>, std::io::Error> {
+    let texture_types = config.texture_types();
+    let texture_types: Vec<&str> = texture_types.iter().map(String::as_str).collect();
+    let mut images = scan_dir_for_image_types(dir, &texture_types)?;
+    images.retain(|image| {
+        let settings = config.for_path(dir, &image.path);
+        settings.stages.images
+            && settings.texture_types.contains(&image.extension)
+            && !config.is_excluded(dir, &image.path)
+    });
+
+    Ok(images)
+}
+
+/// Move the images to the textures directory of their model and convert them
+pub fn process_images(
+    dir: &Path,
+    config: &Config,
+    images: Vec<Image>,
+) -> std::result::Result<(), std::io::Error> {
+    print_warning(
+        "Note that webify models is a destructive action and will DELETE the existing non-PNG files.",
+        None,
+    );
     let image_bar = create_progress_bar(images.len() as u64);
 
     image_bar.set_prefix("Texture Move");
     for image in images {
+        let _model = model_span(dir, &image.path).entered();
         image_bar.inc(1);
         let styled_path = style(image.path.to_string_lossy()).dim().to_string();
 
         image_bar.set_message(&format!("Moving {} to textures directory...", styled_path));
-        let moved_image = move_to_textures_dir(image, dir)?;
+        let move_action = FileAction::start("move", &image.path);
+        let settings = config.for_path(dir, &image.path);
+        let moved_image = match move_to_textures_dir(image, dir, &settings.textures_dir) {
+            Ok(moved_image) => moved_image,
+            Err(e) => {
+                move_action.fail(&e);
+                return Err(e);
+            }
+        };
+        if moved_image.path != move_action.source {
+            move_action.finish(&moved_image.path);
+        }
         let moved_image_path = style(moved_image.path.to_string_lossy()).dim().to_string();
         image_bar.set_message(&format!("Moved {} to {}", styled_path, moved_image_path));
 
         image_bar.set_prefix("PNG Conversion");
-        if moved_image.extension == "png" {
+        if settings.texture_format == TextureFormat::Keep {
+            image_bar.set_message(&format!("{} kept as is", moved_image_path));
+        } else if moved_image.extension == "png" {
             image_bar.set_message(&format!("{} already in PNG, skipping", moved_image_path));
         } else {
             image_bar.set_message(&format!("Converting {}...", moved_image_path));
-            convert_to_png(moved_image)?;
+            let mut convert_action = FileAction::start("convert", &moved_image.path);
+            let converted_image = match convert_to_png(moved_image) {
+                Ok(converted_image) => converted_image,
+                Err(e) => {
+                    convert_action.fail(&e);
+                    return Err(e);
+                }
+            };
+            if converted_image.path == convert_action.source {
+                convert_action.warn("Left as is, TIFF images aren't converted");
+            }
+            convert_action.finish(&converted_image.path);
             image_bar.set_message(&format!("{} converted!", moved_image_path));
         }
     }
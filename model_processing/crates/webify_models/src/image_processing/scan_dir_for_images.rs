@@ -1,89 +1,110 @@
 use console::style;
 use std::io::Result;
-use std::{fs, path::Path};
+use std::path::Path;
 
 use crate::image_processing::Image;
+use crate::progress::print_message;
+use crate::walk::walk_assets;
 
-const TEXTURE_IMAGE_TYPES: [&str; 7] = [
+pub const TEXTURE_IMAGE_TYPES: [&str; 7] = [
     r#"tif"#, r#"tga"#, r#"tiff"#, r#"jpeg"#, r#"jpg"#, r#"gif"#, r#"png"#,
 ];
 
 /// Find texture images in the specified path
 pub fn scan_dir_for_images(dir: &Path) -> Result<Vec<Image>> {
-    println!("\nScanning for images to webify...");
-    println!(
-    "{}",
-    &style(
-      "Note that webify models is a destructive action and will DELETE the existing non-PNG files."
-    )
-    .on_red()
-  );
-
-    let mut images = match recursive_scan(dir, Vec::new()) {
-        Ok(image_list) => image_list,
-        Err(error) => panic!("Failed to scan all directories for images: {:?}", error),
-    };
-    images.sort_by(|a, b| b.extension.cmp(&a.extension));
-
-    println!("Images found: {}\n", style(images.len()).bold().blue());
-
-    Ok(images)
+    scan_dir_for_image_types(dir, &TEXTURE_IMAGE_TYPES)
 }
 
-/// Recursively scan the directory and only return files that qualify
-/// as the images we're looking for
-fn recursive_scan(dir: &Path, mut images: Vec<Image>) -> Result<Vec<Image>> {
-    if dir.is_dir() {
-        for entry in fs::read_dir(dir)? {
-            let e = entry?;
-            let path = e.path();
+/// Find images with one of the extensions `types` in the specified path, for when
+/// `webify.toml` configures other texture types. Files the scan filter excludes are left
+/// out.
+pub fn scan_dir_for_image_types(dir: &Path, types: &[&str]) -> Result<Vec<Image>> {
+    print_message("\nScanning for images to webify...");
 
-            if path.is_dir() {
-                images = recursive_scan(&path, images.clone())?;
+    let mut images: Vec<Image> = walk_assets(dir)?
+        .into_iter()
+        .filter_map(|asset| {
+            let extension = asset.extension();
+            if types.contains(&extension.as_str()) {
+                Some(Image {
+                    path: asset.path,
+                    extension,
+                })
             } else {
-                let extension = match path.extension() {
-                    Some(ext) => ext.to_str().unwrap(),
-                    _ => "",
-                };
-
-                if TEXTURE_IMAGE_TYPES.contains(&extension) {
-                    images.push(Image {
-                        path: path.clone(),
-                        extension: extension.to_string(),
-                    });
-                };
+                None
             }
-        }
-    }
+        })
+        .collect();
+    images.sort_by(|a, b| b.extension.cmp(&a.extension));
+
+    print_message(format!(
+        "Images found: {}\n",
+        style(images.len()).bold().blue()
+    ));
 
     Ok(images)
 }
 
 #[cfg(test)]
 mod scan_dir_for_images_tests {
+    use super::*;
 
     #[test]
-    #[ignore = "not yet implemented"]
-    fn it_scans_the_dir() {
-        assert!(false);
+    fn it_scans_the_dir() -> Result<()> {
+        let dir = Path::new("tests")
+            .join("image_processing")
+            .join("image_scan");
+        let results = scan_dir_for_images(&dir)?;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].path,
+            dir.join("textures").join("materials").join("example.jpg")
+        );
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
-mod recursive_scan_tests {
+mod scan_dir_for_image_types_tests {
     use super::*;
+    use crate::context::RunContext;
+    use crate::filter::{read_ignore_file, PathFilter};
 
     #[test]
-    fn it_recursively_scans_the_dir() {
-        let dir = &Path::new("tests")
-            .join("image_processing")
-            .join("image_scan");
-        let results = recursive_scan(dir, Vec::new()).unwrap();
+    fn it_leaves_thumbnails_out() -> Result<()> {
+        let dir = Path::new("tests").join("filter").join("models");
+        let model_dir = dir.join("box");
+        let mut paths: Vec<_> = scan_dir_for_image_types(&model_dir, &TEXTURE_IMAGE_TYPES)?
+            .into_iter()
+            .map(|image| image.path)
+            .collect();
+        paths.sort();
 
-        assert_eq!(results.len(), 1);
         assert_eq!(
-            results[0].path,
-            dir.join("textures").join("materials").join("example.jpg")
+            paths,
+            vec![
+                model_dir.join("materials").join("textures").join("box.png"),
+                model_dir.join("source").join("box_diffuse.jpg"),
+            ]
+        );
+
+        // The anchored globs of the .webifyignore still apply from a model directory
+        let context = RunContext {
+            filter: PathFilter::new(&[], &read_ignore_file(&dir)?)?,
+            ..RunContext::default()
+        };
+        let _context = context.enter_dir(&dir);
+        let paths: Vec<_> = scan_dir_for_image_types(&model_dir, &TEXTURE_IMAGE_TYPES)?
+            .into_iter()
+            .map(|image| image.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![model_dir.join("materials").join("textures").join("box.png")]
         );
+
+        Ok(())
     }
 }
@@ -0,0 +1,81 @@
+//! Recursively copy a directory
+
+use std::{fs, path::Path};
+
+use crate::report::FileAction;
+use crate::walk::walk_all_assets;
+
+/// Copy the contents of `from` into `to`, creating it if needed, and return the number
+/// of files copied. Symbolic links are followed, without looping on links back up the
+/// tree, and directories without any file are left out. Every copy is recorded.
+pub fn copy_dir(from: &Path, to: &Path) -> std::result::Result<u64, std::io::Error> {
+    fs::create_dir_all(to)?;
+
+    let mut copied = 0;
+    for asset in walk_all_assets(from)? {
+        let destination = to.join(asset.path.strip_prefix(from).unwrap_or(&asset.path));
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let action = FileAction::start("copy", &asset.path);
+        match fs::copy(&asset.path, &destination) {
+            Ok(_) => action.finish(&destination),
+            Err(e) => {
+                action.fail(&e);
+                return Err(e);
+            }
+        }
+        copied += 1;
+    }
+
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod copy_dir_tests {
+    use super::*;
+
+    #[test]
+    fn it_copies_recursively() -> std::result::Result<(), std::io::Error> {
+        let from = Path::new("tests").join("materials").join("box");
+        let to = Path::new("tests")
+            .join("import")
+            .join("test_run_it_copies_recursively");
+
+        assert_eq!(copy_dir(&from, &to)?, 3);
+        assert!(to
+            .join("materials")
+            .join("scripts")
+            .join("box.material")
+            .is_file());
+
+        fs::remove_dir_all(to)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_copies_links_without_looping() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests")
+            .join("import")
+            .join("test_run_it_copies_links_without_looping");
+        let meshes_dir = test_dir.join("box").join("meshes");
+        fs::create_dir_all(&meshes_dir)?;
+        fs::write(meshes_dir.join("box.dae"), "")?;
+        std::os::unix::fs::symlink("..", meshes_dir.join("parent"))?;
+
+        let copied = copy_dir(&test_dir.join("box"), &test_dir.join("copy"));
+        let copied_mesh = test_dir
+            .join("copy")
+            .join("meshes")
+            .join("box.dae")
+            .is_file();
+        fs::remove_dir_all(&test_dir)?;
+
+        assert_eq!(copied?, 1);
+        assert!(copied_mesh);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+//! Find the models in a list of source directories, keeping the newest of each
+
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::model_config::{compare_versions, read_model_config};
+
+/// A model directory found in one of the sources
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSource {
+    /// Name of the model directory, which `model://` URIs refer to
+    pub name: String,
+    pub dir: PathBuf,
+    pub version: Option<String>,
+}
+
+/// Find the models directly inside each source, i.e. the directories with a
+/// `model.config`. When several sources have a model with the same name, the one with
+/// the highest `model.config` version wins, and the earliest source on a tie, the same
+/// precedence Gazebo gives `GAZEBO_MODEL_PATH`. Sources that don't exist are skipped.
+pub fn find_models(sources: &[PathBuf]) -> std::result::Result<Vec<ModelSource>, std::io::Error> {
+    let mut models: Vec<ModelSource> = Vec::new();
+
+    for source in sources.iter().filter(|source| source.is_dir()) {
+        let mut dirs: Vec<PathBuf> = fs::read_dir(source)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::result::Result<_, _>>()?;
+        dirs.sort();
+
+        for dir in dirs.into_iter().filter(|dir| is_model(dir)) {
+            let candidate = ModelSource {
+                name: dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                version: read_model_config(&dir)
+                    .ok()
+                    .and_then(|config| config.version),
+                dir,
+            };
+
+            match models.iter_mut().find(|m| m.name == candidate.name) {
+                Some(existing) if is_newer(&candidate.version, &existing.version) => {
+                    *existing = candidate
+                }
+                Some(_) => {}
+                None => models.push(candidate),
+            }
+        }
+    }
+
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
+
+fn is_model(dir: &Path) -> bool {
+    dir.join("model.config").is_file()
+}
+
+/// Whether version `a` is strictly newer than `b`, a missing version being the oldest
+pub fn is_newer(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => compare_versions(a, b) == Ordering::Greater,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod find_models_tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_newest_models() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("import");
+        let sources = vec![
+            dir.join("gazebo_models"),
+            dir.join("does_not_exist"),
+            dir.join("user_models"),
+        ];
+
+        assert_eq!(
+            find_models(&sources)?,
+            vec![
+                ModelSource {
+                    name: String::from("box"),
+                    dir: dir.join("user_models").join("box"),
+                    version: Some(String::from("2.0")),
+                },
+                ModelSource {
+                    name: String::from("sphere"),
+                    dir: dir.join("gazebo_models").join("sphere"),
+                    version: Some(String::from("1.0")),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod is_newer_tests {
+    use super::*;
+
+    #[test]
+    fn it_treats_missing_versions_as_oldest() {
+        let v = |s: &str| Some(String::from(s));
+
+        assert!(is_newer(&v("1.1"), &v("1.0")));
+        assert!(!is_newer(&v("1.0"), &v("1.0")));
+        assert!(is_newer(&v("0.1"), &None));
+        assert!(!is_newer(&None, &None));
+    }
+}
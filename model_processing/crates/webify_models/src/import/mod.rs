@@ -0,0 +1,10 @@
+//! Gathers models from the local model paths (e.g. `GAZEBO_MODEL_PATH`) into the
+//! assets directory, replacing what get_local_models.py used to do
+
+mod copy_dir;
+mod find_models;
+mod process;
+
+pub use self::copy_dir::copy_dir;
+pub use self::find_models::{find_models, is_newer};
+pub use self::process::process;
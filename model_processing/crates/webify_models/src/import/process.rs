@@ -0,0 +1,150 @@
+//! Orchestrator to copy the models from the source directories into the assets directory
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+
+use crate::import::{copy_dir, find_models, is_newer};
+use crate::model_config::read_model_config;
+use crate::progress::{create_progress_bar, print_message};
+use crate::report::FileAction;
+
+/// Orchestrator to copy the newest version of every model found in `sources` into
+/// `assets_dir`. Models already in the assets are only replaced by newer versions.
+pub fn process(assets_dir: &Path, sources: &[PathBuf]) -> std::result::Result<(), std::io::Error> {
+    print_message(format!(
+        "\nLooking for models in {} source(s)...",
+        sources.len()
+    ));
+    let models = find_models(sources)?;
+    print_message(format!(
+        "Models found: {}\n",
+        style(models.len()).bold().blue()
+    ));
+
+    let import_bar = create_progress_bar(models.len() as u64);
+    import_bar.set_prefix("Model Import");
+
+    let mut imported = 0;
+    for model in models {
+        import_bar.inc(1);
+
+        let destination = assets_dir.join(&model.name);
+        if destination.exists() {
+            let existing = read_model_config(&destination)
+                .ok()
+                .and_then(|config| config.version);
+            if !is_newer(&model.version, &existing) {
+                continue;
+            }
+
+            import_bar.println(format!(
+                "{} {} {} with {}",
+                style("Replacing").yellow(),
+                model.name,
+                existing.as_deref().unwrap_or("(no version)"),
+                model.version.as_deref().unwrap_or("(no version)")
+            ));
+            let remove_action = FileAction::start("remove", &destination);
+            match fs::remove_dir_all(&destination) {
+                Ok(()) => remove_action.finish(&destination),
+                Err(e) => {
+                    remove_action.fail(&e);
+                    return Err(e);
+                }
+            }
+        }
+
+        import_bar.set_message(&format!("Copying {:?}...", &model.dir));
+        copy_dir(&model.dir, &destination)?;
+        imported += 1;
+    }
+    import_bar.finish_with_message(&format!("{} model(s) imported", imported));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::context::RunContext;
+    use crate::report::Recorder;
+
+    fn setup(test_run_id: &str) -> std::result::Result<PathBuf, std::io::Error> {
+        let example_path = Path::new("tests").join("import");
+        let destination_path = example_path.join(test_run_id);
+
+        // An older sphere is already in the assets, and a newer box
+        copy_dir(
+            &example_path.join("gazebo_models").join("sphere"),
+            &destination_path.join("sphere"),
+        )?;
+        copy_dir(
+            &example_path.join("user_models").join("box"),
+            &destination_path.join("box"),
+        )?;
+        fs::write(
+            destination_path.join("sphere").join("model.config"),
+            "<model><name>Sphere</name><version>0.9</version></model>",
+        )?;
+
+        Ok(destination_path)
+    }
+
+    fn teardown(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let destination_path = Path::new("tests").join("import").join(test_run_id);
+        fs::remove_dir_all(destination_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_only_replaces_older_models() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "test_run_it_only_replaces_older_models";
+        let assets_dir = setup(test_run_id)?;
+        fs::write(assets_dir.join("box").join("webified.txt"), "")?;
+
+        let sources = vec![Path::new("tests").join("import").join("gazebo_models")];
+        process(&assets_dir, &sources)?;
+
+        let version = |model: &str| read_model_config(&assets_dir.join(model)).unwrap().version;
+        assert_eq!(version("sphere"), Some(String::from("1.0")));
+        assert_eq!(version("box"), Some(String::from("2.0")));
+        assert!(assets_dir.join("box").join("webified.txt").is_file());
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_records_what_it_replaces() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "test_run_it_records_what_it_replaces";
+        let assets_dir = setup(test_run_id)?;
+        let recorder = Recorder::default();
+        let context = RunContext {
+            recorder: Some(recorder.clone()),
+            ..RunContext::default()
+        };
+
+        let _context = context.enter_dir(&assets_dir);
+        let sources = vec![Path::new("tests").join("import").join("gazebo_models")];
+        process(&assets_dir, &sources)?;
+
+        // The older sphere is removed, then each file of the newer one is copied
+        let actions = recorder.take();
+        assert_eq!(actions[0].action, "remove");
+        assert_eq!(actions[0].source, assets_dir.join("sphere"));
+        assert_eq!(actions.len(), 3);
+        for action in &actions[1..] {
+            assert_eq!(action.action, "copy");
+            let destination = action.destination.as_ref().unwrap();
+            assert!(destination.starts_with(assets_dir.join("sphere")));
+        }
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+//! Gather what the web client needs to list a single model
+
+use std::{fs, io::Error, path::Path};
+
+use serde::Serialize;
+
+use crate::filter::is_excluded;
+use crate::image_processing::scan_dir_for_images::TEXTURE_IMAGE_TYPES;
+use crate::model_config::read_model_config;
+use crate::stats::{collect_mesh_stats, BoundingBox};
+use crate::walk::{walk_all_assets, AssetKind};
+
+/// Entry of a model in the index. Paths are relative to the assets directory, with `/`
+/// separators.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelEntry {
+    /// Name from `model.config`
+    pub name: String,
+    /// Directory of the model, which `model://` URIs refer to
+    pub path: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub version: Option<String>,
+    pub sdf: Vec<String>,
+    pub meshes: Vec<String>,
+    pub textures: Vec<String>,
+    /// Size of all the files of the model, in bytes
+    pub size: u64,
+    /// Bounds of the DAE meshes, in their own units
+    pub bounding_box: Option<BoundingBox>,
+    pub thumbnail: Option<String>,
+}
+
+/// Read the `model.config` of a model and list its files
+pub fn collect_model_entry(model_dir: &Path, assets_dir: &Path) -> Result<ModelEntry, Error> {
+    let config = read_model_config(model_dir)?;
+    let assets = walk_all_assets(model_dir)?;
+
+    let mut entry = ModelEntry {
+        name: config.name,
+        path: asset_path(model_dir, assets_dir),
+        description: config.description,
+        authors: config.authors,
+        version: config.version,
+        sdf: config
+            .sdf
+            .iter()
+            .map(|sdf| asset_path(&model_dir.join(sdf), assets_dir))
+            .collect(),
+        meshes: Vec::new(),
+        textures: Vec::new(),
+        size: 0,
+        bounding_box: None,
+        thumbnail: None,
+    };
+
+    let thumbnails_dir = model_dir.join("thumbnails");
+    for asset in assets {
+        let file = asset.path;
+        entry.size += fs::metadata(&file)?.len();
+        // Thumbnails are excluded from the scans, but that's where the index finds them
+        if is_excluded(assets_dir, &file) && !file.starts_with(&thumbnails_dir) {
+            continue;
+        }
+
+        let extension = file
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if asset.kind.is_mesh() {
+            if asset.kind == AssetKind::Collada {
+                let bounds = collect_mesh_stats(&file, model_dir)
+                    .ok()
+                    .and_then(|stats| stats.bounds);
+                entry.bounding_box = match (entry.bounding_box, bounds) {
+                    (Some(a), Some(b)) => Some(a.merge(&b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            entry.meshes.push(asset_path(&file, assets_dir));
+        } else if file.starts_with(&thumbnails_dir) {
+            if file.file_name().is_some_and(|name| name == "0.png") {
+                entry.thumbnail = Some(asset_path(&file, assets_dir));
+            }
+        } else if TEXTURE_IMAGE_TYPES.contains(&extension.as_str()) {
+            entry.textures.push(asset_path(&file, assets_dir));
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Path relative to the assets directory, with `/` separators like in URLs
+fn asset_path(path: &Path, assets_dir: &Path) -> String {
+    path.strip_prefix(assets_dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod collect_model_entry_tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_the_model() -> Result<(), Error> {
+        let assets_dir = Path::new("tests").join("index");
+        let entry = collect_model_entry(&assets_dir.join("cube"), &assets_dir)?;
+
+        assert_eq!(entry.name, "Cube");
+        assert_eq!(entry.path, "cube");
+        assert_eq!(entry.authors, vec!["Jane Doe <jane@example.com>"]);
+        assert_eq!(entry.sdf, vec!["cube/model.sdf"]);
+        assert_eq!(entry.meshes, vec!["cube/meshes/cube.dae"]);
+        assert_eq!(
+            entry.textures,
+            vec!["cube/materials/textures/cube_diffuse.png"]
+        );
+        assert_eq!(entry.thumbnail.as_deref(), Some("cube/thumbnails/0.png"));
+        assert_eq!(entry.bounding_box.unwrap().max[1], 11.0);
+        assert!(entry.size > fs::metadata(assets_dir.join("cube/meshes/cube.dae"))?.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+//! Writes an `index.json` listing every model in the assets directory, so the web
+//! client can build its model menus from data
+
+mod collect_model_entry;
+mod process;
+
+pub use self::collect_model_entry::{collect_model_entry, ModelEntry};
+pub use self::process::process;
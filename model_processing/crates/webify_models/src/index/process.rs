@@ -0,0 +1,76 @@
+//! Orchestrator to write the `index.json` of an assets directory
+
+use std::{fs, path::Path};
+
+use console::style;
+use tracing::info_span;
+
+use crate::index::{collect_model_entry, ModelEntry};
+use crate::model_config::list_model_dirs;
+use crate::progress::create_progress_bar;
+use crate::report::FileAction;
+
+/// Orchestrator to list every model directly inside the assets directory, i.e. the
+/// directories with a `model.config`, in an `index.json` at its root
+pub fn process(assets_dir: &Path) -> std::result::Result<(), std::io::Error> {
+    let model_dirs = list_model_dirs(assets_dir)?;
+
+    let index_bar = create_progress_bar(model_dirs.len() as u64);
+    index_bar.set_prefix("Model Index");
+
+    let mut models: Vec<ModelEntry> = Vec::new();
+    for model_dir in model_dirs {
+        let name = model_dir.file_name().unwrap_or_default().to_string_lossy();
+        let _model = info_span!("model", name = %name).entered();
+        index_bar.inc(1);
+        index_bar.set_message(&format!("Indexing {:?}...", &model_dir));
+
+        match collect_model_entry(&model_dir, assets_dir) {
+            Ok(entry) => models.push(entry),
+            Err(e) => {
+                index_bar.println(format!("{} {:?}: {}", style("Skipped").red(), model_dir, e));
+                FileAction::start("index", &model_dir).fail(e);
+            }
+        }
+    }
+
+    let index_action = FileAction::start("write index", assets_dir);
+    fs::write(
+        assets_dir.join("index.json"),
+        serde_json::to_string_pretty(&models)?,
+    )?;
+    index_action.finish(&assets_dir.join("index.json"));
+    index_bar.finish_with_message(&format!("Indexed {} model(s)", models.len()));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::import::copy_dir;
+
+    #[test]
+    fn it_writes_the_index() -> std::result::Result<(), std::io::Error> {
+        let assets_dir = Path::new("tests")
+            .join("index")
+            .join("test_run_it_writes_the_index");
+        copy_dir(
+            &Path::new("tests").join("index").join("cube"),
+            &assets_dir.join("cube"),
+        )?;
+        fs::create_dir_all(assets_dir.join("not_a_model"))?;
+
+        process(&assets_dir)?;
+
+        let index: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(assets_dir.join("index.json"))?)?;
+        assert_eq!(index.as_array().unwrap().len(), 1);
+        assert_eq!(index[0]["name"], "Cube");
+        assert_eq!(index[0]["version"], "1.0");
+        assert_eq!(index[0]["thumbnail"], "cube/thumbnails/0.png");
+
+        fs::remove_dir_all(assets_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+//! Webify_models is a script to convert Gazebo models to a format that
+//! that is friendlier for the web, mainly, to use PNGs and to move the
+//! texture files in consistent paths. To be honest, I'm not entirely sure
+//! this is necessary as browsers render pretty much everything nowadays,
+//! but hey, I'm not going to shake the tree too much before I fully understand
+//! the purpose of all these things are in somebody else's project that I'm rewriting.
+//!
+//! The [`Pipeline`] runs everything the `webify_models` command does, and the scanners,
+//! converters and report types are there to build other tooling on.
+
+pub mod archive;
+pub mod cache;
+pub mod check;
+pub mod collada;
+pub mod compress;
+pub mod config;
+pub mod context;
+pub mod filter;
+pub mod image_processing;
+pub mod import;
+pub mod index;
+pub mod logging;
+pub mod materials;
+pub mod mesh_update;
+pub mod model_config;
+pub mod pipeline;
+pub mod progress;
+pub mod prune;
+pub mod references;
+pub mod report;
+pub mod serve;
+pub mod stats;
+pub mod thumbnails;
+pub mod validate;
+pub mod walk;
+pub mod watch;
+
+pub use crate::config::{load_config, Config, Settings};
+pub use crate::context::RunContext;
+pub use crate::filter::PathFilter;
+pub use crate::image_processing::{
+    convert_to_png, move_to_textures_dir, scan_dir_for_image_types, scan_dir_for_images, Image,
+};
+pub use crate::materials::scan_dir_for_material_scripts;
+pub use crate::mesh_update::{rename_image_references, scan_dir_for_meshes};
+pub use crate::pipeline::Pipeline;
+pub use crate::progress::ProgressMode;
+pub use crate::report::{write_report, FileAction, Recorder};
+pub use crate::walk::{walk_all_assets, walk_assets, Asset, AssetKind};
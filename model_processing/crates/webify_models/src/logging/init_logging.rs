@@ -0,0 +1,55 @@
+//! Set up where the log records of the run go
+
+use std::{
+    cmp::{max, min},
+    fs::File,
+    path::Path,
+    sync::Mutex,
+};
+
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    fmt,
+    prelude::*,
+};
+
+/// Least verbose level of the log file, which is there to find out what happened to a
+/// file after the fact
+const LOG_FILE_LEVEL: LevelFilter = LevelFilter::DEBUG;
+
+/// Write the records of `level` and up to stderr, leaving stdout to the progress, and
+/// every record of the debug level and up, with the model and stage they're in, to
+/// `log_file` when there's one
+pub fn init_logging(
+    level: LevelFilter,
+    log_file: Option<&Path>,
+) -> std::result::Result<(), std::io::Error> {
+    let terminal = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(console::colors_enabled_stderr())
+        .with_target(false)
+        .with_filter(targets(level));
+    let file = match log_file {
+        Some(log_file) => Some(
+            fmt::layer()
+                .with_writer(Mutex::new(File::create(log_file)?))
+                .with_ansi(false)
+                .with_filter(targets(max(level, LOG_FILE_LEVEL))),
+        ),
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(terminal)
+        .with(file)
+        .try_init()
+        .map_err(std::io::Error::other)
+}
+
+/// Let the records of this crate through at `level`, but keep the chatter of the
+/// dependencies down to their warnings
+fn targets(level: LevelFilter) -> Targets {
+    Targets::new()
+        .with_default(min(level, LevelFilter::WARN))
+        .with_target(env!("CARGO_CRATE_NAME"), level)
+}
@@ -0,0 +1,8 @@
+//! Leveled logging of what every stage does to which file, on the terminal and in an
+//! optional log file, so there's a record left once the progress bars are gone
+
+mod init_logging;
+mod model_span;
+
+pub use self::init_logging::init_logging;
+pub use self::model_span::model_span;
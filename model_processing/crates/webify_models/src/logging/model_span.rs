@@ -0,0 +1,14 @@
+//! Span grouping the log records of a single model
+
+use std::path::Path;
+
+use tracing::{info_span, Span};
+
+use crate::model_config::model_name;
+
+/// Span of the model a file of the models directory `dir` is in, for the records of what
+/// is done to the file
+pub fn model_span(dir: &Path, path: &Path) -> Span {
+    let name = model_name(dir, path);
+    info_span!("model", name = name.as_deref().unwrap_or("(top level)"))
+}
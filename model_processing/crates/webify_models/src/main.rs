@@ -1,33 +1,279 @@
-//! Webify_models is a script to convert Gazebo models to a format that
-//! that is friendlier for the web, mainly, to use PNGs and to move the
-//! texture files in consistent paths. To be honest, I'm not entirely sure
-//! this is necessary as browsers render pretty much everything nowadays,
-//! but hey, I'm not going to shake the tree too much before I fully understand
-//! the purpose of all these things are in somebody else's project that I'm rewriting.
+//! Command-line interface of webify_models, see the README for its commands and options
+
+mod cli;
 
 use std::env;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
 use std::process::exit;
+use std::time::SystemTime;
 
 use console::style;
 
-mod cli;
-mod image_processing;
-mod mesh_update;
+use webify_models::{
+    archive, check, compress, config, filter, import, index, logging, materials, progress, prune,
+    references, report, serve, stats, thumbnails, validate, watch, Pipeline, ProgressMode,
+    Recorder, RunContext,
+};
 
-fn main() -> std::result::Result<(), std::io::Error> {
-    println!("{}", style("Roboverse").underlined().bold().white());
+/// Address `serve` listens on when there's no `--host`, so the models aren't shared with
+/// the network unless asked to
+const DEFAULT_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// Port `serve` listens on when there's no `--port`, the same as gzbridge's `server.js`
+const DEFAULT_PORT: u16 = 8080;
 
+fn main() -> std::result::Result<(), std::io::Error> {
     let args: Vec<String> = env::args().collect();
-    let path = match cli::parse_args_for_path(&args) {
+    logging::init_logging(
+        cli::parse_args_for_verbosity(&args),
+        cli::parse_args_for_option(&args, "--log-file").map(Path::new),
+    )?;
+    let mut context = RunContext {
+        progress: parse_args_for_value(&args, "--progress", ProgressMode::Auto)?.resolve(),
+        ..RunContext::default()
+    };
+    // Colors are left out of anything but progress bars, so logs and JSON don't get
+    // escape codes
+    if context.progress != ProgressMode::Bar {
+        console::set_colors_enabled(false);
+    }
+    let _progress = context.enter();
+    progress::print_message(style("Roboverse").underlined().bold().white());
+
+    let input = match cli::parse_args_for_path(&args) {
         Ok(p) => p,
         Err(e) => {
-            println!("{}", e);
+            progress::print_error(e, None);
             exit(1)
         }
     };
 
-    image_processing::process(path)?;
-    mesh_update::process(path)?;
+    let command = cli::parse_args_for_command(&args);
+    let output = cli::parse_args_for_option(&args, "--output").map(Path::new);
+    let report_path = cli::parse_args_for_option(&args, "--report").map(Path::new);
+    let started = SystemTime::now();
+    let recorder = Recorder::default();
+    if report_path.is_some() {
+        context.recorder = Some(recorder.clone());
+    }
+
+    // Watching leaves the sources alone and keeps writing to its own output directory
+    if command == cli::Command::Watch {
+        if !input.is_dir() {
+            progress::print_error("Only directories can be watched.", None);
+            exit(1)
+        }
+        let output_dir = match output {
+            Some(output) => output.to_path_buf(),
+            None => input.with_file_name(format!(
+                "{}_webified",
+                input.file_name().unwrap_or_default().to_string_lossy()
+            )),
+        };
+        context.filter = scan_filter(input, &args)?;
+        let _context = context.enter_dir(input);
+        let pipeline = build_pipeline(config::load_config(input)?, &args, &context)?;
+
+        return watch::process(
+            input,
+            &output_dir,
+            |dir| pipeline.convert(dir).map(|_| ()),
+            |dir| pipeline.finish(dir).map(|_| ()),
+        );
+    }
+
+    // Archives, and directories given an --output, are worked on in a fresh copy
+    let workspace = archive::open_workspace(input, output, command.is_read_only(&args))?;
+    let path = workspace.root();
+    context.filter = scan_filter(path, &args)?;
+    let _context = context.enter_dir(path);
+
+    // The report is written even when the command fails, which is when it's needed most
+    let result = run_command(command, path, &args, &context);
+    if let Some(report_path) = report_path {
+        let error = result.as_ref().err().map(ToString::to_string);
+        report::write_report(report_path, path, &args, started, recorder.take(), error)?;
+    }
+    let failed = result?;
+    workspace.finish()?;
+
+    if failed {
+        exit(1)
+    }
 
     Ok(())
 }
+
+/// Run a command, other than watching, on the models directory `path`, and return
+/// whether to exit with an error once the report is written and the workspace packed
+fn run_command(
+    command: cli::Command,
+    path: &Path,
+    args: &[String],
+    context: &RunContext,
+) -> std::result::Result<bool, std::io::Error> {
+    let config = config::load_config(path)?;
+
+    let mut failed = false;
+    match command {
+        cli::Command::Process => {
+            build_pipeline(config, args, context)?.run(path)?;
+        }
+        cli::Command::Import => {
+            let sources = cli::parse_args_for_sources(args);
+            if sources.is_empty() {
+                progress::print_warning(
+                    "No directories to import from, only webifying the assets.",
+                    None,
+                );
+            } else {
+                import::process(path, &sources)?;
+            }
+            // Imported models can come with their own webify.toml
+            build_pipeline(config::load_config(path)?, args, context)?.run(path)?;
+        }
+        cli::Command::Stats => {
+            let json_path = cli::parse_args_for_option(args, "--json").map(Path::new);
+
+            stats::process(path, json_path)?;
+        }
+        cli::Command::Validate => {
+            let repair = cli::parse_args_for_flag(args, "--repair");
+
+            failed = validate::process(path, repair)? > 0;
+        }
+        cli::Command::Check => {
+            let settings = config.global();
+            let default = check::Budget {
+                max_texture_size: settings.max_texture_size,
+                max_triangles: settings.max_triangles,
+            };
+            let budget = check::Budget {
+                max_texture_size: parse_args_for_value(
+                    args,
+                    "--max-texture-size",
+                    default.max_texture_size,
+                )?,
+                max_triangles: parse_args_for_value(
+                    args,
+                    "--max-triangles",
+                    default.max_triangles,
+                )?,
+            };
+
+            let texture_types = config.texture_types();
+            let texture_types: Vec<&str> = texture_types.iter().map(String::as_str).collect();
+
+            failed = check::process(path, &budget, &texture_types)? > 0;
+        }
+        cli::Command::References => {
+            let search_roots = cli::parse_args_for_sources(args);
+
+            failed = references::process(path, &search_roots)? > 0;
+        }
+        cli::Command::Prune => {
+            let search_roots = cli::parse_args_for_sources(args);
+            let apply = cli::parse_args_for_flag(args, "--apply");
+
+            prune::process(path, &search_roots, apply)?;
+        }
+        cli::Command::Materials => {
+            materials::process(path)?;
+        }
+        cli::Command::Index => {
+            index::process(path)?;
+        }
+        cli::Command::Compress => {
+            compress::process(
+                path,
+                parse_args_for_value(args, "--min-size", compress::DEFAULT_MIN_SIZE)?,
+            )?;
+        }
+        cli::Command::Thumbnails => match cli::parse_args_for_action(args) {
+            Some("postprocess") => thumbnails::postprocess(path)?,
+            _ => thumbnails::process(path)?,
+        },
+        cli::Command::Serve => {
+            serve::process(
+                path,
+                parse_args_for_value(args, "--host", DEFAULT_HOST)?,
+                parse_args_for_value(args, "--port", DEFAULT_PORT)?,
+            )?;
+        }
+        cli::Command::Watch => unreachable!("watch is handled before opening a workspace"),
+    }
+
+    Ok(failed)
+}
+
+/// The filter leaving out of every scan what `--exclude` and the `.webifyignore` of the
+/// models directory exclude, and keeping only what `--include` includes when given
+fn scan_filter(
+    dir: &Path,
+    args: &[String],
+) -> std::result::Result<filter::PathFilter, std::io::Error> {
+    let include: Vec<String> = cli::parse_args_for_options(args, "--include")
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut exclude = filter::read_ignore_file(dir)?;
+    exclude.extend(
+        cli::parse_args_for_options(args, "--exclude")
+            .into_iter()
+            .map(String::from),
+    );
+
+    filter::PathFilter::new(&include, &exclude)
+}
+
+/// The webify pipeline with the settings of `config` and the options of the command line,
+/// skipping the models that are already in the `--cache` directory when one is given, and
+/// scanning, showing progress and recording like the rest of the run in `context`
+fn build_pipeline(
+    config: config::Config,
+    args: &[String],
+    context: &RunContext,
+) -> std::result::Result<Pipeline, std::io::Error> {
+    let compress = cli::parse_args_for_flag(args, "--compress") || config.global().stages.compress;
+    let mut pipeline = Pipeline::new(config)
+        .normalize(cli::parse_args_for_flag(args, "--normalize"))
+        .filter(context.filter.clone())
+        .progress(context.progress);
+    if let Some(recorder) = &context.recorder {
+        pipeline = pipeline.record(recorder);
+    }
+    if compress {
+        pipeline = pipeline.compress(parse_args_for_value(
+            args,
+            "--min-size",
+            compress::DEFAULT_MIN_SIZE,
+        )?);
+    }
+    if let Some(cache_dir) = cli::parse_args_for_option(args, "--cache") {
+        pipeline = pipeline.cache(Path::new(cache_dir));
+    }
+
+    Ok(pipeline)
+}
+
+/// The value of an option, like a number, or `default` without it, failing when it can't
+/// be parsed
+fn parse_args_for_value<T>(
+    args: &[String],
+    option: &str,
+    default: T,
+) -> std::result::Result<T, std::io::Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match cli::parse_args_for_option(args, option).map(str::parse) {
+        None => Ok(default),
+        Some(Ok(value)) => Ok(value),
+        Some(Err(e)) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid {}: {}", option, e),
+        )),
+    }
+}
@@ -0,0 +1,74 @@
+//! Standalone glTF document holding only materials, their textures and images
+
+use serde_json::{json, Value};
+
+use crate::materials::GltfMaterial;
+
+/// Build a glTF document with the specified materials. Texture paths are relative to
+/// the assets directory, so `uri_prefix` leads from the document back to it, e.g. `../`
+/// for a document at the root of a model.
+pub fn gltf_document(materials: &[GltfMaterial], uri_prefix: &str) -> Value {
+    let mut images: Vec<String> = Vec::new();
+    let mut texture_index = |texture: &str| match images.iter().position(|i| i == texture) {
+        Some(index) => index,
+        None => {
+            images.push(String::from(texture));
+            images.len() - 1
+        }
+    };
+    let materials: Vec<Value> = materials
+        .iter()
+        .map(|material| material.to_json(&mut texture_index))
+        .collect();
+
+    let mut document = json!({
+        "asset": { "version": "2.0", "generator": "webify_models" },
+        "materials": materials,
+    });
+    if !images.is_empty() {
+        document["textures"] = (0..images.len())
+            .map(|index| json!({ "source": index }))
+            .collect();
+        document["images"] = images
+            .iter()
+            .map(|image| json!({ "uri": format!("{}{}", uri_prefix, image) }))
+            .collect();
+    }
+    if materials.iter().any(|material| {
+        material["pbrMetallicRoughness"]["baseColorTexture"]
+            .get("extensions")
+            .is_some()
+    }) {
+        document["extensionsUsed"] = json!(["KHR_texture_transform"]);
+    }
+
+    document
+}
+
+#[cfg(test)]
+mod gltf_document_tests {
+    use super::*;
+    use crate::materials::OgreMaterial;
+
+    #[test]
+    fn it_shares_images_between_materials() {
+        let ogre = OgreMaterial {
+            texture: Some(String::from("box/materials/textures/box.png")),
+            scale: Some(vec![2.0, 2.0]),
+            ..OgreMaterial::default()
+        };
+        let materials = vec![
+            GltfMaterial::from_ogre("A", &ogre),
+            GltfMaterial::from_ogre("B", &ogre),
+        ];
+        let document = gltf_document(&materials, "../");
+
+        assert_eq!(document["materials"].as_array().unwrap().len(), 2);
+        assert_eq!(document["textures"], json!([{ "source": 0 }]));
+        assert_eq!(
+            document["images"],
+            json!([{ "uri": "../box/materials/textures/box.png" }])
+        );
+        assert_eq!(document["extensionsUsed"], json!(["KHR_texture_transform"]));
+    }
+}
@@ -0,0 +1,311 @@
+//! glTF `pbrMetallicRoughness` material converted from an OGRE or SDF material.
+//!
+//! OGRE passes are fixed-function Blinn-Phong, so the conversion is an approximation:
+//!
+//! - The base color is the diffuse color, or white when there's only a texture. Its
+//!   alpha is multiplied by the texture unit's opacity.
+//! - Ambient is dropped, glTF has no ambient term.
+//! - Fixed-function materials aren't metals, so metallic is 0.
+//! - Roughness comes from the specular shininess (the 5th `specular` value) with the
+//!   usual Blinn-Phong exponent to GGX conversion, `(2 / (shininess + 2))^(1/4)`. A
+//!   black specular is fully rough.
+//! - Any `scene_blend` other than `replace`, an opacity or a diffuse alpha under 1
+//!   blend. glTF has no additive or modulative blending, so those blend too.
+//! - `alpha_rejection greater` masks, with its value over 255 as the cutoff.
+//! - `cull_hardware none` is double sided.
+//! - The texture unit `scale` becomes a `KHR_texture_transform`, inverted because OGRE
+//!   scales the texture and glTF the texture coordinates.
+//!
+//! SDF materials start from the OGRE material in their `<script>`, with their own
+//! colors on top. A `<pbr><metal>` block then sets the textures and factors directly:
+//!
+//! - `albedo_map` is the base color texture and `normal_map` the normal texture.
+//! - `emissive_map` is the emissive texture, with a white emissive factor when there
+//!   is no emissive color.
+//! - glTF reads roughness from the green channel and metalness from the blue channel
+//!   of a single texture. A grayscale `roughness_map` works as is. A `metalness_map`
+//!   is only used when it's the same file, otherwise `metalness` is used as a factor,
+//!   which the roughness map's blue channel also scales.
+
+use serde_json::{json, Map, Value};
+
+use crate::materials::{OgreMaterial, SdfMaterial};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    /// Alpha cutoff from 0 to 1
+    Mask(f64),
+    Blend,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: String,
+    pub base_color_factor: [f64; 4],
+    /// Texture paths are relative to the assets directory
+    pub base_color_texture: Option<String>,
+    /// Texture coordinate scale of the base color texture
+    pub texture_scale: Option<[f64; 2]>,
+    pub metallic_factor: f64,
+    pub roughness_factor: f64,
+    pub metallic_roughness_texture: Option<String>,
+    pub normal_texture: Option<String>,
+    pub emissive_factor: [f64; 3],
+    pub emissive_texture: Option<String>,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+}
+
+impl GltfMaterial {
+    /// Convert an OGRE material, see the module documentation for the heuristics
+    pub fn from_ogre(name: &str, ogre: &OgreMaterial) -> GltfMaterial {
+        let mut base_color_factor = ogre
+            .diffuse
+            .as_deref()
+            .map(color)
+            .unwrap_or([1.0, 1.0, 1.0, 1.0]);
+        base_color_factor[3] *= ogre.opacity.unwrap_or(1.0);
+
+        let roughness_factor = match ogre.specular.as_deref() {
+            Some(specular) if specular.iter().take(3).any(|&c| c > 0.0) => {
+                let shininess = specular.get(4).copied().unwrap_or(0.0).max(0.0);
+                (2.0 / (shininess + 2.0)).powf(0.25)
+            }
+            _ => 1.0,
+        };
+
+        let blends = ogre
+            .scene_blend
+            .as_deref()
+            .is_some_and(|blend| blend != "replace" && blend != "one zero");
+        let alpha_mode = if blends || base_color_factor[3] < 1.0 {
+            AlphaMode::Blend
+        } else if let Some(rejection) = ogre.alpha_rejection {
+            AlphaMode::Mask(rejection / 255.0)
+        } else {
+            AlphaMode::Opaque
+        };
+
+        let emissive = ogre.emissive.as_deref().map(color).unwrap_or_default();
+
+        GltfMaterial {
+            name: String::from(name),
+            base_color_factor,
+            base_color_texture: ogre.texture.clone(),
+            texture_scale: ogre.scale.as_deref().map(|scale| {
+                let u = scale.first().copied().unwrap_or(1.0);
+                let v = scale.get(1).copied().unwrap_or(u);
+                [inverse(u), inverse(v)]
+            }),
+            metallic_factor: 0.0,
+            roughness_factor,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            emissive_factor: [emissive[0], emissive[1], emissive[2]],
+            emissive_texture: None,
+            alpha_mode,
+            double_sided: ogre.cull_hardware.as_deref() == Some("none"),
+        }
+    }
+
+    /// Convert an SDF material, with `script` being the OGRE material it names, if any
+    pub fn from_sdf(name: &str, sdf: &SdfMaterial, script: Option<&OgreMaterial>) -> GltfMaterial {
+        let colors = OgreMaterial {
+            ambient: sdf.ambient.clone(),
+            diffuse: sdf.diffuse.clone(),
+            specular: sdf.specular.clone(),
+            emissive: sdf.emissive.clone(),
+            ..OgreMaterial::default()
+        };
+        let colors = match script {
+            Some(script) => colors.inherit(script),
+            None => colors,
+        };
+        let mut material = GltfMaterial::from_ogre(name, &colors);
+
+        if let Some(metal) = &sdf.metal {
+            if metal.albedo_map.is_some() {
+                material.base_color_texture = metal.albedo_map.clone();
+                material.texture_scale = None;
+            }
+            material.metallic_factor = metal.metalness.unwrap_or(0.0);
+            material.roughness_factor = metal.roughness.unwrap_or(1.0);
+            material.metallic_roughness_texture = metal.roughness_map.clone();
+            if metal.metalness_map.is_some() && metal.metalness_map == metal.roughness_map {
+                material.metallic_factor = metal.metalness.unwrap_or(1.0);
+            }
+            material.normal_texture = metal.normal_map.clone();
+            if metal.emissive_map.is_some() {
+                material.emissive_texture = metal.emissive_map.clone();
+                if sdf.emissive.is_none() {
+                    material.emissive_factor = [1.0, 1.0, 1.0];
+                }
+            }
+        }
+
+        material
+    }
+
+    /// glTF JSON for this material, with `texture_index` returning the index in the
+    /// document's `textures` of a texture path
+    pub fn to_json(&self, texture_index: &mut dyn FnMut(&str) -> usize) -> Value {
+        let mut pbr = Map::new();
+        pbr.insert(
+            String::from("baseColorFactor"),
+            json!(self.base_color_factor),
+        );
+        if let Some(texture) = &self.base_color_texture {
+            let mut info = json!({ "index": texture_index(texture) });
+            if let Some(scale) = self.texture_scale {
+                info["extensions"] = json!({ "KHR_texture_transform": { "scale": scale } });
+            }
+            pbr.insert(String::from("baseColorTexture"), info);
+        }
+        pbr.insert(String::from("metallicFactor"), json!(self.metallic_factor));
+        pbr.insert(
+            String::from("roughnessFactor"),
+            json!(self.roughness_factor),
+        );
+        if let Some(texture) = &self.metallic_roughness_texture {
+            pbr.insert(
+                String::from("metallicRoughnessTexture"),
+                json!({ "index": texture_index(texture) }),
+            );
+        }
+
+        let mut material = json!({
+            "name": self.name,
+            "pbrMetallicRoughness": pbr,
+            "emissiveFactor": self.emissive_factor,
+        });
+        if let Some(texture) = &self.normal_texture {
+            material["normalTexture"] = json!({ "index": texture_index(texture) });
+        }
+        if let Some(texture) = &self.emissive_texture {
+            material["emissiveTexture"] = json!({ "index": texture_index(texture) });
+        }
+        match self.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask(cutoff) => {
+                material["alphaMode"] = json!("MASK");
+                material["alphaCutoff"] = json!(cutoff);
+            }
+            AlphaMode::Blend => material["alphaMode"] = json!("BLEND"),
+        }
+        if self.double_sided {
+            material["doubleSided"] = json!(true);
+        }
+
+        material
+    }
+}
+
+/// RGBA color from 3 or 4 values, clamped to [0, 1] as glTF requires
+fn color(values: &[f64]) -> [f64; 4] {
+    let mut rgba = [0.0, 0.0, 0.0, 1.0];
+    for (channel, value) in rgba.iter_mut().zip(values) {
+        *channel = value.clamp(0.0, 1.0);
+    }
+
+    rgba
+}
+
+fn inverse(scale: f64) -> f64 {
+    if scale == 0.0 {
+        1.0
+    } else {
+        1.0 / scale
+    }
+}
+
+#[cfg(test)]
+mod gltf_material_tests {
+    use super::*;
+    use crate::materials::sdf_material::PbrMetal;
+
+    #[test]
+    fn it_converts_ogre_materials() {
+        let ogre = OgreMaterial {
+            ambient: Some(vec![0.3, 0.3, 0.3, 1.0]),
+            diffuse: Some(vec![0.5, 0.5, 0.5, 1.0]),
+            specular: Some(vec![0.5, 0.5, 0.5, 1.0, 30.0]),
+            texture: Some(String::from("box/materials/textures/box.png")),
+            scale: Some(vec![0.5, 0.25]),
+            opacity: Some(0.5),
+            cull_hardware: Some(String::from("none")),
+            ..OgreMaterial::default()
+        };
+        let material = GltfMaterial::from_ogre("Box/Glass", &ogre);
+
+        assert_eq!(material.base_color_factor, [0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(material.texture_scale, Some([2.0, 4.0]));
+        assert_eq!(material.metallic_factor, 0.0);
+        assert!((material.roughness_factor - (2.0f64 / 32.0).powf(0.25)).abs() < 1e-12);
+        assert_eq!(material.alpha_mode, AlphaMode::Blend);
+        assert!(material.double_sided);
+    }
+
+    #[test]
+    fn it_masks_with_alpha_rejection() {
+        let ogre = OgreMaterial {
+            alpha_rejection: Some(127.5),
+            ..OgreMaterial::default()
+        };
+        let material = GltfMaterial::from_ogre("Leaves", &ogre);
+
+        assert_eq!(material.base_color_factor, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(material.roughness_factor, 1.0);
+        assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+    }
+
+    #[test]
+    fn it_converts_sdf_pbr_materials() {
+        let script = OgreMaterial {
+            diffuse: Some(vec![1.0, 0.0, 0.0, 1.0]),
+            emissive: Some(vec![0.1, 0.1, 0.1, 1.0]),
+            ..OgreMaterial::default()
+        };
+        let sdf = SdfMaterial {
+            script: Some(String::from("Gazebo/Red")),
+            diffuse: Some(vec![0.0, 1.0, 0.0, 1.0]),
+            metal: Some(PbrMetal {
+                albedo_map: Some(String::from("albedo.png")),
+                roughness_map: Some(String::from("rm.png")),
+                metalness_map: Some(String::from("rm.png")),
+                roughness: Some(0.5),
+                ..PbrMetal::default()
+            }),
+            ..SdfMaterial::default()
+        };
+        let material = GltfMaterial::from_sdf("box::link::visual", &sdf, Some(&script));
+
+        assert_eq!(material.base_color_factor, [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(material.emissive_factor, [0.1, 0.1, 0.1]);
+        assert_eq!(material.base_color_texture.as_deref(), Some("albedo.png"));
+        assert_eq!(
+            material.metallic_roughness_texture.as_deref(),
+            Some("rm.png")
+        );
+        assert_eq!(material.metallic_factor, 1.0);
+        assert_eq!(material.roughness_factor, 0.5);
+    }
+
+    #[test]
+    fn it_writes_gltf_json() {
+        let material = GltfMaterial::from_ogre(
+            "Leaves",
+            &OgreMaterial {
+                texture: Some(String::from("leaves.png")),
+                alpha_rejection: Some(51.0),
+                ..OgreMaterial::default()
+            },
+        );
+        let json = material.to_json(&mut |_| 3);
+
+        assert_eq!(json["pbrMetallicRoughness"]["baseColorTexture"]["index"], 3);
+        assert_eq!(json["alphaMode"], "MASK");
+        assert_eq!(json["alphaCutoff"], 0.2);
+        assert!(json.get("doubleSided").is_none());
+    }
+}
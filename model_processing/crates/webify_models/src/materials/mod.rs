@@ -0,0 +1,25 @@
+//! Parses the OGRE material scripts of every model into JSON, so the web client
+//! doesn't need to parse them at runtime, and converts them and the SDF materials
+//! to glTF PBR materials
+
+mod gltf_document;
+mod gltf_material;
+mod ogre_material;
+mod parse_material_script;
+mod process;
+mod resolve_texture;
+mod scan_dir_for_material_scripts;
+mod scan_dir_for_sdf_files;
+mod script_node;
+mod sdf_material;
+
+pub use self::gltf_document::gltf_document;
+pub use self::gltf_material::GltfMaterial;
+pub use self::ogre_material::OgreMaterial;
+pub use self::parse_material_script::parse_material_script;
+pub use self::process::process;
+pub use self::resolve_texture::resolve_texture;
+pub use self::scan_dir_for_material_scripts::scan_dir_for_material_scripts;
+pub use self::scan_dir_for_sdf_files::scan_dir_for_sdf_files;
+pub use self::script_node::ScriptNode;
+pub use self::sdf_material::{read_sdf_material, read_sdf_materials, SdfMaterial};
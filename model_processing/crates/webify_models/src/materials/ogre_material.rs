@@ -0,0 +1,196 @@
+//! Material properties the web client reads from an OGRE material, the same ones
+//! gzbridge's OgreMaterialParser used to send over
+
+use serde::{Deserialize, Serialize};
+
+use crate::materials::ScriptNode;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OgreMaterial {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ambient: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diffuse: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specular: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emissive: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_write: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_check: Option<bool>,
+    /// e.g. `alpha_blend`, or the two blend factors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_blend: Option<String>,
+    /// Alpha, from 0 to 255, fragments need to be above to be drawn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpha_rejection: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cull_hardware: Option<String>,
+    /// Texture path relative to the assets directory once resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+}
+
+impl OgreMaterial {
+    /// Read the first pass of the first technique of a `material` node. Returns None
+    /// when there is no pass, like the OGRE parser in gzbridge.
+    ///
+    /// Values that aren't numbers (e.g. `ambient vertexcolour`) are left out rather
+    /// than passed to the client as strings.
+    pub fn from_node(material: &ScriptNode) -> Option<OgreMaterial> {
+        let pass = material.find_child("technique")?.find_child("pass")?;
+        let mut result = OgreMaterial {
+            ambient: read_numbers(pass, "ambient"),
+            diffuse: read_numbers(pass, "diffuse"),
+            specular: read_numbers(pass, "specular"),
+            emissive: read_numbers(pass, "emissive")
+                .or_else(|| read_numbers(pass, "self_illumination")),
+            depth_write: read_switch(pass, "depth_write"),
+            depth_check: read_switch(pass, "depth_check"),
+            scene_blend: pass
+                .find_child("scene_blend")
+                .map(|blend| blend.values.join(" ")),
+            alpha_rejection: pass
+                .find_child("alpha_rejection")
+                .filter(|rejection| {
+                    rejection
+                        .values
+                        .first()
+                        .is_some_and(|function| function.starts_with("greater"))
+                })
+                .and_then(|rejection| rejection.values.get(1))
+                .and_then(|value| value.parse().ok()),
+            cull_hardware: pass
+                .find_child("cull_hardware")
+                .and_then(|cull| cull.values.first())
+                .cloned(),
+            ..OgreMaterial::default()
+        };
+
+        if let Some(texture_unit) = pass.find_child("texture_unit") {
+            result.texture = texture_unit
+                .find_child("texture")
+                .and_then(|texture| texture.values.first())
+                .cloned();
+            result.scale = read_numbers(texture_unit, "scale");
+            // Only the manual alpha source is supported, which is what Gazebo uses for
+            // transparency
+            result.opacity = texture_unit
+                .find_child("alpha_op_ex")
+                .filter(|alpha_op| alpha_op.values.get(1).is_some_and(|v| v == "src_manual"))
+                .and_then(|alpha_op| alpha_op.values.get(3))
+                .and_then(|value| value.parse().ok());
+        }
+
+        Some(result)
+    }
+
+    /// Fill the properties this material doesn't set from the material it inherits
+    /// from, as in `material Child : Parent`
+    pub fn inherit(self, parent: &OgreMaterial) -> OgreMaterial {
+        let parent = parent.clone();
+        OgreMaterial {
+            ambient: self.ambient.or(parent.ambient),
+            diffuse: self.diffuse.or(parent.diffuse),
+            specular: self.specular.or(parent.specular),
+            emissive: self.emissive.or(parent.emissive),
+            depth_write: self.depth_write.or(parent.depth_write),
+            depth_check: self.depth_check.or(parent.depth_check),
+            scene_blend: self.scene_blend.or(parent.scene_blend),
+            alpha_rejection: self.alpha_rejection.or(parent.alpha_rejection),
+            cull_hardware: self.cull_hardware.or(parent.cull_hardware),
+            texture: self.texture.or(parent.texture),
+            scale: self.scale.or(parent.scale),
+            opacity: self.opacity.or(parent.opacity),
+        }
+    }
+}
+
+/// Values of a child node when they are all numbers
+fn read_numbers(node: &ScriptNode, name: &str) -> Option<Vec<f64>> {
+    let values = &node.find_child(name)?.values;
+    if values.is_empty() {
+        return None;
+    }
+
+    values.iter().map(|value| value.parse().ok()).collect()
+}
+
+/// On/off value of a child node, anything but `off` being on
+fn read_switch(node: &ScriptNode, name: &str) -> Option<bool> {
+    node.find_child(name)
+        .map(|switch| switch.values.first().is_none_or(|value| value != "off"))
+}
+
+#[cfg(test)]
+mod ogre_material_tests {
+    use super::*;
+    use crate::materials::parse_material_script;
+
+    fn parse(script: &str) -> Option<OgreMaterial> {
+        OgreMaterial::from_node(&parse_material_script(script).unwrap()[0])
+    }
+
+    #[test]
+    fn it_reads_the_first_pass() {
+        let material = parse(
+            "material Glass {
+              technique {
+                pass {
+                  ambient .5 .5 .5 1
+                  diffuse vertexcolour
+                  depth_write off
+                  depth_check on
+                  scene_blend src_alpha one_minus_src_alpha
+                  alpha_rejection greater_equal 128
+                  cull_hardware none
+                  texture_unit {
+                    texture glass.jpg
+                    scale 0.5 2
+                    alpha_op_ex source1 src_manual src_current 0.4
+                  }
+                }
+                pass { specular 1 1 1 1 }
+              }
+            }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            material,
+            OgreMaterial {
+                ambient: Some(vec![0.5, 0.5, 0.5, 1.0]),
+                depth_write: Some(false),
+                depth_check: Some(true),
+                scene_blend: Some(String::from("src_alpha one_minus_src_alpha")),
+                alpha_rejection: Some(128.0),
+                cull_hardware: Some(String::from("none")),
+                texture: Some(String::from("glass.jpg")),
+                scale: Some(vec![0.5, 2.0]),
+                opacity: Some(0.4),
+                ..OgreMaterial::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_skips_materials_without_a_pass() {
+        assert_eq!(parse("material Empty { technique { } }"), None);
+    }
+
+    #[test]
+    fn it_inherits_unset_properties() {
+        let parent =
+            parse("material P { technique { pass {\nambient 1 0 0 1\ndiffuse 1 0 0 1\n} } }");
+        let child = parse("material C : P { technique { pass { diffuse 0 1 0 1 } } }");
+
+        let material = child.unwrap().inherit(&parent.unwrap());
+        assert_eq!(material.ambient, Some(vec![1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(material.diffuse, Some(vec![0.0, 1.0, 0.0, 1.0]));
+    }
+}
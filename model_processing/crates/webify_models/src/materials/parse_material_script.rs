@@ -0,0 +1,207 @@
+//! Parse an OGRE material script into a tree of nodes, a port of gzbridge's ConfigLoader
+
+use std::io::{Error, ErrorKind};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::materials::ScriptNode;
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Text(String),
+    NewLine,
+    OpenBrace,
+    CloseBrace,
+}
+
+/// Parse the text of a material script and return its root nodes, e.g. one
+/// `material Name` node per material with its techniques as children
+pub fn parse_material_script(script: &str) -> std::result::Result<Vec<ScriptNode>, Error> {
+    let tokens = tokenize(script);
+    let mut position = 0;
+
+    parse_nodes(&tokens, &mut position, 0)
+}
+
+/// Split a script in words, newlines and braces, dropping `//` and `/* */` comments
+fn tokenize(script: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = script.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            }
+            '\r' | '\n' => {
+                chars.next();
+                if tokens.last() != Some(&Token::NewLine) {
+                    tokens.push(Token::NewLine);
+                }
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::OpenBrace);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::CloseBrace);
+            }
+            '"' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Text(text));
+            }
+            '/' if starts_comment(&chars) => skip_comment(&mut chars),
+            _ => {
+                let mut text = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || starts_comment(&chars) {
+                        break;
+                    }
+                    text.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Text(text));
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Whether the next characters open a `//` or `/* */` comment
+fn starts_comment(chars: &Peekable<Chars>) -> bool {
+    let mut lookahead = chars.clone();
+    lookahead.next() == Some('/') && matches!(lookahead.next(), Some('/') | Some('*'))
+}
+
+/// Skip the comment starting at the current character. Line comments leave their
+/// newline in place so it still ends the current node.
+fn skip_comment(chars: &mut Peekable<Chars>) {
+    chars.next();
+    if chars.next() == Some('/') {
+        while chars.peek().is_some_and(|&c| c != '\r' && c != '\n') {
+            chars.next();
+        }
+    } else {
+        let mut previous = ' ';
+        for c in chars.by_ref() {
+            if previous == '*' && c == '/' {
+                break;
+            }
+            previous = c;
+        }
+    }
+}
+
+/// Parse nodes until the closing brace of the current block, or the end of the script
+/// at the root
+fn parse_nodes(
+    tokens: &[Token],
+    position: &mut usize,
+    depth: usize,
+) -> std::result::Result<Vec<ScriptNode>, Error> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.get(*position) {
+        *position += 1;
+        match token {
+            Token::Text(name) => {
+                let mut node = ScriptNode::new(name);
+                while let Some(Token::Text(value)) = tokens.get(*position) {
+                    node.values.push(value.clone());
+                    *position += 1;
+                }
+
+                let mut lookahead = *position;
+                while tokens.get(lookahead) == Some(&Token::NewLine) {
+                    lookahead += 1;
+                }
+                if tokens.get(lookahead) == Some(&Token::OpenBrace) {
+                    *position = lookahead + 1;
+                    node.children = parse_nodes(tokens, position, depth + 1)?;
+                }
+
+                nodes.push(node);
+            }
+            Token::NewLine => {}
+            Token::OpenBrace => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Found a block without a name",
+                ))
+            }
+            Token::CloseBrace if depth > 0 => return Ok(nodes),
+            Token::CloseBrace => {
+                return Err(Error::new(ErrorKind::InvalidData, "Found an unmatched }"))
+            }
+        }
+    }
+
+    if depth > 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "Missing a closing }"));
+    }
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod parse_material_script_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_nested_blocks() -> std::result::Result<(), Error> {
+        let script = "
+            material Gazebo/Red// a comment
+            {
+              technique
+              {
+                pass main
+                {
+                  /* ambient 0 0 0 1 */
+                  ambient 1 0 0 1
+                  texture_unit { texture \"red paint.jpg\" }
+                }
+              }
+            }";
+        let nodes = parse_material_script(script)?;
+
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].name, "material");
+        assert_eq!(nodes[0].values, vec!["Gazebo/Red"]);
+
+        let pass = nodes[0]
+            .find_child("technique")
+            .and_then(|technique| technique.find_child("pass"))
+            .unwrap();
+        assert_eq!(pass.values, vec!["main"]);
+        assert_eq!(pass.children.len(), 2);
+        assert_eq!(pass.children[0].values, vec!["1", "0", "0", "1"]);
+        assert_eq!(
+            pass.children[1].find_child("texture").unwrap().values,
+            vec!["red paint.jpg"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_parses_inheritance_and_imports() -> std::result::Result<(), Error> {
+        let script = "import * from \"base.material\"\nmaterial Child : Parent {}\n";
+        let nodes = parse_material_script(script)?;
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].name, "import");
+        assert_eq!(nodes[1].values, vec!["Child", ":", "Parent"]);
+        assert!(nodes[1].children.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_unbalanced_braces() {
+        assert!(parse_material_script("material A { technique {").is_err());
+        assert!(parse_material_script("material A { } }").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Orchestrator to turn every model's material scripts into a `materials.json`
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+
+use crate::materials::{
+    gltf_document, parse_material_script, read_sdf_materials, resolve_texture,
+    scan_dir_for_material_scripts, scan_dir_for_sdf_files, GltfMaterial, OgreMaterial,
+};
+use crate::progress::create_progress_bar;
+use crate::report::FileAction;
+
+/// How deep `material A : B` chains are followed before giving up on a cycle
+const MAX_INHERITANCE_DEPTH: usize = 16;
+
+/// A material as found in a script, before inheritance is resolved
+struct Definition {
+    model_dir: PathBuf,
+    parent: Option<String>,
+    material: Option<OgreMaterial>,
+}
+
+/// Orchestrator to parse all the material scripts and write, for every model that has
+/// any, a `materials.json` mapping material names to their properties, with textures
+/// resolved to their webified paths. The same materials, along with the materials of
+/// the visuals in SDF files, are also converted to glTF in a `materials.gltf`.
+pub fn process(dir: &Path) -> std::result::Result<(), std::io::Error> {
+    let definitions = read_definitions(dir)?;
+
+    // Names are global to OGRE, the first definition wins like it does for gzbridge
+    let mut by_name: HashMap<&str, &Definition> = HashMap::new();
+    for (name, definition) in &definitions {
+        by_name.entry(name).or_insert(definition);
+    }
+
+    let mut resolved: HashMap<&str, OgreMaterial> = HashMap::new();
+    let mut models: BTreeMap<&Path, BTreeMap<&str, OgreMaterial>> = BTreeMap::new();
+    for (name, definition) in &definitions {
+        if let Some(material) = resolve_material(definition, &by_name, 0) {
+            resolved.entry(name).or_insert_with(|| material.clone());
+            models
+                .entry(&definition.model_dir)
+                .or_default()
+                .insert(name, material);
+        }
+    }
+
+    let mut gltf_models: BTreeMap<PathBuf, Vec<GltfMaterial>> = BTreeMap::new();
+    for (model_dir, materials) in &models {
+        let json_action = FileAction::start("write materials", model_dir);
+        fs::write(
+            model_dir.join("materials.json"),
+            serde_json::to_string_pretty(materials)?,
+        )?;
+        json_action.finish(&model_dir.join("materials.json"));
+        gltf_models.insert(
+            model_dir.to_path_buf(),
+            materials
+                .iter()
+                .map(|(name, material)| GltfMaterial::from_ogre(name, material))
+                .collect(),
+        );
+    }
+
+    let sdf_files = scan_dir_for_sdf_files(dir)?;
+    let sdf_bar = create_progress_bar(sdf_files.len() as u64);
+    sdf_bar.set_prefix("SDF Materials");
+    for sdf in sdf_files {
+        sdf_bar.inc(1);
+        sdf_bar.set_message(&format!("Converting {:?}...", &sdf));
+
+        let model_dir = sdf.parent().unwrap_or(dir).to_path_buf();
+        let materials = match read_sdf_materials(&sdf) {
+            Ok(materials) => materials,
+            Err(e) => {
+                sdf_bar.println(format!("{} {:?}: {}", style("Skipped").red(), sdf, e));
+                FileAction::start("read materials", &sdf).fail(e);
+                continue;
+            }
+        };
+
+        for (name, mut material) in materials {
+            if let Some(metal) = material.metal.as_mut() {
+                for map in [
+                    &mut metal.albedo_map,
+                    &mut metal.roughness_map,
+                    &mut metal.metalness_map,
+                    &mut metal.normal_map,
+                    &mut metal.emissive_map,
+                ] {
+                    *map = map
+                        .as_deref()
+                        .map(|texture| resolve_texture(texture, &model_dir, dir));
+                }
+            }
+            let script = material
+                .script
+                .as_deref()
+                .and_then(|script| resolved.get(script));
+            gltf_models
+                .entry(model_dir.clone())
+                .or_default()
+                .push(GltfMaterial::from_sdf(&name, &material, script));
+        }
+    }
+
+    for (model_dir, materials) in &gltf_models {
+        let depth = model_dir
+            .strip_prefix(dir)
+            .map_or(0, |p| p.components().count());
+        let document = gltf_document(materials, &"../".repeat(depth));
+        let gltf_action = FileAction::start("write materials", model_dir);
+        fs::write(
+            model_dir.join("materials.gltf"),
+            serde_json::to_string_pretty(&document)?,
+        )?;
+        gltf_action.finish(&model_dir.join("materials.gltf"));
+    }
+    sdf_bar.finish_with_message(&format!(
+        "Wrote materials.gltf for {} model(s)",
+        gltf_models.len()
+    ));
+
+    Ok(())
+}
+
+/// Parse every material script, keeping the materials in the order they're defined
+fn read_definitions(dir: &Path) -> std::result::Result<Vec<(String, Definition)>, std::io::Error> {
+    let scripts = scan_dir_for_material_scripts(dir)?;
+    let materials_bar = create_progress_bar(scripts.len() as u64);
+    materials_bar.set_prefix("Material Scripts");
+
+    let mut definitions: Vec<(String, Definition)> = Vec::new();
+    for script in scripts {
+        materials_bar.inc(1);
+        materials_bar.set_message(&format!("Parsing {:?}...", &script));
+
+        // materials/scripts/*.material, so the model is three levels up
+        let model_dir = script
+            .ancestors()
+            .nth(3)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| dir.to_path_buf());
+        let nodes = match fs::read_to_string(&script).and_then(|s| parse_material_script(&s)) {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                materials_bar.println(format!("{} {:?}: {}", style("Skipped").red(), script, e));
+                FileAction::start("read materials", &script).fail(&e);
+                continue;
+            }
+        };
+
+        for node in nodes.iter().filter(|node| node.name == "material") {
+            let name = match node.values.first() {
+                Some(name) => name.clone(),
+                None => continue,
+            };
+            let parent = match node.values.as_slice() {
+                [_, colon, parent, ..] if colon == ":" => Some(parent.clone()),
+                _ => None,
+            };
+            let material = OgreMaterial::from_node(node).map(|mut material| {
+                material.texture = material
+                    .texture
+                    .map(|texture| resolve_texture(&texture, &model_dir, dir));
+                material
+            });
+
+            definitions.push((
+                name,
+                Definition {
+                    model_dir: model_dir.clone(),
+                    parent,
+                    material,
+                },
+            ));
+        }
+    }
+    materials_bar.finish_with_message(&format!("{} material(s) parsed", definitions.len()));
+
+    Ok(definitions)
+}
+
+/// Material with the properties it inherits filled in
+fn resolve_material(
+    definition: &Definition,
+    by_name: &HashMap<&str, &Definition>,
+    depth: usize,
+) -> Option<OgreMaterial> {
+    let parent = definition
+        .parent
+        .as_deref()
+        .filter(|_| depth < MAX_INHERITANCE_DEPTH)
+        .and_then(|parent| by_name.get(parent))
+        .and_then(|parent| resolve_material(parent, by_name, depth + 1));
+
+    match (definition.material.clone(), parent) {
+        (Some(material), Some(parent)) => Some(material.inherit(&parent)),
+        (material, parent) => material.or(parent),
+    }
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+
+    fn setup(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let example_path = Path::new("tests").join("materials");
+        let destination_path = example_path.join(test_run_id);
+
+        for file in &[
+            "box/materials/scripts/box.material",
+            "box/materials/textures/box.png",
+            "media/materials/scripts/gazebo.material",
+            "box/model.sdf",
+            "media/materials/textures/ground.tif",
+        ] {
+            let destination = destination_path.join(file);
+            fs::create_dir_all(destination.parent().unwrap())?;
+            fs::copy(example_path.join(file), destination)?;
+        }
+
+        Ok(())
+    }
+
+    fn teardown(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let destination_path = Path::new("tests").join("materials").join(test_run_id);
+        fs::remove_dir_all(destination_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_writes_materials_json_per_model() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "test_run_it_writes_materials_json_per_model";
+        setup(test_run_id)?;
+
+        let dir = Path::new("tests").join("materials").join(test_run_id);
+        process(&dir)?;
+
+        let read = |model: &str| -> std::result::Result<serde_json::Value, std::io::Error> {
+            let json = fs::read_to_string(dir.join(model).join("materials.json"))?;
+            Ok(serde_json::from_str(&json)?)
+        };
+        let media = read("media")?;
+        assert_eq!(media["Gazebo/Grey"]["ambient"][0], 0.3);
+        assert_eq!(
+            media["Gazebo/Ground"]["texture"],
+            "media/materials/textures/ground.tif"
+        );
+        assert!(media.get("Gazebo/Program").is_none());
+
+        let model = read("box")?;
+        assert_eq!(
+            model["Box/Wood"]["texture"],
+            "box/materials/textures/box.png"
+        );
+        assert_eq!(model["Box/Wood"]["opacity"], 0.5);
+        assert_eq!(model["Box/Grey"]["diffuse"][0], 0.7);
+        assert_eq!(model["Box/Grey"]["specular"][0], 0.2);
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_writes_gltf_materials_per_model() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "test_run_it_writes_gltf_materials_per_model";
+        setup(test_run_id)?;
+
+        let dir = Path::new("tests").join("materials").join(test_run_id);
+        process(&dir)?;
+
+        let gltf: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("box").join("materials.gltf"))?)?;
+        let materials = gltf["materials"].as_array().unwrap();
+        let names: Vec<&str> = materials
+            .iter()
+            .map(|material| material["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Box/Grey",
+                "Box/Wood",
+                "box::link::wood",
+                "box::link::metal"
+            ]
+        );
+        assert_eq!(materials[2]["alphaMode"], "BLEND");
+        assert_eq!(materials[3]["pbrMetallicRoughness"]["metallicFactor"], 1.0);
+        assert_eq!(
+            gltf["images"],
+            serde_json::json!([{ "uri": "../box/materials/textures/box.png" }])
+        );
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Find where a texture named in a material script ended up after webifying
+
+use std::path::{Path, PathBuf};
+
+/// Path, relative to the assets directory and with `/` separators, of the texture
+/// a material script refers to. Textures were converted to PNG and moved to
+/// `materials/textures` by image processing, so that's checked first, then the
+/// original file in case it couldn't be converted.
+pub fn resolve_texture(texture: &str, model_dir: &Path, dir: &Path) -> String {
+    let textures_dir = model_dir.join("materials").join("textures");
+    let file_name = Path::new(texture)
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(texture));
+    let png = textures_dir.join(file_name.with_extension("png"));
+    let original = textures_dir.join(&file_name);
+
+    let resolved = if !png.is_file() && original.is_file() {
+        original
+    } else {
+        png
+    };
+
+    resolved
+        .strip_prefix(dir)
+        .unwrap_or(&resolved)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod resolve_texture_tests {
+    use super::*;
+
+    #[test]
+    fn it_points_at_the_png() {
+        let dir = Path::new("tests").join("collada");
+
+        assert_eq!(
+            resolve_texture("cube_diffuse.jpg", &dir.join("cube"), &dir),
+            "cube/materials/textures/cube_diffuse.png"
+        );
+    }
+
+    #[test]
+    fn it_keeps_textures_that_were_not_converted() {
+        let dir = Path::new("tests").join("materials");
+
+        assert_eq!(
+            resolve_texture("../textures/ground.tif", &dir.join("media"), &dir),
+            "media/materials/textures/ground.tif"
+        );
+    }
+}
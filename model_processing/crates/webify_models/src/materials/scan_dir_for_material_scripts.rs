@@ -0,0 +1,47 @@
+//! Scan all files recursively in the specified path for OGRE material scripts
+
+use std::path::{Path, PathBuf};
+
+use console::style;
+
+use crate::progress::print_message;
+use crate::walk::{walk_assets, AssetKind};
+
+/// Scan the specified directory for `materials/scripts/*.material` files, leaving out the
+/// files the scan filter excludes
+pub fn scan_dir_for_material_scripts(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    print_message("\nScanning for material scripts...");
+    let scripts: Vec<PathBuf> = walk_assets(dir)?
+        .into_iter()
+        .filter(|asset| asset.kind == AssetKind::Material)
+        .map(|asset| asset.path)
+        .collect();
+
+    print_message(format!(
+        "Material scripts found: {}\n",
+        style(scripts.len()).bold().blue()
+    ));
+
+    Ok(scripts)
+}
+
+#[cfg(test)]
+mod scan_dir_for_material_scripts_tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_material_scripts() -> std::io::Result<()> {
+        let dir = Path::new("tests").join("materials");
+        let scripts = scan_dir_for_material_scripts(&dir)?;
+
+        assert_eq!(
+            scripts,
+            vec![
+                dir.join("box/materials/scripts/box.material"),
+                dir.join("media/materials/scripts/gazebo.material"),
+            ]
+        );
+
+        Ok(())
+    }
+}
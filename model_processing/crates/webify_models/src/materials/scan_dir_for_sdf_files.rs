@@ -0,0 +1,40 @@
+//! Scan all files recursively in the specified path for SDF files
+
+use std::path::{Path, PathBuf};
+
+use console::style;
+
+use crate::progress::print_message;
+use crate::walk::{walk_assets, AssetKind};
+
+/// Scan the specified directory for `.sdf` files, leaving out the files the scan filter
+/// excludes
+pub fn scan_dir_for_sdf_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    print_message("\nScanning for SDF files...");
+    let sdf_files: Vec<PathBuf> = walk_assets(dir)?
+        .into_iter()
+        .filter(|asset| asset.kind == AssetKind::Sdf)
+        .map(|asset| asset.path)
+        .collect();
+
+    print_message(format!(
+        "SDF files found: {}\n",
+        style(sdf_files.len()).bold().blue()
+    ));
+
+    Ok(sdf_files)
+}
+
+#[cfg(test)]
+mod scan_dir_for_sdf_files_tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_sdf_files() -> std::io::Result<()> {
+        let dir = Path::new("tests").join("materials").join("box");
+
+        assert_eq!(scan_dir_for_sdf_files(&dir)?, vec![dir.join("model.sdf")]);
+
+        Ok(())
+    }
+}
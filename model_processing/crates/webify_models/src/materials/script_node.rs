@@ -0,0 +1,40 @@
+//! Node of a parsed OGRE script, e.g. `diffuse 1 0 0 1` or `pass main { ... }`
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptNode {
+    pub name: String,
+    pub values: Vec<String>,
+    pub children: Vec<ScriptNode>,
+}
+
+impl ScriptNode {
+    pub fn new(name: &str) -> ScriptNode {
+        ScriptNode {
+            name: String::from(name),
+            ..ScriptNode::default()
+        }
+    }
+
+    /// First direct child with the specified name
+    pub fn find_child(&self, name: &str) -> Option<&ScriptNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+#[cfg(test)]
+mod find_child_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_first_child() {
+        let mut technique = ScriptNode::new("technique");
+        let mut first = ScriptNode::new("pass");
+        first.values.push(String::from("first"));
+        let mut second = ScriptNode::new("pass");
+        second.values.push(String::from("second"));
+        technique.children = vec![first.clone(), second];
+
+        assert_eq!(technique.find_child("pass"), Some(&first));
+        assert_eq!(technique.find_child("texture_unit"), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! `<material>` blocks of the visuals in an SDF file
+
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::Element;
+
+use crate::collada::{child_elements, parse_floats};
+
+/// Textures and factors of an SDF `<pbr><metal>` workflow
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PbrMetal {
+    pub albedo_map: Option<String>,
+    pub roughness_map: Option<String>,
+    pub metalness_map: Option<String>,
+    pub normal_map: Option<String>,
+    pub emissive_map: Option<String>,
+    pub roughness: Option<f64>,
+    pub metalness: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SdfMaterial {
+    /// Name of the OGRE material in `<script>`, e.g. `Gazebo/Grey`
+    pub script: Option<String>,
+    pub ambient: Option<Vec<f64>>,
+    pub diffuse: Option<Vec<f64>>,
+    pub specular: Option<Vec<f64>>,
+    pub emissive: Option<Vec<f64>>,
+    pub metal: Option<PbrMetal>,
+}
+
+/// Read the material of every visual in an SDF file, named after the model, link and
+/// visual they're in, e.g. `box::link::visual`
+pub fn read_sdf_materials(sdf: &Path) -> std::result::Result<Vec<(String, SdfMaterial)>, Error> {
+    let file = BufReader::new(File::open(sdf)?);
+    let root = Element::parse(file).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {:?} as SDF: {}", sdf, e),
+        )
+    })?;
+
+    let mut materials = Vec::new();
+    collect(&root, &mut Vec::new(), &mut materials);
+
+    Ok(materials)
+}
+
+/// Walk the tree keeping track of the names of the enclosing models, links and visuals
+fn collect<'a>(
+    element: &'a Element,
+    names: &mut Vec<&'a str>,
+    materials: &mut Vec<(String, SdfMaterial)>,
+) {
+    let name = match element.name.as_str() {
+        "model" | "link" | "visual" => element.attributes.get("name"),
+        _ => None,
+    };
+    if let Some(name) = name {
+        names.push(name);
+    }
+
+    if element.name == "visual" {
+        if let Some(material) = child_elements(element, "material").next() {
+            materials.push((names.join("::"), read_sdf_material(material)));
+        }
+    }
+    for child in element.children.iter().filter_map(|c| c.as_element()) {
+        collect(child, names, materials);
+    }
+
+    if name.is_some() {
+        names.pop();
+    }
+}
+
+/// Read a single `<material>` element
+pub fn read_sdf_material(material: &Element) -> SdfMaterial {
+    SdfMaterial {
+        script: child_elements(material, "script")
+            .next()
+            .and_then(|script| read_text(script, "name")),
+        ambient: read_color(material, "ambient"),
+        diffuse: read_color(material, "diffuse"),
+        specular: read_color(material, "specular"),
+        emissive: read_color(material, "emissive"),
+        metal: child_elements(material, "pbr")
+            .next()
+            .and_then(|pbr| child_elements(pbr, "metal").next())
+            .map(|metal| PbrMetal {
+                albedo_map: read_text(metal, "albedo_map"),
+                roughness_map: read_text(metal, "roughness_map"),
+                metalness_map: read_text(metal, "metalness_map"),
+                normal_map: read_text(metal, "normal_map"),
+                emissive_map: read_text(metal, "emissive_map"),
+                roughness: read_text(metal, "roughness").and_then(|v| v.parse().ok()),
+                metalness: read_text(metal, "metalness").and_then(|v| v.parse().ok()),
+            }),
+    }
+}
+
+/// Trimmed text of the first child with the given name, if it isn't empty
+fn read_text(element: &Element, name: &str) -> Option<String> {
+    child_elements(element, name)
+        .next()
+        .and_then(|child| child.get_text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn read_color(element: &Element, name: &str) -> Option<Vec<f64>> {
+    read_text(element, name).and_then(|text| parse_floats(&text).ok())
+}
+
+#[cfg(test)]
+mod read_sdf_materials_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_visual_materials() -> std::result::Result<(), Error> {
+        let sdf = Path::new("tests")
+            .join("materials")
+            .join("box")
+            .join("model.sdf");
+        let materials = read_sdf_materials(&sdf)?;
+
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].0, "box::link::wood");
+        assert_eq!(materials[0].1.script.as_deref(), Some("Box/Wood"));
+        assert_eq!(materials[1].0, "box::link::metal");
+        assert_eq!(materials[1].1.diffuse, Some(vec![0.8, 0.8, 0.8, 1.0]));
+        assert_eq!(
+            materials[1].1.metal,
+            Some(PbrMetal {
+                albedo_map: Some(String::from("materials/textures/box.png")),
+                roughness_map: Some(String::from("materials/textures/box.png")),
+                metalness: Some(1.0),
+                ..PbrMetal::default()
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,533 @@
+//! Bake the COLLADA `<unit>` scale and `<up_axis>` of a DAE mesh into its vertex data
+//! and node transforms, so that every mesh ends up in meters and Z_UP
+
+use std::{
+    collections::HashSet,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::{Element, XMLNode};
+
+use crate::collada::{
+    find_elements, find_elements_mut, format_floats, parse_floats, read_dae, write_dae, Matrix,
+};
+
+/// Semantics of inputs whose sources hold directions, which get rotated but not scaled
+const DIRECTION_SEMANTICS: [&str; 5] =
+    ["NORMAL", "TANGENT", "BINORMAL", "TEXTANGENT", "TEXBINORMAL"];
+
+/// Scale and rotation that take a point from the mesh's frame into meters/Z_UP
+#[derive(Debug, Clone, PartialEq)]
+struct Conversion {
+    scale: f64,
+    /// Rows of the rotation matrix, always a signed permutation
+    rotation: [[f64; 3]; 3],
+}
+
+impl Conversion {
+    fn is_identity(&self) -> bool {
+        self.scale == 1.0 && self.rotation == IDENTITY
+    }
+
+    fn rotate(&self, v: [f64; 3]) -> [f64; 3] {
+        let r = &self.rotation;
+        [
+            r[0][0] * v[0] + r[0][1] * v[1] + r[0][2] * v[2],
+            r[1][0] * v[0] + r[1][1] * v[1] + r[1][2] * v[2],
+            r[2][0] * v[0] + r[2][1] * v[1] + r[2][2] * v[2],
+        ]
+    }
+
+    fn point(&self, v: [f64; 3]) -> [f64; 3] {
+        let [x, y, z] = self.rotate(v);
+        [x * self.scale, y * self.scale, z * self.scale]
+    }
+
+    /// Conjugate a 3x3 linear part by the rotation, `R * m * R^T`
+    fn conjugate(&self, m: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+        let r = &self.rotation;
+        let mut result = [[0.0; 3]; 3];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3)
+                    .flat_map(|k| (0..3).map(move |l| (k, l)))
+                    .map(|(k, l)| r[i][k] * m[k][l] * r[j][l])
+                    .sum();
+            }
+        }
+
+        result
+    }
+}
+
+const IDENTITY: [[f64; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// Normalize the specified DAE mesh in place to meters/Z_UP.
+/// Returns whether the mesh needed (and received) any changes.
+pub fn normalize_mesh(mesh: &Path) -> std::result::Result<bool, std::io::Error> {
+    let mut root = read_dae(mesh)?;
+    let conversion = get_conversion(&root)?;
+
+    if conversion.is_identity() {
+        return Ok(false);
+    }
+
+    bake_sources(&mut root, &conversion)?;
+    bake_node_transforms(&mut root, &conversion)?;
+    reset_asset(&mut root);
+    write_dae(mesh, &root)?;
+
+    Ok(true)
+}
+
+/// Transform that takes the mesh's points into meters/Z_UP without changing the
+/// mesh, e.g. to draw it next to others
+pub fn normalizing_transform(root: &Element) -> std::result::Result<Matrix, std::io::Error> {
+    let conversion = get_conversion(root)?;
+    let r = &conversion.rotation;
+    let s = conversion.scale;
+
+    Ok(Matrix([
+        r[0][0] * s,
+        r[0][1] * s,
+        r[0][2] * s,
+        0.0,
+        r[1][0] * s,
+        r[1][1] * s,
+        r[1][2] * s,
+        0.0,
+        r[2][0] * s,
+        r[2][1] * s,
+        r[2][2] * s,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+    ]))
+}
+
+/// Read `<asset><unit meter>` and `<asset><up_axis>`, with the COLLADA defaults
+/// of 1 meter and Y_UP when they are missing
+fn get_conversion(root: &Element) -> std::result::Result<Conversion, std::io::Error> {
+    let asset = root.get_child("asset");
+    let scale = match asset
+        .and_then(|a| a.get_child("unit"))
+        .and_then(|u| u.attributes.get("meter"))
+    {
+        Some(meter) => meter.trim().parse::<f64>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid <unit meter=\"{}\">", meter),
+            )
+        })?,
+        None => 1.0,
+    };
+    let up_axis = asset
+        .and_then(|a| a.get_child("up_axis"))
+        .and_then(|u| u.get_text())
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| String::from("Y_UP"));
+
+    let rotation = match up_axis.as_str() {
+        "Z_UP" => IDENTITY,
+        // (x, y, z) -> (x, -z, y)
+        "Y_UP" => [[1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]],
+        // (x, y, z) -> (-y, -z, x)
+        "X_UP" => [[0.0, -1.0, 0.0], [0.0, 0.0, -1.0], [1.0, 0.0, 0.0]],
+        other => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown <up_axis> {:?}", other),
+            ))
+        }
+    };
+
+    Ok(Conversion { scale, rotation })
+}
+
+/// Convert the `<float_array>` of every source used as a position or a direction
+fn bake_sources(
+    root: &mut Element,
+    conversion: &Conversion,
+) -> std::result::Result<(), std::io::Error> {
+    let mut positions = HashSet::new();
+    let mut directions = HashSet::new();
+    for input in find_elements(root, "input") {
+        let semantic = input.attributes.get("semantic").map(String::as_str);
+        let source = match input.attributes.get("source") {
+            Some(s) => s.trim_start_matches('#').to_string(),
+            None => continue,
+        };
+        match semantic {
+            Some("POSITION") => {
+                positions.insert(source);
+            }
+            Some(s) if DIRECTION_SEMANTICS.contains(&s) => {
+                directions.insert(source);
+            }
+            _ => (),
+        }
+    }
+
+    let mut result = Ok(());
+    find_elements_mut(root, "source", &mut |source| {
+        let id = source.attributes.get("id").cloned().unwrap_or_default();
+        let is_position = positions.contains(&id);
+        if !is_position && !directions.contains(&id) {
+            return;
+        }
+        if let Err(e) = bake_float_array(source, conversion, is_position) {
+            result = Err(e);
+        }
+    });
+
+    result
+}
+
+/// Convert the first three components of every element of a source's float array,
+/// as points (rotated and scaled) or as directions (rotated only)
+fn bake_float_array(
+    source: &mut Element,
+    conversion: &Conversion,
+    is_position: bool,
+) -> std::result::Result<(), std::io::Error> {
+    let stride = source
+        .get_child("technique_common")
+        .and_then(|t| t.get_child("accessor"))
+        .and_then(|a| a.attributes.get("stride"))
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(3);
+    if stride < 3 {
+        return Ok(());
+    }
+
+    let float_array = match source.get_mut_child("float_array") {
+        Some(f) => f,
+        None => return Ok(()),
+    };
+    let mut values = parse_floats(&float_array.get_text().unwrap_or_default())?;
+    for chunk in values.chunks_mut(stride).filter(|c| c.len() >= 3) {
+        let v = [chunk[0], chunk[1], chunk[2]];
+        let converted = if is_position {
+            conversion.point(v)
+        } else {
+            conversion.rotate(v)
+        };
+        chunk[..3].copy_from_slice(&converted);
+    }
+    set_text(float_array, format_floats(&values));
+
+    Ok(())
+}
+
+/// Conjugate every node transform by the conversion, so that the baked vertex data
+/// ends up at the same place in the scene
+fn bake_node_transforms(
+    root: &mut Element,
+    conversion: &Conversion,
+) -> std::result::Result<(), std::io::Error> {
+    let mut result = Ok(());
+    find_elements_mut(root, "node", &mut |node| {
+        for child in node.children.iter_mut() {
+            if let XMLNode::Element(transform) = child {
+                if let Err(e) = bake_transform(transform, conversion) {
+                    result = Err(e);
+                }
+            }
+        }
+    });
+
+    result
+}
+
+fn bake_transform(
+    transform: &mut Element,
+    conversion: &Conversion,
+) -> std::result::Result<(), std::io::Error> {
+    let expected = match transform.name.as_str() {
+        "translate" | "scale" => 3,
+        "rotate" => 4,
+        "lookat" => 9,
+        "matrix" => 16,
+        _ => return Ok(()),
+    };
+    let v = parse_floats(&transform.get_text().unwrap_or_default())?;
+    if v.len() != expected {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "<{}> expects {} values, found {}",
+                transform.name,
+                expected,
+                v.len()
+            ),
+        ));
+    }
+
+    let baked: Vec<f64> = match transform.name.as_str() {
+        "translate" => conversion.point([v[0], v[1], v[2]]).to_vec(),
+        "scale" => {
+            // Conjugating a diagonal by a signed permutation only permutes the diagonal
+            let diagonal =
+                conversion.conjugate([[v[0], 0.0, 0.0], [0.0, v[1], 0.0], [0.0, 0.0, v[2]]]);
+            vec![diagonal[0][0], diagonal[1][1], diagonal[2][2]]
+        }
+        "rotate" => {
+            let mut axis = conversion.rotate([v[0], v[1], v[2]]).to_vec();
+            axis.push(v[3]);
+            axis
+        }
+        "lookat" => [
+            conversion.point([v[0], v[1], v[2]]),
+            conversion.point([v[3], v[4], v[5]]),
+            conversion.rotate([v[6], v[7], v[8]]),
+        ]
+        .concat(),
+        _ => {
+            // Row-major 4x4, linear part in the upper 3x3 and translation in the last column
+            let linear =
+                conversion.conjugate([[v[0], v[1], v[2]], [v[4], v[5], v[6]], [v[8], v[9], v[10]]]);
+            let translation = conversion.point([v[3], v[7], v[11]]);
+            vec![
+                linear[0][0],
+                linear[0][1],
+                linear[0][2],
+                translation[0],
+                linear[1][0],
+                linear[1][1],
+                linear[1][2],
+                translation[1],
+                linear[2][0],
+                linear[2][1],
+                linear[2][2],
+                translation[2],
+                v[12],
+                v[13],
+                v[14],
+                v[15],
+            ]
+        }
+    };
+    set_text(transform, format_floats(&baked));
+
+    Ok(())
+}
+
+/// Reset `<asset>` to meters and Z_UP, creating any missing elements
+fn reset_asset(root: &mut Element) {
+    let namespace = root.namespace.clone();
+    let new_element = |name: &str| {
+        let mut element = Element::new(name);
+        element.namespace = namespace.clone();
+        element
+    };
+
+    if root.get_child("asset").is_none() {
+        root.children
+            .insert(0, XMLNode::Element(new_element("asset")));
+    }
+    let asset = root.get_mut_child("asset").unwrap();
+
+    if asset.get_child("unit").is_none() {
+        asset.children.push(XMLNode::Element(new_element("unit")));
+    }
+    let unit = asset.get_mut_child("unit").unwrap();
+    unit.attributes
+        .insert(String::from("name"), String::from("meter"));
+    unit.attributes
+        .insert(String::from("meter"), String::from("1"));
+
+    if asset.get_child("up_axis").is_none() {
+        asset
+            .children
+            .push(XMLNode::Element(new_element("up_axis")));
+    }
+    set_text(
+        asset.get_mut_child("up_axis").unwrap(),
+        String::from("Z_UP"),
+    );
+}
+
+fn set_text(element: &mut Element, text: String) {
+    element.children = vec![XMLNode::Text(text)];
+}
+
+#[cfg(test)]
+mod normalize_mesh_tests {
+    use std::fs;
+
+    use super::*;
+
+    fn setup(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let example_path = Path::new("tests").join("collada").join("cube");
+        let destination_path = Path::new("tests").join("mesh_update").join(test_run_id);
+
+        fs::create_dir_all(destination_path.join("meshes"))?;
+        fs::copy(
+            example_path.join("meshes").join("cube.dae"),
+            destination_path.join("meshes").join("cube.dae"),
+        )?;
+
+        Ok(())
+    }
+
+    fn teardown(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let destination_path = Path::new("tests").join("mesh_update").join(test_run_id);
+        fs::remove_dir_all(destination_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_bakes_units_and_up_axis() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "normalize_mesh_1";
+        setup(test_run_id)?;
+
+        let mesh = Path::new("tests")
+            .join("mesh_update")
+            .join(test_run_id)
+            .join("meshes")
+            .join("cube.dae");
+        assert!(normalize_mesh(&mesh)?);
+
+        let root = read_dae(&mesh)?;
+        let asset = root.get_child("asset").unwrap();
+        assert_eq!(asset.get_child("unit").unwrap().attributes["meter"], "1");
+        assert_eq!(
+            asset.get_child("up_axis").unwrap().get_text().unwrap(),
+            "Z_UP"
+        );
+
+        let arrays = find_elements(&root, "float_array");
+        let positions = parse_floats(&arrays[0].get_text().unwrap())?;
+        assert_eq!(positions[..3], [0.0254, -0.0254, 0.0254]);
+        let normals = parse_floats(&arrays[1].get_text().unwrap())?;
+        // +Y becomes +Z
+        assert_eq!(normals[6..9], [0.0, 0.0, 1.0]);
+        let uvs = parse_floats(&arrays[2].get_text().unwrap())?;
+        assert_eq!(uvs, vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+
+        let translate = parse_floats(&find_elements(&root, "translate")[0].get_text().unwrap())?;
+        assert_eq!(translate, vec![0.0, 0.0, 10.0 * 0.0254]);
+
+        // Running it again is a no-op
+        assert!(!normalize_mesh(&mesh)?);
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod get_conversion_tests {
+    use super::*;
+
+    #[test]
+    fn it_defaults_to_y_up_meters() -> std::result::Result<(), std::io::Error> {
+        let root = Element::parse(r#"<COLLADA><asset/></COLLADA>"#.as_bytes()).unwrap();
+        let conversion = get_conversion(&root)?;
+
+        assert_eq!(conversion.scale, 1.0);
+        assert_eq!(conversion.rotate([0.0, 1.0, 0.0]), [0.0, 0.0, 1.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_maps_x_up_to_z_up() -> std::result::Result<(), std::io::Error> {
+        let root = Element::parse(
+            r#"<COLLADA><asset><up_axis>X_UP</up_axis></asset></COLLADA>"#.as_bytes(),
+        )
+        .unwrap();
+        let conversion = get_conversion(&root)?;
+
+        assert_eq!(conversion.rotate([1.0, 0.0, 0.0]), [0.0, 0.0, 1.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_errors_on_unknown_axis() {
+        let root = Element::parse(
+            r#"<COLLADA><asset><up_axis>W_UP</up_axis></asset></COLLADA>"#.as_bytes(),
+        )
+        .unwrap();
+
+        assert!(get_conversion(&root).is_err());
+    }
+}
+
+#[cfg(test)]
+mod normalizing_transform_tests {
+    use super::*;
+
+    #[test]
+    fn it_scales_and_rotates_points() -> std::result::Result<(), std::io::Error> {
+        let root =
+            Element::parse(r#"<COLLADA><asset><unit meter="0.5"/></asset></COLLADA>"#.as_bytes())
+                .unwrap();
+        let transform = normalizing_transform(&root)?;
+
+        assert_eq!(transform.transform_point([2.0, 4.0, 6.0]), [1.0, -3.0, 2.0]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bake_transform_tests {
+    use super::*;
+
+    fn y_up_inches() -> Conversion {
+        Conversion {
+            scale: 0.0254,
+            rotation: [[1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]],
+        }
+    }
+
+    #[test]
+    fn it_conjugates_matrices() -> std::result::Result<(), std::io::Error> {
+        let mut matrix =
+            Element::parse(r#"<matrix>1 0 0 10 0 2 0 0 0 0 3 0 0 0 0 1</matrix>"#.as_bytes())
+                .unwrap();
+        bake_transform(&mut matrix, &y_up_inches())?;
+
+        let result = parse_floats(&matrix.get_text().unwrap())?;
+        assert_eq!(
+            result,
+            vec![
+                1.0,
+                0.0,
+                0.0,
+                10.0 * 0.0254,
+                0.0,
+                3.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                2.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                1.0
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rotates_rotation_axes() -> std::result::Result<(), std::io::Error> {
+        let mut rotate = Element::parse(r#"<rotate>0 1 0 90</rotate>"#.as_bytes()).unwrap();
+        bake_transform(&mut rotate, &y_up_inches())?;
+
+        let result = parse_floats(&rotate.get_text().unwrap())?;
+        assert_eq!(result, vec![0.0, 0.0, 1.0, 90.0]);
+
+        Ok(())
+    }
+}
@@ -1,20 +1,73 @@
 //! Orchestrator to run the mesh updater
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::cli::create_progress_bar;
-use crate::mesh_update::{rename_image_references, scan_dir_for_meshes};
+use crate::config::Config;
+use crate::logging::model_span;
+use crate::mesh_update::{normalize_mesh, rename_image_references, scan_dir_for_meshes};
+use crate::progress::create_progress_bar;
+use crate::report::FileAction;
 
-/// Orchestrator to run the mesh updater
-pub fn process(dir: &Path) -> std::result::Result<(), std::io::Error> {
-    let meshes = scan_dir_for_meshes(dir).unwrap();
+/// Orchestrator to run the mesh updater, optionally normalizing every mesh to meters/Z_UP,
+/// with the settings of the model each mesh is in
+pub fn process(
+    dir: &Path,
+    normalize: bool,
+    config: &Config,
+) -> std::result::Result<(), std::io::Error> {
+    let meshes = plan_meshes(dir, config)?;
+    process_meshes(dir, normalize, config, meshes)
+}
+
+/// Find the meshes to update, leaving out those of the models whose settings disable the
+/// meshes stage or exclude them
+pub fn plan_meshes(
+    dir: &Path,
+    config: &Config,
+) -> std::result::Result<Vec<PathBuf>, std::io::Error> {
+    let mut meshes = scan_dir_for_meshes(dir)?;
+    meshes
+        .retain(|mesh| config.for_path(dir, mesh).stages.meshes && !config.is_excluded(dir, mesh));
+
+    Ok(meshes)
+}
+
+/// Rename the texture references of the meshes, and normalize them when asked to or when
+/// their settings say so
+pub fn process_meshes(
+    dir: &Path,
+    normalize: bool,
+    config: &Config,
+    meshes: Vec<PathBuf>,
+) -> std::result::Result<(), std::io::Error> {
     let mesh_bar = create_progress_bar(meshes.len() as u64);
 
     mesh_bar.set_prefix("Mesh Update");
     for mesh in meshes {
+        let _model = model_span(dir, &mesh).entered();
         mesh_bar.inc(1);
         mesh_bar.set_message(&format!("Updating {:?}...", &mesh));
-        rename_image_references(&mesh)?;
+        let settings = config.for_path(dir, &mesh);
+        let rename_action = FileAction::start("rename image references", &mesh);
+        match rename_image_references(&mesh, settings) {
+            Ok(()) => rename_action.finish(&mesh),
+            Err(e) => {
+                rename_action.fail(&e);
+                return Err(e);
+            }
+        }
+
+        if normalize || settings.stages.normalize {
+            mesh_bar.set_message(&format!("Normalizing {:?}...", &mesh));
+            let normalize_action = FileAction::start("normalize", &mesh);
+            match normalize_mesh(&mesh) {
+                Ok(_) => normalize_action.finish(&mesh),
+                Err(e) => {
+                    normalize_action.fail(&e);
+                    return Err(e);
+                }
+            }
+        }
     }
 
     // TODO: Update image references in material, txt, and sdf
@@ -23,3 +76,34 @@ pub fn process(dir: &Path) -> std::result::Result<(), std::io::Error> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod process_meshes_tests {
+    use super::*;
+    use crate::context::RunContext;
+    use crate::report::Recorder;
+
+    #[test]
+    fn it_records_the_failed_meshes() {
+        let dir = Path::new("tests").join("mesh_update");
+        let recorder = Recorder::default();
+        let context = RunContext {
+            recorder: Some(recorder.clone()),
+            ..RunContext::default()
+        };
+
+        let _context = context.enter_dir(&dir);
+        let result = process_meshes(
+            &dir,
+            false,
+            &Config::default(),
+            vec![dir.join("missing.dae")],
+        );
+
+        assert!(result.is_err());
+        let actions = recorder.take();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].action, "rename image references");
+        assert_eq!(actions[0].errors.len(), 1);
+    }
+}
@@ -8,39 +8,65 @@ use std::{
 };
 
 use aho_corasick::AhoCorasickBuilder;
+use tracing::{debug, info};
+
+use crate::config::Settings;
 
 /// Orchestrator to rename image references in a DAE mesh
-pub fn rename_image_references(mesh: &PathBuf) -> std::result::Result<(), std::io::Error> {
-    let result = find_and_rename_image_references(mesh)?;
-    let final_result = update_texture_path(result)?;
+pub fn rename_image_references(
+    mesh: &PathBuf,
+    settings: &Settings,
+) -> std::result::Result<(), std::io::Error> {
+    let result = find_and_rename_image_references(mesh, &settings.converted_types())?;
+    let final_result =
+        update_texture_path(result, &settings.textures_dir, &settings.output_types())?;
     fs::write(mesh, final_result)?;
+    info!(?mesh, "Rewrote the image references");
 
     Ok(())
 }
 
-/// Rename all occurences of supported image types to PNG
-fn find_and_rename_image_references(mesh: &PathBuf) -> std::result::Result<String, std::io::Error> {
-    let patterns = &[
-        ".tga", "_tga", ".jpg", "_jpg", ".jpeg", "_jpeg", ".gif", "_gif",
-    ];
+/// Rename all occurences of the converted image types to PNG
+fn find_and_rename_image_references(
+    mesh: &PathBuf,
+    converted_types: &[&str],
+) -> std::result::Result<String, std::io::Error> {
     let f = fs::read_to_string(mesh)?;
+    debug!(?mesh, bytes = f.len(), "Read the mesh");
+    if converted_types.is_empty() {
+        debug!(?mesh, "No converted image types to rename");
+        return Ok(f);
+    }
+
+    let mut patterns = vec![];
+    let mut replacements = vec![];
+    for extension in converted_types {
+        patterns.push(format!(".{}", extension));
+        replacements.push(".png");
+        patterns.push(format!("_{}", extension));
+        replacements.push("_png");
+    }
 
-    let ac = AhoCorasickBuilder::new().build(patterns);
-    let result = ac.replace_all(
-        &f,
-        &[
-            ".png", "_png", ".png", "_png", ".png", "_png", ".png", "_png",
-        ],
+    let ac = AhoCorasickBuilder::new().build(&patterns);
+    let result = ac.replace_all(&f, &replacements);
+    debug!(
+        ?mesh,
+        renamed = ac.find_iter(&f).count(),
+        "Renamed the references to {}",
+        converted_types.join(", ")
     );
 
     Ok(result)
 }
 
 /// Update all texture paths to point to the relative path for textures/materials
-fn update_texture_path(contents: String) -> std::result::Result<String, std::io::Error> {
+fn update_texture_path(
+    contents: String,
+    texture_path: &Path,
+    output_types: &[&str],
+) -> std::result::Result<String, std::io::Error> {
     let texture_element = &["<init_from>", "</init_from>"];
     let ac = AhoCorasickBuilder::new().build(texture_element);
-    let texture_path = Path::new("materials").join("textures");
 
     let mut new_contents = String::new();
 
@@ -54,19 +80,24 @@ fn update_texture_path(contents: String) -> std::result::Result<String, std::io:
             } else if mat.pattern() == 1 {
                 let texture_name = &line[start..mat.start()];
                 // Prefix image reference with relative directory path to textures
-                if texture_name.ends_with(".png")
+                if output_types
+                    .iter()
+                    .any(|t| texture_name.ends_with(&format!(".{}", t)))
                     && !texture_name.contains(&texture_path.to_str().unwrap())
                 {
                     // TODO: Properly find the root path of the mesh, rather than assuming
                     new_line = line.replace(
                         texture_name,
                         Path::new("..")
-                            .join("materials")
-                            .join("textures")
+                            .join(texture_path)
                             .join(texture_name)
                             .to_str()
                             .unwrap(),
                     );
+                    debug!(
+                        texture = texture_name,
+                        "Pointed the reference at the textures directory"
+                    );
                 }
             }
         }
@@ -121,7 +152,7 @@ mod rename_image_references_tests {
             .join(&test_run_id)
             .join("meshes")
             .join("test.dae");
-        rename_image_references(&destination_path)?;
+        rename_image_references(&destination_path, &Settings::default())?;
 
         let mut file = File::open(destination_path)?;
         let mut contents = String::new();
@@ -146,7 +177,10 @@ mod find_and_rename_image_references_tests {
             .join("test")
             .join("meshes")
             .join("test.dae");
-        let result = find_and_rename_image_references(&destination_path)?;
+        let result = find_and_rename_image_references(
+            &destination_path,
+            &Settings::default().converted_types(),
+        )?;
         assert_eq!(result, "<!-- This is not a valid DAE, just a test file -->\n\n<image id=\"Test_Diffuse_png\">\n  <init_from>test_diffuse.png</init_from>\n</image>\n");
 
         Ok(())
@@ -172,7 +206,8 @@ mod update_texture_path_tests {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
-        let result = update_texture_path(contents)?;
+        let result =
+            update_texture_path(contents, &Path::new("materials").join("textures"), &["png"])?;
         assert_eq!(result, "<!-- This is not a valid DAE, just a test file -->\n\n<image id=\"Test_Diffuse_png\">\n  <init_from>../materials/textures/test_diffuse.png</init_from>\n</image>\n");
 
         Ok(())
@@ -0,0 +1,36 @@
+//! Order `model.config` versions like `1.0`, `1.10` and `2`
+
+use std::cmp::Ordering;
+
+/// Compare two versions component by component, numerically when both components are
+/// numbers. Missing components count as 0, so `1` and `1.0` are equal.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let a: Vec<&str> = a.trim().split('.').collect();
+    let b: Vec<&str> = b.trim().split('.').collect();
+
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or("0");
+        let y = b.get(i).copied().unwrap_or("0");
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod compare_versions_tests {
+    use super::*;
+
+    #[test]
+    fn it_compares_numerically() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1", "1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.9", "1.0"), Ordering::Less);
+    }
+}
@@ -0,0 +1,31 @@
+//! Find the models of a models directory
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The directories directly inside `dir` with a `model.config`, sorted by path
+pub fn list_model_dirs(dir: &Path) -> std::result::Result<Vec<PathBuf>, std::io::Error> {
+    let mut model_dirs: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::result::Result<_, _>>()?;
+    model_dirs.retain(|model_dir| model_dir.join("model.config").is_file());
+    model_dirs.sort();
+
+    Ok(model_dirs)
+}
+
+#[cfg(test)]
+mod list_model_dirs_tests {
+    use super::*;
+
+    #[test]
+    fn it_only_lists_models() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("references").join("models");
+
+        assert_eq!(list_model_dirs(&dir)?, vec![dir.join("crate_box")]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+//! Reads the `model.config` that describes every Gazebo model
+
+mod compare_versions;
+mod list_model_dirs;
+mod model_name;
+mod read_model_config;
+
+pub use self::compare_versions::compare_versions;
+pub use self::list_model_dirs::list_model_dirs;
+pub use self::model_name::model_name;
+pub use self::read_model_config::read_model_config;
@@ -0,0 +1,31 @@
+//! Work out which model a file belongs to
+
+use std::path::Path;
+
+/// The top-level directory of `dir` the file is in
+pub fn model_name(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let mut components = relative.components();
+    let model = components.next()?;
+    // Files at the root of the models directory aren't in a model
+    components.next()?;
+
+    Some(model.as_os_str().to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod model_name_tests {
+    use super::*;
+
+    #[test]
+    fn it_names_the_top_level_directory() {
+        let dir = Path::new("models");
+
+        assert_eq!(
+            model_name(dir, &dir.join("box").join("meshes").join("box.dae")),
+            Some(String::from("box"))
+        );
+        assert_eq!(model_name(dir, &dir.join("index.json")), None);
+        assert_eq!(model_name(dir, Path::new("elsewhere/box.dae")), None);
+    }
+}
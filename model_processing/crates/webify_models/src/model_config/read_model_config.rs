@@ -0,0 +1,94 @@
+//! Metadata of a model from its `model.config`
+
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind},
+    path::Path,
+};
+
+use serde::Serialize;
+use xmltree::Element;
+
+use crate::collada::child_elements;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModelConfig {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// `Name <email>`, or just the name when there is no email
+    pub authors: Vec<String>,
+    /// SDF files of the model, relative to its directory
+    pub sdf: Vec<String>,
+}
+
+/// Read the `model.config` of the specified model directory
+pub fn read_model_config(model_dir: &Path) -> std::result::Result<ModelConfig, Error> {
+    let config = model_dir.join("model.config");
+    let root = Element::parse(BufReader::new(File::open(&config)?)).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {:?}: {}", config, e),
+        )
+    })?;
+
+    Ok(ModelConfig {
+        name: read_text(&root, "name").unwrap_or_default(),
+        version: read_text(&root, "version"),
+        description: read_text(&root, "description"),
+        authors: child_elements(&root, "author")
+            .filter_map(|author| {
+                let name = read_text(author, "name")?;
+                Some(match read_text(author, "email") {
+                    Some(email) => format!("{} <{}>", name, email),
+                    None => name,
+                })
+            })
+            .collect(),
+        sdf: child_elements(&root, "sdf")
+            .filter_map(|sdf| sdf.get_text())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect(),
+    })
+}
+
+/// Trimmed text of the first child with the given name, if it isn't empty
+fn read_text(element: &Element, name: &str) -> Option<String> {
+    child_elements(element, name)
+        .next()
+        .and_then(|child| child.get_text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod read_model_config_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_the_metadata() -> std::result::Result<(), Error> {
+        let model_dir = Path::new("tests")
+            .join("import")
+            .join("user_models")
+            .join("box");
+
+        assert_eq!(
+            read_model_config(&model_dir)?,
+            ModelConfig {
+                name: String::from("Box"),
+                version: Some(String::from("2.0")),
+                description: Some(String::from("A box, redone by a user.")),
+                authors: vec![String::from("Jane Doe <jane@example.com>")],
+                sdf: vec![String::from("model.sdf")],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_without_a_config() {
+        assert!(read_model_config(Path::new("tests")).is_err());
+    }
+}
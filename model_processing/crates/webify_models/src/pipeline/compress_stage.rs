@@ -0,0 +1,41 @@
+//! Built-in stage writing the `.gz` and `.br` siblings of the text assets
+
+use std::path::Path;
+
+use crate::compress::{process_assets, scan_dir_for_text_assets};
+use crate::config::Config;
+use crate::pipeline::{Plan, Scope, Stage};
+
+pub struct CompressStage {
+    /// Text assets smaller than this are left alone, and all of them without one
+    pub min_size: Option<u64>,
+}
+
+impl Stage for CompressStage {
+    fn name(&self) -> &str {
+        "compress"
+    }
+
+    fn scope(&self) -> Scope {
+        Scope::Tree
+    }
+
+    fn is_enabled(&self, _config: &Config) -> bool {
+        self.min_size.is_some()
+    }
+
+    fn plan(&self, dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+        Ok(Plan {
+            files: scan_dir_for_text_assets(dir, self.min_size.unwrap_or_default())?,
+        })
+    }
+
+    fn execute(
+        &self,
+        dir: &Path,
+        _config: &Config,
+        plan: Plan,
+    ) -> std::result::Result<(), std::io::Error> {
+        process_assets(dir, plan.files)
+    }
+}
@@ -0,0 +1,45 @@
+//! Built-in stage moving the textures and converting them to PNG
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::image_processing::{plan_images, process_images, Image};
+use crate::pipeline::{Plan, Stage};
+
+pub struct ImagesStage;
+
+impl Stage for ImagesStage {
+    fn name(&self) -> &str {
+        "images"
+    }
+
+    fn plan(&self, dir: &Path, config: &Config) -> std::result::Result<Plan, std::io::Error> {
+        Ok(Plan {
+            files: plan_images(dir, config)?
+                .into_iter()
+                .map(|image| image.path)
+                .collect(),
+        })
+    }
+
+    fn execute(
+        &self,
+        dir: &Path,
+        config: &Config,
+        plan: Plan,
+    ) -> std::result::Result<(), std::io::Error> {
+        let images = plan
+            .files
+            .into_iter()
+            .map(|path| Image {
+                extension: path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path,
+            })
+            .collect();
+
+        process_images(dir, config, images)
+    }
+}
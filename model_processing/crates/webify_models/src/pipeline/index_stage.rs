@@ -0,0 +1,39 @@
+//! Built-in stage writing the `index.json` of the models directory
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::index;
+use crate::model_config::list_model_dirs;
+use crate::pipeline::{Plan, Scope, Stage};
+
+pub struct IndexStage;
+
+impl Stage for IndexStage {
+    fn name(&self) -> &str {
+        "index"
+    }
+
+    fn scope(&self) -> Scope {
+        Scope::Tree
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.global().stages.index
+    }
+
+    fn plan(&self, dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+        Ok(Plan {
+            files: list_model_dirs(dir)?,
+        })
+    }
+
+    fn execute(
+        &self,
+        dir: &Path,
+        _config: &Config,
+        _plan: Plan,
+    ) -> std::result::Result<(), std::io::Error> {
+        index::process(dir)
+    }
+}
@@ -0,0 +1,40 @@
+//! Built-in stage writing the `materials.json` and `materials.gltf` of every model
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::materials;
+use crate::model_config::list_model_dirs;
+use crate::pipeline::{Plan, Scope, Stage};
+
+pub struct MaterialsStage;
+
+impl Stage for MaterialsStage {
+    fn name(&self) -> &str {
+        "materials"
+    }
+
+    fn scope(&self) -> Scope {
+        Scope::Tree
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.global().stages.materials
+    }
+
+    fn plan(&self, dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+        Ok(Plan {
+            files: list_model_dirs(dir)?,
+        })
+    }
+
+    /// Material names are global, so every script of the directory is read again
+    fn execute(
+        &self,
+        dir: &Path,
+        _config: &Config,
+        _plan: Plan,
+    ) -> std::result::Result<(), std::io::Error> {
+        materials::process(dir)
+    }
+}
@@ -0,0 +1,33 @@
+//! Built-in stage renaming the texture references of the meshes, and normalizing them
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::mesh_update::{plan_meshes, process_meshes};
+use crate::pipeline::{Plan, Stage};
+
+pub struct MeshesStage {
+    /// Normalize every mesh, whatever the settings of its model say
+    pub normalize: bool,
+}
+
+impl Stage for MeshesStage {
+    fn name(&self) -> &str {
+        "meshes"
+    }
+
+    fn plan(&self, dir: &Path, config: &Config) -> std::result::Result<Plan, std::io::Error> {
+        Ok(Plan {
+            files: plan_meshes(dir, config)?,
+        })
+    }
+
+    fn execute(
+        &self,
+        dir: &Path,
+        config: &Config,
+        plan: Plan,
+    ) -> std::result::Result<(), std::io::Error> {
+        process_meshes(dir, self.normalize, config, plan.files)
+    }
+}
@@ -0,0 +1,21 @@
+//! The whole webify pipeline, for the command line and for crates using webify_models as
+//! a library. It's made of stages, the built-in ones and any registered with
+//! `Pipeline::stage`, which `webify.toml` can reorder or leave out.
+
+mod compress_stage;
+mod images_stage;
+mod index_stage;
+mod materials_stage;
+mod meshes_stage;
+mod pipeline_builder;
+mod run_stages;
+mod stage;
+
+pub use self::compress_stage::CompressStage;
+pub use self::images_stage::ImagesStage;
+pub use self::index_stage::IndexStage;
+pub use self::materials_stage::MaterialsStage;
+pub use self::meshes_stage::MeshesStage;
+pub use self::pipeline_builder::Pipeline;
+pub use self::run_stages::run_stages;
+pub use self::stage::{Plan, Scope, Stage, StageReport};
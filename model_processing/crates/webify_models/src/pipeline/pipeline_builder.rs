@@ -0,0 +1,369 @@
+//! Builder for a run of the webify pipeline on a models directory
+
+use std::{
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::config::{load_config, Config};
+use crate::context::RunContext;
+use crate::filter::PathFilter;
+use crate::pipeline::{
+    run_stages, CompressStage, ImagesStage, IndexStage, MaterialsStage, MeshesStage, Scope, Stage,
+    StageReport,
+};
+use crate::progress::ProgressMode;
+use crate::report::Recorder;
+use crate::{cache, compress};
+
+/// Convert the textures and update the meshes of every model, then write the materials
+/// and the index, and pre-compress the text assets when asked to. Registered stages run
+/// after the built-in ones of their scope, unless the `order` of `webify.toml` says
+/// otherwise. Each pipeline scans, shows progress and records with its own options, so
+/// several can run in the same process.
+///
+/// ```no_run
+/// use std::path::Path;
+/// use webify_models::Pipeline;
+///
+/// let models = Path::new("models");
+/// Pipeline::for_dir(models)?.normalize(true).run(models)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Pipeline {
+    config: Config,
+    images: ImagesStage,
+    meshes: MeshesStage,
+    materials: MaterialsStage,
+    index: IndexStage,
+    compress: CompressStage,
+    registered: Vec<Box<dyn Stage>>,
+    cache_dir: Option<PathBuf>,
+    context: RunContext,
+}
+
+impl Pipeline {
+    /// Pipeline with the settings of `config`, which also decide whether to compress
+    pub fn new(config: Config) -> Pipeline {
+        let min_size = if config.global().stages.compress {
+            Some(compress::DEFAULT_MIN_SIZE)
+        } else {
+            None
+        };
+
+        Pipeline {
+            config,
+            images: ImagesStage,
+            meshes: MeshesStage { normalize: false },
+            materials: MaterialsStage,
+            index: IndexStage,
+            compress: CompressStage { min_size },
+            registered: vec![],
+            cache_dir: None,
+            context: RunContext::default(),
+        }
+    }
+
+    /// Pipeline with the `webify.toml` settings of the models directory
+    pub fn for_dir(dir: &Path) -> std::result::Result<Pipeline, std::io::Error> {
+        Ok(Pipeline::new(load_config(dir)?))
+    }
+
+    /// Also normalize the meshes, whatever the settings say
+    pub fn normalize(mut self, normalize: bool) -> Pipeline {
+        self.meshes.normalize = normalize;
+        self
+    }
+
+    /// Pre-compress the text assets of at least `min_size` bytes
+    pub fn compress(mut self, min_size: u64) -> Pipeline {
+        self.compress.min_size = Some(min_size);
+        self
+    }
+
+    /// Skip the models that are already converted in `cache_dir`
+    pub fn cache(mut self, cache_dir: &Path) -> Pipeline {
+        self.cache_dir = Some(cache_dir.to_path_buf());
+        self
+    }
+
+    /// Only scan the files `filter` keeps, like the ones of `--include`, `--exclude` and
+    /// the `.webifyignore`, instead of leaving out the thumbnails and version control
+    pub fn filter(mut self, filter: PathFilter) -> Pipeline {
+        self.context.filter = filter;
+        self
+    }
+
+    /// Show progress with `mode`, instead of the way that suits the terminal
+    pub fn progress(mut self, mode: ProgressMode) -> Pipeline {
+        self.context.progress = mode;
+        self
+    }
+
+    /// Record every file action of the runs in `recorder`, for a report
+    pub fn record(mut self, recorder: &Recorder) -> Pipeline {
+        self.context.recorder = Some(recorder.clone());
+        self
+    }
+
+    /// Add a stage of our own, like watermarking the textures or checking the licenses
+    pub fn stage(mut self, stage: impl Stage + 'static) -> Pipeline {
+        self.registered.push(Box::new(stage));
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Every stage, in the order they run in. Without an `order` in `webify.toml`, the
+    /// built-in stages come first, then the registered ones.
+    pub fn stages(&self) -> std::result::Result<Vec<&dyn Stage>, std::io::Error> {
+        let mut stages: Vec<&dyn Stage> = vec![
+            &self.images,
+            &self.meshes,
+            &self.materials,
+            &self.index,
+            &self.compress,
+        ];
+        stages.extend(self.registered.iter().map(|stage| stage.as_ref()));
+
+        match &self.config.global().stages.order {
+            None => Ok(stages),
+            Some(order) => order
+                .iter()
+                .map(|name| {
+                    stages
+                        .iter()
+                        .find(|stage| stage.name() == name)
+                        .copied()
+                        .ok_or_else(|| {
+                            Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("Invalid order of stages: there's no {:?} stage", name),
+                            )
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    /// Run the whole pipeline on a models directory
+    pub fn run(&self, dir: &Path) -> std::result::Result<Vec<StageReport>, std::io::Error> {
+        let _context = self.context.enter_dir(dir);
+        let cache_dir = match &self.cache_dir {
+            Some(cache_dir) => cache_dir,
+            None => {
+                let mut reports = self.convert(dir)?;
+                reports.extend(self.finish(dir)?);
+                return Ok(reports);
+            }
+        };
+
+        let names: Vec<&str> = self.stages()?.iter().map(|stage| stage.name()).collect();
+        // Models cached with other globs were scanned differently, so they don't count
+        let settings = format!(
+            "webify_models {} stages={:?} normalize={} compress={:?} filter={:?} config={:?}",
+            env!("CARGO_PKG_VERSION"),
+            names,
+            self.meshes.normalize,
+            self.compress.min_size,
+            self.context.filter.globs(),
+            self.config
+        );
+        let (mut reports, mut finish_reports) = (vec![], vec![]);
+        cache::process(
+            dir,
+            cache_dir,
+            &settings,
+            |dir| {
+                reports = self.convert(dir)?;
+                Ok(())
+            },
+            |dir| {
+                finish_reports = self.finish(dir)?;
+                Ok(())
+            },
+        )?;
+        reports.extend(finish_reports);
+
+        Ok(reports)
+    }
+
+    /// Run the stages converting single models on the models of a directory
+    pub fn convert(&self, dir: &Path) -> std::result::Result<Vec<StageReport>, std::io::Error> {
+        let _context = self.context.enter_dir(dir);
+        run_stages(&self.stages()?, Scope::Model, dir, &self.config)
+    }
+
+    /// Run the stages that depend on every model of the directory, like writing the
+    /// materials and the index, so they always run on the whole tree. Stages disabled by
+    /// the root `webify.toml` are skipped.
+    pub fn finish(&self, dir: &Path) -> std::result::Result<Vec<StageReport>, std::io::Error> {
+        let _context = self.context.enter_dir(dir);
+        run_stages(&self.stages()?, Scope::Tree, dir, &self.config)
+    }
+}
+
+#[cfg(test)]
+mod pipeline_tests {
+    use super::*;
+    use crate::import::copy_dir;
+    use crate::pipeline::Plan;
+    use crate::report::FileAction;
+    use std::fs;
+
+    struct Watermark;
+
+    impl Stage for Watermark {
+        fn name(&self) -> &str {
+            "watermark"
+        }
+
+        fn plan(&self, _dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+            Ok(Plan::default())
+        }
+
+        fn execute(
+            &self,
+            _dir: &Path,
+            _config: &Config,
+            _plan: Plan,
+        ) -> std::result::Result<(), std::io::Error> {
+            Ok(())
+        }
+    }
+
+    /// Stage recording an action on the directory it runs on
+    struct Stamp;
+
+    impl Stage for Stamp {
+        fn name(&self) -> &str {
+            "stamp"
+        }
+
+        fn plan(&self, _dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+            Ok(Plan::default())
+        }
+
+        fn execute(
+            &self,
+            dir: &Path,
+            _config: &Config,
+            _plan: Plan,
+        ) -> std::result::Result<(), std::io::Error> {
+            FileAction::start("stamp", dir).finish(dir);
+            Ok(())
+        }
+    }
+
+    fn stage_names(pipeline: &Pipeline) -> std::result::Result<Vec<&str>, std::io::Error> {
+        Ok(pipeline
+            .stages()?
+            .iter()
+            .map(|stage| stage.name())
+            .collect())
+    }
+
+    #[test]
+    fn it_compresses_when_the_config_says_so() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("config").join("models");
+        let pipeline = Pipeline::for_dir(&dir)?;
+
+        assert_eq!(pipeline.compress.min_size, Some(compress::DEFAULT_MIN_SIZE));
+        assert_eq!(pipeline.compress(1).compress.min_size, Some(1));
+        assert_eq!(Pipeline::new(Config::default()).compress.min_size, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_runs_registered_stages_last() -> std::result::Result<(), std::io::Error> {
+        let pipeline = Pipeline::new(Config::default()).stage(Watermark);
+
+        assert_eq!(
+            stage_names(&pipeline)?,
+            vec![
+                "images",
+                "meshes",
+                "materials",
+                "index",
+                "compress",
+                "watermark"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_follows_the_order_of_the_config() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("pipeline").join("reordered");
+        let pipeline = Pipeline::for_dir(&dir)?.stage(Watermark);
+
+        assert_eq!(
+            stage_names(&pipeline)?,
+            vec!["watermark", "meshes", "images", "index"]
+        );
+        assert_eq!(
+            Pipeline::for_dir(&dir)?.stages().err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidInput)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_records_each_pipeline_on_its_own() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("pipeline").join("stamped");
+        let (first, second) = (Recorder::default(), Recorder::default());
+        let one = Pipeline::for_dir(&dir)?.stage(Stamp).record(&first);
+        let two = Pipeline::for_dir(&dir)?.stage(Stamp).record(&second);
+
+        one.convert(&dir)?;
+        two.convert(&dir)?;
+        one.convert(&dir)?;
+
+        assert_eq!(first.take().len(), 2);
+        assert_eq!(second.take().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_caches_per_filter() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests")
+            .join("pipeline")
+            .join("test_run_it_caches_per_filter");
+        let dir = test_dir.join("models");
+        copy_dir(
+            &Path::new("tests").join("import").join("gazebo_models"),
+            &dir,
+        )?;
+        fs::copy(
+            Path::new("tests")
+                .join("pipeline")
+                .join("stamped")
+                .join("webify.toml"),
+            dir.join("webify.toml"),
+        )?;
+
+        // How many times the models were converted rather than taken from the cache
+        let stamps = |filter: PathFilter| -> std::result::Result<usize, std::io::Error> {
+            let recorder = Recorder::default();
+            Pipeline::for_dir(&dir)?
+                .stage(Stamp)
+                .filter(filter)
+                .cache(&test_dir.join("cache"))
+                .record(&recorder)
+                .run(&dir)?;
+            let actions = recorder.take();
+            Ok(actions.iter().filter(|a| a.action == "stamp").count())
+        };
+        assert_eq!(stamps(PathFilter::default())?, 1);
+        assert_eq!(stamps(PathFilter::default())?, 0);
+        assert_eq!(stamps(PathFilter::new(&[], &["*.psd".to_string()])?)?, 1);
+
+        fs::remove_dir_all(test_dir)
+    }
+}
@@ -0,0 +1,149 @@
+//! Runner going through the stages of a pipeline
+
+use std::{path::Path, time::Instant};
+
+use tracing::{debug, error, info, info_span};
+
+use crate::config::Config;
+use crate::pipeline::{Scope, Stage, StageReport};
+use crate::progress::{emit, ProgressEvent};
+
+/// Plan, execute and report every stage of `scope` in turn on the models directory
+/// `dir`, skipping the ones the settings disable. Every stage emits progress events as it
+/// starts and finishes, or fails.
+pub fn run_stages(
+    stages: &[&dyn Stage],
+    scope: Scope,
+    dir: &Path,
+    config: &Config,
+) -> std::result::Result<Vec<StageReport>, std::io::Error> {
+    let mut reports = vec![];
+    for stage in stages
+        .iter()
+        .filter(|stage| stage.scope() == scope && stage.is_enabled(config))
+    {
+        let _stage = info_span!("stage", name = stage.name()).entered();
+        let started = Instant::now();
+        let plan = stage
+            .plan(dir, config)
+            .map_err(|e| stage_failed(*stage, e))?;
+        let files = plan.files.len();
+        debug!("Planned {} file(s)", files);
+        emit(ProgressEvent::StageStarted {
+            stage: stage.name(),
+            files,
+        });
+        stage
+            .execute(dir, config, plan)
+            .map_err(|e| stage_failed(*stage, e))?;
+
+        let report = stage.report(files, started.elapsed());
+        info!(
+            "Done with {} file(s) in {:.0} ms",
+            files, report.duration_ms
+        );
+        emit(ProgressEvent::StageFinished { report: &report });
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+/// Emit the error a stage failed with, before handing it back
+fn stage_failed(stage: &dyn Stage, error: std::io::Error) -> std::io::Error {
+    error!("Failed: {}", error);
+    emit(ProgressEvent::Error {
+        path: None,
+        message: format!("{} stage failed: {}", stage.name(), error),
+    });
+    error
+}
+
+#[cfg(test)]
+mod run_stages_tests {
+    use super::*;
+    use crate::pipeline::Plan;
+    use std::{cell::RefCell, path::PathBuf};
+
+    /// Stage noting what it's asked to do
+    struct Recorder<'a> {
+        name: &'a str,
+        scope: Scope,
+        calls: &'a RefCell<Vec<String>>,
+    }
+
+    impl Stage for Recorder<'_> {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn scope(&self) -> Scope {
+            self.scope
+        }
+
+        fn plan(&self, dir: &Path, _config: &Config) -> std::result::Result<Plan, std::io::Error> {
+            self.calls.borrow_mut().push(format!("plan {}", self.name));
+            Ok(Plan {
+                files: vec![dir.join("a"), dir.join("b")],
+            })
+        }
+
+        fn execute(
+            &self,
+            _dir: &Path,
+            _config: &Config,
+            plan: Plan,
+        ) -> std::result::Result<(), std::io::Error> {
+            self.calls
+                .borrow_mut()
+                .push(format!("execute {} on {}", self.name, plan.files.len()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_runs_the_stages_of_the_scope_in_order() -> std::result::Result<(), std::io::Error> {
+        let calls = RefCell::new(vec![]);
+        let watermark = Recorder {
+            name: "watermark",
+            scope: Scope::Model,
+            calls: &calls,
+        };
+        let license = Recorder {
+            name: "license",
+            scope: Scope::Tree,
+            calls: &calls,
+        };
+        let stamp = Recorder {
+            name: "stamp",
+            scope: Scope::Model,
+            calls: &calls,
+        };
+
+        let reports = run_stages(
+            &[&watermark, &license, &stamp],
+            Scope::Model,
+            &PathBuf::from("models"),
+            &Config::default(),
+        )?;
+
+        assert_eq!(
+            calls.into_inner(),
+            vec![
+                "plan watermark",
+                "execute watermark on 2",
+                "plan stamp",
+                "execute stamp on 2"
+            ]
+        );
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.stage.as_str(), report.files))
+                .collect::<Vec<_>>(),
+            vec![("watermark", 2), ("stamp", 2)]
+        );
+
+        Ok(())
+    }
+}
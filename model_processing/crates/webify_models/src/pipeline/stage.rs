@@ -0,0 +1,70 @@
+//! What a step of the pipeline has to implement, for the built-in stages and for the
+//! ones registered by crates using webify_models
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// What a stage works on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Models one by one, so with `--cache` it only runs on the models that changed
+    Model,
+    /// The whole models directory, after every model is converted
+    Tree,
+}
+
+/// The files a stage is about to work on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub files: Vec<PathBuf>,
+}
+
+/// What a stage did, once executed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageReport {
+    pub stage: String,
+    pub files: usize,
+    pub duration_ms: f64,
+}
+
+/// A step of the pipeline. The runner asks it for a plan of the files it works on in a
+/// models directory, executes that plan, then asks it for a report.
+pub trait Stage {
+    /// Name the stage goes by in the `order` of `webify.toml`
+    fn name(&self) -> &str;
+
+    fn scope(&self) -> Scope {
+        Scope::Model
+    }
+
+    /// Whether the settings let the stage run at all
+    fn is_enabled(&self, _config: &Config) -> bool {
+        true
+    }
+
+    /// Find what the stage would work on in `dir`, without changing anything
+    fn plan(&self, dir: &Path, config: &Config) -> std::result::Result<Plan, std::io::Error>;
+
+    /// Work on the planned files
+    fn execute(
+        &self,
+        dir: &Path,
+        config: &Config,
+        plan: Plan,
+    ) -> std::result::Result<(), std::io::Error>;
+
+    /// Sum up a run of the stage on the `files` it planned, which took `elapsed`
+    fn report(&self, files: usize, elapsed: Duration) -> StageReport {
+        StageReport {
+            stage: self.name().to_string(),
+            files,
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+        }
+    }
+}
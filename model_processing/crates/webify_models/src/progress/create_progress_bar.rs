@@ -2,7 +2,10 @@
 
 use indicatif::{ProgressBar, ProgressStyle};
 
-pub fn create_progress_bar(item_count: u64) -> ProgressBar {
+use crate::progress::Progress;
+
+/// Progress of `item_count` items, as a bar or as lines depending on the progress mode
+pub fn create_progress_bar(item_count: u64) -> Progress {
     let bar = ProgressBar::new(item_count);
     let bar_style = ProgressStyle::default_bar()
     .template(
@@ -12,7 +15,7 @@ pub fn create_progress_bar(item_count: u64) -> ProgressBar {
 
     bar.set_style(bar_style);
 
-    bar
+    Progress::new(item_count, bar)
 }
 
 #[cfg(test)]
@@ -21,13 +24,13 @@ mod create_progress_bar_tests {
 
     #[test]
     fn it_returns_a_progress_bar() {
-        let progress_bar: ProgressBar = create_progress_bar(10);
+        let progress_bar: Progress = create_progress_bar(10);
         assert_eq!(progress_bar.length(), 10);
     }
 
     #[test]
     fn it_accepts_zero_length() {
-        let progress_bar: ProgressBar = create_progress_bar(0);
+        let progress_bar: Progress = create_progress_bar(0);
         assert_eq!(progress_bar.length(), 0);
     }
 }
@@ -0,0 +1,9 @@
+//! The progress mode the run going on shows its progress with
+
+use crate::context::RunContext;
+use crate::progress::ProgressMode;
+
+/// The mode progress is shown with, never `Auto`
+pub fn progress_mode() -> ProgressMode {
+    RunContext::current().progress.resolve()
+}
@@ -0,0 +1,16 @@
+//! Set of functions to show how a run is going, as progress bars in a terminal, plain
+//! lines in CI logs, or newline-delimited JSON events for tools wrapping webify_models
+
+mod create_progress_bar;
+mod current_mode;
+mod print_message;
+mod progress_bar;
+mod progress_event;
+mod progress_mode;
+
+pub use self::create_progress_bar::create_progress_bar;
+pub use self::current_mode::progress_mode;
+pub use self::print_message::{print_error, print_message, print_warning};
+pub use self::progress_bar::Progress;
+pub use self::progress_event::{emit, ProgressEvent};
+pub use self::progress_mode::ProgressMode;
@@ -0,0 +1,38 @@
+//! Print what isn't tied to a progress bar in a way that suits the progress mode, and log
+//! warnings and errors so `-q` and `--log-file` apply to them
+
+use std::{fmt::Display, path::Path};
+
+use console::strip_ansi_codes;
+use tracing::{error, warn};
+
+use crate::progress::{emit, progress_mode, ProgressEvent, ProgressMode};
+
+/// Print a line for whoever reads the output, which JSON progress leaves out
+pub fn print_message(message: impl Display) {
+    if progress_mode() != ProgressMode::Json {
+        println!("{}", message);
+    }
+}
+
+/// Log a warning, about `path` when there's one, and emit it as a JSON event, both
+/// without colors
+pub fn print_warning(message: impl Display, path: Option<&Path>) {
+    let message = strip_ansi_codes(&message.to_string()).to_string();
+    match path {
+        Some(path) => warn!(?path, "{}", message),
+        None => warn!("{}", message),
+    }
+    emit(ProgressEvent::Warning { path, message });
+}
+
+/// Log an error, about `path` when there's one, and emit it as a JSON event, both
+/// without colors
+pub fn print_error(message: impl Display, path: Option<&Path>) {
+    let message = strip_ansi_codes(&message.to_string()).to_string();
+    match path {
+        Some(path) => error!(?path, "{}", message),
+        None => error!("{}", message),
+    }
+    emit(ProgressEvent::Error { path, message });
+}
@@ -0,0 +1,85 @@
+//! Progress of a single step, drawn as a bar or printed as lines depending on the mode
+
+use std::sync::Mutex;
+
+use console::strip_ansi_codes;
+use indicatif::ProgressBar;
+
+use crate::progress::{emit, progress_mode, ProgressEvent, ProgressMode};
+
+/// Works like the indicatif bar it wraps. Without bars, the bar is hidden and only
+/// keeps count: plain progress prints every message as a line instead, and JSON progress
+/// turns what's printed into warnings without colors, leaving the rest to the file events.
+pub struct Progress {
+    bar: ProgressBar,
+    mode: ProgressMode,
+    prefix: Mutex<String>,
+}
+
+impl Progress {
+    /// Progress of `item_count` items, shown like the `bar` says when there are bars
+    pub fn new(item_count: u64, bar: ProgressBar) -> Progress {
+        let mode = progress_mode();
+        let bar = match mode {
+            ProgressMode::Bar => bar,
+            _ => ProgressBar::hidden(),
+        };
+        bar.set_length(item_count);
+
+        Progress {
+            bar,
+            mode,
+            prefix: Mutex::new(String::new()),
+        }
+    }
+
+    pub fn set_prefix(&self, prefix: &str) {
+        self.bar.set_prefix(prefix);
+        *self.prefix.lock().unwrap_or_else(|e| e.into_inner()) = prefix.to_string();
+    }
+
+    pub fn set_message(&self, message: &str) {
+        self.bar.set_message(message);
+        if self.mode == ProgressMode::Plain {
+            println!("{}", self.line(message));
+        }
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    pub fn length(&self) -> u64 {
+        self.bar.length()
+    }
+
+    pub fn println<I: Into<String>>(&self, message: I) {
+        match self.mode {
+            ProgressMode::Json => emit(ProgressEvent::Warning {
+                path: None,
+                message: strip_ansi_codes(&message.into()).to_string(),
+            }),
+            ProgressMode::Plain => println!("{}", message.into()),
+            _ => self.bar.println(message),
+        }
+    }
+
+    pub fn finish_with_message(&self, message: &str) {
+        self.bar.finish_with_message(message);
+        if self.mode == ProgressMode::Plain {
+            println!("{}", self.line(message));
+        }
+    }
+
+    /// `message` as a plain line, with the prefix and how far along the step is
+    fn line(&self, message: &str) -> String {
+        let prefix = self.prefix.lock().unwrap_or_else(|e| e.into_inner());
+        format!(
+            "{} [{}/{}] {}",
+            prefix,
+            self.bar.position(),
+            self.bar.length(),
+            message
+        )
+    }
+}
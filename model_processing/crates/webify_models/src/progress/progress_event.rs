@@ -0,0 +1,95 @@
+//! Events written, one JSON object per line, with `--progress=json`
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::pipeline::StageReport;
+use crate::progress::{progress_mode, ProgressMode};
+use crate::report::FileAction;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    /// A stage planned its work and is starting on the `files` it found
+    StageStarted { stage: &'a str, files: usize },
+    FileProcessed {
+        #[serde(flatten)]
+        action: &'a FileAction,
+    },
+    Warning {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a Path>,
+        message: String,
+    },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a Path>,
+        message: String,
+    },
+    StageFinished {
+        #[serde(flatten)]
+        report: &'a StageReport,
+    },
+}
+
+impl ProgressEvent<'_> {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Write the event on its own line, when showing progress as JSON
+pub fn emit(event: ProgressEvent) {
+    if progress_mode() == ProgressMode::Json {
+        println!("{}", event.to_json());
+    }
+}
+
+#[cfg(test)]
+mod progress_event_tests {
+    use super::*;
+
+    #[test]
+    fn it_tags_the_events() {
+        let report = StageReport {
+            stage: String::from("images"),
+            files: 3,
+            duration_ms: 1.5,
+        };
+
+        assert_eq!(
+            ProgressEvent::StageStarted {
+                stage: "images",
+                files: 3
+            }
+            .to_json(),
+            r#"{"event":"stage_started","stage":"images","files":3}"#
+        );
+        assert_eq!(
+            ProgressEvent::StageFinished { report: &report }.to_json(),
+            r#"{"event":"stage_finished","stage":"images","files":3,"duration_ms":1.5}"#
+        );
+        assert_eq!(
+            ProgressEvent::Warning {
+                path: None,
+                message: String::from("careful")
+            }
+            .to_json(),
+            r#"{"event":"warning","message":"careful"}"#
+        );
+    }
+
+    #[test]
+    fn it_flattens_the_file_actions() {
+        let source = Path::new("box.jpg");
+        let action = FileAction::start("convert", source);
+
+        let event: serde_json::Value =
+            serde_json::from_str(&ProgressEvent::FileProcessed { action: &action }.to_json())
+                .unwrap();
+        assert_eq!(event["event"], "file_processed");
+        assert_eq!(event["action"], "convert");
+        assert_eq!(event["source"], "box.jpg");
+    }
+}
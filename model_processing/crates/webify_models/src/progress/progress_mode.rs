@@ -0,0 +1,59 @@
+//! How progress is shown, as picked with `--progress`
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressMode {
+    /// Bars in a terminal, plain lines otherwise
+    #[default]
+    Auto,
+    /// Progress bars, with colors
+    Bar,
+    /// One line of text per step, without colors
+    Plain,
+    /// One JSON event per line, and nothing else
+    Json,
+}
+
+impl ProgressMode {
+    /// The mode `Auto` stands for where the output goes, or the mode itself otherwise
+    pub fn resolve(self) -> ProgressMode {
+        match self {
+            ProgressMode::Auto if console::user_attended() => ProgressMode::Bar,
+            ProgressMode::Auto => ProgressMode::Plain,
+            mode => mode,
+        }
+    }
+}
+
+impl FromStr for ProgressMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<ProgressMode, String> {
+        match value {
+            "auto" => Ok(ProgressMode::Auto),
+            "bar" => Ok(ProgressMode::Bar),
+            "plain" => Ok(ProgressMode::Plain),
+            "json" => Ok(ProgressMode::Json),
+            _ => Err(format!("{:?} isn't one of auto, bar, plain or json", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod progress_mode_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_the_modes() {
+        assert_eq!("json".parse(), Ok(ProgressMode::Json));
+        assert_eq!("plain".parse(), Ok(ProgressMode::Plain));
+        assert!("fancy".parse::<ProgressMode>().is_err());
+    }
+
+    #[test]
+    fn it_only_resolves_auto() {
+        assert_eq!(ProgressMode::Json.resolve(), ProgressMode::Json);
+        assert_ne!(ProgressMode::Auto.resolve(), ProgressMode::Auto);
+    }
+}
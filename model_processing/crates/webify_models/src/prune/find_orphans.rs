@@ -0,0 +1,176 @@
+//! Find the assets of the models that can't be reached from any model
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::filter::is_excluded;
+use crate::image_processing::scan_dir_for_images::TEXTURE_IMAGE_TYPES;
+use crate::model_config::list_model_dirs;
+use crate::references::{ReferenceGraph, Resolution};
+use crate::walk::walk_all_assets;
+
+/// Extensions, besides textures, of the files that are only kept when referenced
+const ASSET_TYPES: [&str; 7] = ["dae", "obj", "mtl", "stl", "material", "psd", "xcf"];
+
+/// Extensions of the pre-compressed siblings of the assets
+const COMPRESSED_TYPES: [&str; 2] = ["gz", "br"];
+
+/// An asset nothing refers to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Orphan {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Walk the reference graph from the `model.config` of every model, the worlds and the
+/// `thumbnails/0.png` the index points at, and return the assets of the models that
+/// aren't reached, sorted by path. Only textures, meshes, material scripts, image
+/// sources and their compressed siblings are candidates; files outside of the models
+/// (directories with a `model.config`), like Gazebo's `media`, and the files the scan
+/// filter excludes are left alone. Thumbnails are only excluded by default so they're
+/// not taken for textures, so old ones are still candidates.
+pub fn find_orphans(
+    dir: &Path,
+    graph: &ReferenceGraph,
+) -> std::result::Result<Vec<Orphan>, std::io::Error> {
+    let model_dirs = list_model_dirs(dir)?;
+
+    let mut files = vec![];
+    for model_dir in model_dirs.iter() {
+        files.extend(
+            walk_all_assets(model_dir)?
+                .into_iter()
+                .map(|asset| asset.path),
+        );
+    }
+
+    let mut targets: HashMap<&Path, Vec<&Path>> = HashMap::new();
+    for (reference, resolution) in graph.references.iter() {
+        if let Resolution::Found(target) = resolution {
+            targets.entry(&reference.source).or_default().push(target);
+        }
+    }
+
+    let mut pending: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| {
+            file.file_name().is_some_and(|name| name == "model.config")
+                || extension(file) == "world"
+        })
+        .cloned()
+        .collect();
+    pending.extend(
+        model_dirs
+            .iter()
+            .map(|model_dir| model_dir.join("thumbnails").join("0.png")),
+    );
+
+    let mut reached: BTreeSet<PathBuf> = BTreeSet::new();
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            // Like a material script directory, everything in it is used
+            pending.extend(walk_all_assets(&path)?.into_iter().map(|asset| asset.path));
+        } else if reached.insert(path.clone()) {
+            if let Some(found) = targets.get(path.as_path()) {
+                pending.extend(found.iter().map(|target| target.to_path_buf()));
+            }
+        }
+    }
+
+    let mut orphans = vec![];
+    for file in files {
+        let is_thumbnail = file
+            .parent()
+            .is_some_and(|parent| parent.file_name().is_some_and(|name| name == "thumbnails"));
+        if is_excluded(dir, &file) && !is_thumbnail {
+            continue;
+        }
+        if is_candidate(&file) && !is_reached(&file, &reached) {
+            orphans.push(Orphan {
+                size: fs::metadata(&file)?.len(),
+                path: file,
+            });
+        }
+    }
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(orphans)
+}
+
+/// Whether a file is an asset, or the compressed sibling of one
+fn is_candidate(file: &Path) -> bool {
+    let extension = extension(file);
+    if COMPRESSED_TYPES.contains(&extension.as_str()) {
+        return is_candidate(&file.with_extension(""));
+    }
+
+    TEXTURE_IMAGE_TYPES.contains(&extension.as_str()) || ASSET_TYPES.contains(&extension.as_str())
+}
+
+/// Whether a file, or the file it's the compressed sibling of, was reached
+fn is_reached(file: &Path, reached: &BTreeSet<PathBuf>) -> bool {
+    if COMPRESSED_TYPES.contains(&extension(file).as_str()) {
+        return is_reached(&file.with_extension(""), reached);
+    }
+
+    reached.contains(file)
+}
+
+/// Lowercase extension of a file, empty when it has none
+fn extension(file: &Path) -> String {
+    file.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod find_orphans_tests {
+    use super::*;
+    use crate::references::build_reference_graph;
+
+    #[test]
+    fn it_finds_what_nothing_refers_to() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("prune").join("models");
+        let model_dir = dir.join("crate_box");
+        let graph = build_reference_graph(&dir, &[])?;
+
+        let orphans = find_orphans(&dir, &graph)?;
+        assert_eq!(
+            orphans.iter().map(|o| o.path.clone()).collect::<Vec<_>>(),
+            vec![
+                model_dir
+                    .join("materials")
+                    .join("textures")
+                    .join("crate.psd"),
+                model_dir
+                    .join("materials")
+                    .join("textures")
+                    .join("crate_old.png"),
+                // Only old.dae uses it
+                model_dir
+                    .join("materials")
+                    .join("textures")
+                    .join("scratches.png"),
+                model_dir.join("meshes").join("old.dae"),
+                model_dir.join("meshes").join("old.dae.gz"),
+                model_dir.join("thumbnails").join("1.png"),
+            ]
+        );
+        assert_eq!(orphans[0].size, 29);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_leaves_everything_referenced() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("references").join("models");
+        let graph = build_reference_graph(&dir, &[])?;
+
+        assert!(find_orphans(&dir, &graph)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+//! Find, and optionally delete, the assets nothing refers to anymore
+
+mod find_orphans;
+mod process;
+
+pub use self::find_orphans::find_orphans;
+pub use self::process::process;
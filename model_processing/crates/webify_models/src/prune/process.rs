@@ -0,0 +1,92 @@
+//! Orchestrator to report, and optionally delete, the assets nothing refers to
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+use tracing::{info, info_span};
+
+use crate::logging::model_span;
+use crate::progress::print_message;
+use crate::prune::find_orphans;
+use crate::references::build_reference_graph;
+use crate::report::FileAction;
+use crate::stats::format_bytes;
+
+/// Orchestrator to print every unreferenced asset with its size, and delete them when
+/// `apply` is given. `model://` URIs are also looked up in the `search_roots`, so that
+/// assets used from other model paths are kept.
+pub fn process(
+    dir: &Path,
+    search_roots: &[PathBuf],
+    apply: bool,
+) -> std::result::Result<(), std::io::Error> {
+    let _stage = info_span!("stage", name = "prune").entered();
+    info!("Following references");
+    let graph = build_reference_graph(dir, search_roots)?;
+    let orphans = find_orphans(dir, &graph)?;
+
+    for orphan in orphans.iter() {
+        print_message(format!(
+            "{} {}",
+            style(format!("{:>10}", format_bytes(orphan.size))).dim(),
+            orphan.path.to_string_lossy()
+        ));
+    }
+
+    let total = format_bytes(orphans.iter().map(|orphan| orphan.size).sum());
+    if orphans.is_empty() {
+        print_message(style("Nothing to prune!").green());
+    } else if apply {
+        for orphan in orphans.iter() {
+            let _model = model_span(dir, &orphan.path).entered();
+            let action = FileAction::start("prune", &orphan.path);
+            match fs::remove_file(&orphan.path) {
+                Ok(()) => action.finish(&orphan.path),
+                Err(e) => action.fail(e),
+            }
+        }
+        print_message(style(format!("Pruned {} file(s), {}", orphans.len(), total)).green());
+    } else {
+        print_message(format!(
+            "{} file(s), {}, can be pruned with --apply",
+            style(orphans.len()).bold().blue(),
+            total
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::import::copy_dir;
+
+    #[test]
+    fn it_deletes_only_when_applied() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests")
+            .join("prune")
+            .join("test_run_it_deletes_only_when_applied");
+        copy_dir(&Path::new("tests").join("prune").join("models"), &dir)?;
+        let meshes = dir.join("crate_box").join("meshes");
+
+        process(&dir, &[], false)?;
+        assert!(meshes.join("old.dae").is_file());
+
+        process(&dir, &[], true)?;
+        assert!(!meshes.join("old.dae").exists());
+        assert!(!meshes.join("old.dae.gz").exists());
+        assert!(meshes.join("crate.dae").is_file());
+        assert!(meshes.join("crate.dae.gz").is_file());
+        assert!(dir
+            .join("crate_box")
+            .join("thumbnails")
+            .join("0.png")
+            .is_file());
+
+        fs::remove_dir_all(&dir)
+    }
+}
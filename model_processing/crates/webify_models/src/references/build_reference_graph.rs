@@ -0,0 +1,121 @@
+//! Collect and resolve the references of every file of a models directory
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::references::{
+    collect_dae_references, collect_obj_references, collect_script_references,
+    collect_sdf_references, resolve_reference, Reference, Resolution,
+};
+use crate::walk::{walk_assets, AssetKind};
+
+/// Every reference of a models directory, with where it leads
+#[derive(Debug, Default)]
+pub struct ReferenceGraph {
+    pub references: Vec<(Reference, Resolution)>,
+    /// Files that couldn't be read or parsed, with why
+    pub unreadable: Vec<(PathBuf, String)>,
+}
+
+impl ReferenceGraph {
+    /// The references that lead nowhere
+    pub fn dangling(&self) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(|(_, resolution)| *resolution == Resolution::Dangling)
+            .map(|(reference, _)| reference)
+    }
+}
+
+/// Collect the references of the meshes, material libraries, material scripts, SDFs,
+/// worlds and model configs of `dir` that the scan filter doesn't exclude, and resolve
+/// them against the directory and the `search_roots`
+pub fn build_reference_graph(
+    dir: &Path,
+    search_roots: &[PathBuf],
+) -> std::result::Result<ReferenceGraph, std::io::Error> {
+    let assets = walk_assets(dir)?;
+
+    let mut textures: HashMap<String, PathBuf> = HashMap::new();
+    for asset in assets
+        .iter()
+        .filter(|asset| asset.kind == AssetKind::Texture)
+    {
+        if let Some(name) = asset.path.file_name() {
+            textures
+                .entry(name.to_string_lossy().to_string())
+                .or_insert_with(|| asset.path.clone());
+        }
+    }
+
+    let mut roots = vec![dir.to_path_buf()];
+    roots.extend(search_roots.iter().cloned());
+
+    let mut graph = ReferenceGraph::default();
+    for asset in assets.iter() {
+        let file = &asset.path;
+        let collected = match asset.extension().to_lowercase().as_str() {
+            "dae" => collect_dae_references(file),
+            "obj" | "mtl" => collect_obj_references(file),
+            "material" => collect_script_references(file),
+            "sdf" | "world" | "config" => collect_sdf_references(file),
+            _ => continue,
+        };
+
+        match collected {
+            Ok(references) => graph
+                .references
+                .extend(references.into_iter().map(|reference| {
+                    let resolution = resolve_reference(&reference, dir, &roots, &textures);
+                    (reference, resolution)
+                })),
+            Err(e) => graph.unreadable.push((file.clone(), e.to_string())),
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod build_reference_graph_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_dangling_references() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("references").join("models");
+        let graph = build_reference_graph(&dir, &[])?;
+
+        assert!(graph.unreadable.is_empty());
+        assert_eq!(graph.references.len(), 14);
+        assert_eq!(
+            graph
+                .dangling()
+                .map(|r| r.target.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "rust.png",
+                "stencil.png",
+                "lid_normal.png",
+                "model://crate_box/meshes/handle.dae",
+                "model://pallet"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_looks_models_up_in_the_search_roots() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("references").join("models");
+        let graph = build_reference_graph(
+            &dir,
+            &[Path::new("tests").join("references").join("more_models")],
+        )?;
+
+        assert!(!graph.dangling().any(|r| r.target == "model://pallet"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Collect the images a COLLADA mesh refers to
+
+use std::{fs, path::Path};
+
+use crate::collada::{read_dae, read_images};
+use crate::references::{LineFinder, Reference, ReferenceKind};
+
+/// Collect every `<init_from>` of the mesh's `<library_images>`
+pub fn collect_dae_references(mesh: &Path) -> std::result::Result<Vec<Reference>, std::io::Error> {
+    let root = read_dae(mesh)?;
+    let text = fs::read_to_string(mesh)?;
+    let mut lines = LineFinder::new(&text);
+
+    Ok(read_images(&root)
+        .into_iter()
+        .map(|image| Reference::new(ReferenceKind::MeshImage, mesh, lines.find(&image), image))
+        .collect())
+}
+
+#[cfg(test)]
+mod collect_dae_references_tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_the_images() -> std::result::Result<(), std::io::Error> {
+        let mesh = Path::new("tests")
+            .join("references")
+            .join("models")
+            .join("crate_box")
+            .join("meshes")
+            .join("crate.dae");
+
+        let references = collect_dae_references(&mesh)?;
+        assert_eq!(
+            references
+                .iter()
+                .map(|r| (r.line, r.target.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(5, "../materials/textures/crate.png"), (8, "stencil.png")]
+        );
+
+        Ok(())
+    }
+}
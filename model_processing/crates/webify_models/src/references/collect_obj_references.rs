@@ -0,0 +1,85 @@
+//! Collect the material libraries of OBJ meshes, and the textures of the libraries
+
+use std::{fs, path::Path};
+
+use crate::references::{Reference, ReferenceKind};
+
+/// Statements of a material library (`.mtl`) that end with a texture file
+const MTL_TEXTURE_STATEMENTS: [&str; 5] = ["bump", "disp", "decal", "refl", "norm"];
+
+/// Collect the `mtllib` files of an OBJ mesh, or the texture maps of a material library,
+/// depending on the extension of `path`
+pub fn collect_obj_references(path: &Path) -> std::result::Result<Vec<Reference>, std::io::Error> {
+    let is_library = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("mtl"));
+    let text = fs::read_to_string(path)?;
+
+    let mut references = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        let statement = match tokens.next() {
+            Some(statement) => statement,
+            None => continue,
+        };
+
+        if !is_library && statement == "mtllib" {
+            references.extend(tokens.map(|library| {
+                Reference::new(ReferenceKind::ObjMaterialLibrary, path, index + 1, library)
+            }));
+        } else if is_library
+            && (statement.starts_with("map_") || MTL_TEXTURE_STATEMENTS.contains(&statement))
+        {
+            // Options like `-s 1 1 1` come first, the file name is last
+            if let Some(texture) = tokens.last() {
+                references.push(Reference::new(
+                    ReferenceKind::ObjTexture,
+                    path,
+                    index + 1,
+                    texture,
+                ));
+            }
+        }
+    }
+
+    Ok(references)
+}
+
+#[cfg(test)]
+mod collect_obj_references_tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_libraries_and_textures() -> std::result::Result<(), std::io::Error> {
+        let meshes = Path::new("tests")
+            .join("references")
+            .join("models")
+            .join("crate_box")
+            .join("meshes");
+
+        let libraries = collect_obj_references(&meshes.join("lid.obj"))?;
+        assert_eq!(
+            libraries,
+            vec![Reference::new(
+                ReferenceKind::ObjMaterialLibrary,
+                &meshes.join("lid.obj"),
+                2,
+                "lid.mtl"
+            )]
+        );
+
+        let textures = collect_obj_references(&meshes.join("lid.mtl"))?;
+        assert_eq!(
+            textures
+                .iter()
+                .map(|r| (r.kind, r.line, r.target.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (ReferenceKind::ObjTexture, 3, "lid.png"),
+                (ReferenceKind::ObjTexture, 4, "lid_normal.png")
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Collect the textures OGRE material scripts refer to
+
+use std::{fs, path::Path};
+
+use crate::references::{Reference, ReferenceKind};
+
+/// Collect the `texture` of every texture unit of the material script
+pub fn collect_script_references(
+    script: &Path,
+) -> std::result::Result<Vec<Reference>, std::io::Error> {
+    let text = fs::read_to_string(script)?;
+
+    let mut references = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split("//").next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("texture") {
+            continue;
+        }
+        if let Some(texture) = tokens.next() {
+            references.push(Reference::new(
+                ReferenceKind::ScriptTexture,
+                script,
+                index + 1,
+                texture,
+            ));
+        }
+    }
+
+    Ok(references)
+}
+
+#[cfg(test)]
+mod collect_script_references_tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_the_textures() -> std::result::Result<(), std::io::Error> {
+        let script = Path::new("tests")
+            .join("references")
+            .join("models")
+            .join("crate_box")
+            .join("materials")
+            .join("scripts")
+            .join("crate.material");
+
+        let references = collect_script_references(&script)?;
+        assert_eq!(
+            references
+                .iter()
+                .map(|r| (r.line, r.target.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(10, "crate.jpg"), (24, "rust.png")]
+        );
+
+        Ok(())
+    }
+}
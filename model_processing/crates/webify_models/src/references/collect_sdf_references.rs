@@ -0,0 +1,119 @@
+//! Collect the URIs of SDF files, and the SDF files of model configs
+
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::{Element, XMLNode};
+
+use crate::references::{LineFinder, Reference, ReferenceKind};
+
+/// Collect every `<uri>` of an SDF or world file, and every `<sdf>` of a `model.config`
+pub fn collect_sdf_references(path: &Path) -> std::result::Result<Vec<Reference>, Error> {
+    let text = fs::read_to_string(path)?;
+    let root = Element::parse(text.as_bytes()).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse {:?} as XML: {}", path, e),
+        )
+    })?;
+
+    let mut references = vec![];
+    collect(&root, path, &mut LineFinder::new(&text), &mut references);
+
+    Ok(references)
+}
+
+/// Depth-first walk of the children of `element`, in document order
+fn collect(
+    element: &Element,
+    path: &Path,
+    lines: &mut LineFinder,
+    references: &mut Vec<Reference>,
+) {
+    for child in element.children.iter() {
+        let child = match child {
+            XMLNode::Element(child) => child,
+            _ => continue,
+        };
+
+        let kind = match (element.name.as_str(), child.name.as_str()) {
+            ("include", "uri") => Some(ReferenceKind::ModelInclude),
+            (_, "uri") => Some(ReferenceKind::SdfUri),
+            // `<sdf>` is also the root of SDF files, but only has a parent in configs
+            ("model", "sdf") => Some(ReferenceKind::ModelConfigSdf),
+            _ => None,
+        };
+        match kind.zip(child.get_text()) {
+            Some((kind, target)) => {
+                let target = target.trim();
+                references.push(Reference::new(kind, path, lines.find(target), target));
+            }
+            None => collect(child, path, lines, references),
+        }
+    }
+}
+
+#[cfg(test)]
+mod collect_sdf_references_tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_the_uris() -> std::result::Result<(), Error> {
+        let model_dir = Path::new("tests")
+            .join("references")
+            .join("models")
+            .join("crate_box");
+
+        let references = collect_sdf_references(&model_dir.join("model.sdf"))?;
+        assert_eq!(
+            references
+                .iter()
+                .map(|r| (r.kind, r.line, r.target.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    ReferenceKind::SdfUri,
+                    8,
+                    "model://crate_box/meshes/crate.dae"
+                ),
+                (
+                    ReferenceKind::SdfUri,
+                    12,
+                    "model://crate_box/materials/scripts"
+                ),
+                (
+                    ReferenceKind::SdfUri,
+                    13,
+                    "file://media/materials/scripts/gazebo.material"
+                ),
+                (
+                    ReferenceKind::SdfUri,
+                    20,
+                    "model://crate_box/meshes/lid.obj"
+                ),
+                (
+                    ReferenceKind::SdfUri,
+                    25,
+                    "model://crate_box/meshes/handle.dae"
+                ),
+                (ReferenceKind::ModelInclude, 30, "model://pallet"),
+            ]
+        );
+
+        let config = collect_sdf_references(&model_dir.join("model.config"))?;
+        assert_eq!(
+            config,
+            vec![Reference::new(
+                ReferenceKind::ModelConfigSdf,
+                &model_dir.join("model.config"),
+                5,
+                "model.sdf"
+            )]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Find the lines values parsed out of a file were written on
+
+/// Finds values in the text of a file in order, so that a value written several times is
+/// found on the line of each occurrence in turn
+pub struct LineFinder<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> LineFinder<'a> {
+    pub fn new(text: &'a str) -> LineFinder<'a> {
+        LineFinder { text, offset: 0 }
+    }
+
+    /// Return the line (starting at 1) of the next occurrence of `value`, wrapping around
+    /// to the start of the text, or 0 when it isn't in the text at all
+    pub fn find(&mut self, value: &str) -> usize {
+        let start = match self.text[self.offset..].find(value) {
+            Some(position) => self.offset + position,
+            None => match self.text.find(value) {
+                Some(position) => position,
+                None => return 0,
+            },
+        };
+        self.offset = start + value.len();
+
+        self.text[..start].matches('\n').count() + 1
+    }
+}
+
+#[cfg(test)]
+mod line_finder_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_each_occurrence_in_turn() {
+        let mut lines = LineFinder::new("<a>\n  <b>x</b>\n  <b>y</b>\n  <b>x</b>\n</a>\n");
+
+        assert_eq!(lines.find("x"), 2);
+        assert_eq!(lines.find("x"), 4);
+        assert_eq!(lines.find("y"), 3);
+        assert_eq!(lines.find("z"), 0);
+    }
+}
@@ -0,0 +1,22 @@
+//! Follow the paths model files use to refer to each other, to find the ones that lead
+//! nowhere
+
+mod build_reference_graph;
+mod collect_dae_references;
+mod collect_obj_references;
+mod collect_script_references;
+mod collect_sdf_references;
+mod line_finder;
+mod process;
+mod reference;
+mod resolve_reference;
+
+pub use self::build_reference_graph::{build_reference_graph, ReferenceGraph};
+pub use self::collect_dae_references::collect_dae_references;
+pub use self::collect_obj_references::collect_obj_references;
+pub use self::collect_script_references::collect_script_references;
+pub use self::collect_sdf_references::collect_sdf_references;
+pub use self::line_finder::LineFinder;
+pub use self::process::process;
+pub use self::reference::{Reference, ReferenceKind};
+pub use self::resolve_reference::{resolve_reference, Resolution};
@@ -0,0 +1,64 @@
+//! Orchestrator to find the references of a models directory that lead nowhere
+
+use std::path::{Path, PathBuf};
+
+use console::style;
+use tracing::{info, info_span};
+
+use crate::logging::model_span;
+use crate::progress::{print_message, print_warning};
+use crate::references::build_reference_graph;
+
+/// Orchestrator to print every dangling reference with the file and line it's written on,
+/// and return how many there are, counting the files that couldn't be read. `model://`
+/// URIs are also looked up in the `search_roots`.
+pub fn process(dir: &Path, search_roots: &[PathBuf]) -> std::result::Result<usize, std::io::Error> {
+    let _stage = info_span!("stage", name = "references").entered();
+    info!("Following references");
+    let graph = build_reference_graph(dir, search_roots)?;
+
+    for (path, error) in graph.unreadable.iter() {
+        let _model = model_span(dir, path).entered();
+        print_warning(format!("Unreadable: {}", error), Some(path));
+    }
+    let mut dangling = 0;
+    for reference in graph.dangling() {
+        let _model = model_span(dir, &reference.source).entered();
+        print_warning(
+            format!(
+                "line {}: {:?} {}",
+                reference.line, reference.kind, reference.target
+            ),
+            Some(&reference.source),
+        );
+        dangling += 1;
+    }
+
+    print_message(format!(
+        "References found: {}",
+        style(graph.references.len()).bold().blue()
+    ));
+    let broken = dangling + graph.unreadable.len();
+    if broken == 0 {
+        print_message(style("Every reference resolves!").green());
+    } else {
+        print_message(style(format!("{} dangling reference(s) found", dangling)).red());
+    }
+
+    Ok(broken)
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_the_dangling_references() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests").join("references");
+
+        assert_eq!(process(&dir.join("models"), &[])?, 5);
+        assert_eq!(process(&dir.join("more_models"), &[])?, 0);
+
+        Ok(())
+    }
+}
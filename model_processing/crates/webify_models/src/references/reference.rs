@@ -0,0 +1,57 @@
+//! Structure that represents a path written in one model file to another
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReferenceKind {
+    /// An `<init_from>` of a COLLADA mesh
+    MeshImage,
+    /// A `mtllib` of an OBJ mesh
+    ObjMaterialLibrary,
+    /// A `map_*` (or `bump`, `disp`...) texture of an OBJ material library
+    ObjTexture,
+    /// A `texture` of an OGRE material script
+    ScriptTexture,
+    /// A `<uri>` of an SDF, like a mesh or a material script directory
+    SdfUri,
+    /// The `<uri>` of an SDF `<include>`, naming a whole model
+    ModelInclude,
+    /// The `<sdf>` of a `model.config`
+    ModelConfigSdf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    /// File the reference is written in
+    pub source: PathBuf,
+    /// Line of the source it's on, starting at 1, or 0 when it couldn't be found
+    pub line: usize,
+    /// Path or URI as written
+    pub target: String,
+}
+
+impl Reference {
+    pub fn new(
+        kind: ReferenceKind,
+        source: &Path,
+        line: usize,
+        target: impl Into<String>,
+    ) -> Reference {
+        Reference {
+            kind,
+            source: source.to_path_buf(),
+            line,
+            target: target.into(),
+        }
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.source.to_string_lossy(), self.line)
+    }
+}
@@ -0,0 +1,211 @@
+//! Work out which file a reference leads to
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::references::{Reference, ReferenceKind};
+use crate::stats::resolve_mesh_texture;
+
+/// Where a reference leads
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// The file or directory it refers to
+    Found(PathBuf),
+    /// Something outside of the models, like an `http://` URI, that isn't checked
+    External,
+    /// Nothing, the reference is broken
+    Dangling,
+}
+
+/// Resolve a reference of a file of the models directory `dir`.
+///
+/// `model://` URIs are looked up in each of the `search_roots` in turn (like
+/// `GAZEBO_MODEL_PATH` entries), `file://` URIs and relative paths next to their source
+/// and then in the search roots. Material script textures are looked up by file name in
+/// `textures`, like OGRE does, also under their `.png` name once converted.
+pub fn resolve_reference(
+    reference: &Reference,
+    dir: &Path,
+    search_roots: &[PathBuf],
+    textures: &HashMap<String, PathBuf>,
+) -> Resolution {
+    let target = reference.target.as_str();
+    if target.is_empty() {
+        return Resolution::Dangling;
+    }
+    if target == "__default__" || target.starts_with("http://") || target.starts_with("https://") {
+        return Resolution::External;
+    }
+
+    if reference.kind == ReferenceKind::ScriptTexture {
+        let path = Path::new(target);
+        let png = path
+            .file_stem()
+            .map(|stem| format!("{}.png", stem.to_string_lossy()));
+        return path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .into_iter()
+            .chain(png)
+            .find_map(|name| textures.get(&name).cloned())
+            .map_or(Resolution::Dangling, Resolution::Found);
+    }
+
+    let found = if let Some(model_path) = target.strip_prefix("model://") {
+        first_existing(search_roots.iter().map(|root| root.join(model_path)))
+    } else {
+        let path = target.strip_prefix("file://").unwrap_or(target);
+        let next_to_source = reference
+            .source
+            .parent()
+            .map(|source_dir| source_dir.join(path));
+        first_existing(
+            next_to_source
+                .into_iter()
+                .chain(search_roots.iter().map(|root| root.join(path))),
+        )
+        .or_else(|| match reference.kind {
+            ReferenceKind::MeshImage => resolve_mesh_texture(
+                target,
+                &reference.source,
+                &model_dir(&reference.source, dir),
+            ),
+            _ => None,
+        })
+    };
+
+    found.map_or(Resolution::Dangling, |path| {
+        Resolution::Found(normalize(&path))
+    })
+}
+
+/// The first of the paths that exists
+fn first_existing<I: IntoIterator<Item = PathBuf>>(paths: I) -> Option<PathBuf> {
+    paths.into_iter().find(|path| path.exists())
+}
+
+/// Drop the `.` and `..` components of a path, so that the same file is always found
+/// under the same path
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// The model directory (the top-level directory of `dir`) the file is in
+fn model_dir(file: &Path, dir: &Path) -> PathBuf {
+    file.strip_prefix(dir)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map_or_else(|| dir.to_path_buf(), |model| dir.join(model))
+}
+
+#[cfg(test)]
+mod resolve_reference_tests {
+    use super::*;
+
+    fn resolve(kind: ReferenceKind, source: &Path, target: &str) -> Resolution {
+        let dir = Path::new("tests").join("references").join("models");
+        let mut textures = HashMap::new();
+        textures.insert(
+            String::from("crate.png"),
+            dir.join("crate_box/materials/textures/crate.png"),
+        );
+
+        resolve_reference(
+            &Reference::new(kind, source, 1, target),
+            &dir,
+            std::slice::from_ref(&dir),
+            &textures,
+        )
+    }
+
+    #[test]
+    fn it_resolves_model_and_file_uris() {
+        let dir = Path::new("tests").join("references").join("models");
+        let sdf = dir.join("crate_box").join("model.sdf");
+
+        assert_eq!(
+            resolve(
+                ReferenceKind::SdfUri,
+                &sdf,
+                "model://crate_box/meshes/crate.dae"
+            ),
+            Resolution::Found(dir.join("crate_box/meshes/crate.dae"))
+        );
+        assert_eq!(
+            resolve(
+                ReferenceKind::SdfUri,
+                &sdf,
+                "file://media/materials/scripts/gazebo.material"
+            ),
+            Resolution::Found(dir.join("media/materials/scripts/gazebo.material"))
+        );
+        assert_eq!(
+            resolve(ReferenceKind::ModelInclude, &sdf, "model://pallet"),
+            Resolution::Dangling
+        );
+        assert_eq!(
+            resolve(
+                ReferenceKind::SdfUri,
+                &sdf,
+                "https://fuel.gazebosim.org/model"
+            ),
+            Resolution::External
+        );
+    }
+
+    #[test]
+    fn it_resolves_relative_paths() {
+        let dir = Path::new("tests").join("references").join("models");
+        let mesh = dir.join("crate_box").join("meshes").join("crate.dae");
+
+        assert_eq!(
+            resolve(
+                ReferenceKind::MeshImage,
+                &mesh,
+                "../materials/textures/crate.png"
+            ),
+            Resolution::Found(dir.join("crate_box/materials/textures/crate.png"))
+        );
+        // Textures moved to materials/textures are found there
+        assert_eq!(
+            resolve(ReferenceKind::MeshImage, &mesh, "crate.png"),
+            Resolution::Found(dir.join("crate_box/materials/textures/crate.png"))
+        );
+        assert_eq!(
+            resolve(ReferenceKind::MeshImage, &mesh, "stencil.png"),
+            Resolution::Dangling
+        );
+    }
+
+    #[test]
+    fn it_resolves_script_textures_by_name() {
+        let script = Path::new("crate.material");
+
+        assert_eq!(
+            resolve(ReferenceKind::ScriptTexture, script, "crate.jpg"),
+            resolve(ReferenceKind::ScriptTexture, script, "crate.png")
+        );
+        assert_eq!(
+            resolve(ReferenceKind::ScriptTexture, script, "rust.png"),
+            Resolution::Dangling
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! A single thing done to a file, as it appears in the report
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use serde::Serialize;
+use tracing::{error, warn};
+
+use crate::progress::{emit, ProgressEvent};
+use crate::report::record;
+
+/// What was done to which file, how big it was before and after, and how long it took
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAction {
+    pub action: String,
+    pub source: PathBuf,
+    pub destination: Option<PathBuf>,
+    pub bytes_before: Option<u64>,
+    pub bytes_after: Option<u64>,
+    pub duration_ms: f64,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+impl FileAction {
+    /// Start timing an action on `source`, noting its size if it's a file
+    pub fn start(action: &str, source: &Path) -> FileAction {
+        FileAction {
+            action: action.to_string(),
+            source: source.to_path_buf(),
+            destination: None,
+            bytes_before: file_size(source),
+            bytes_after: None,
+            duration_ms: 0.0,
+            warnings: vec![],
+            errors: vec![],
+            started: Some(Instant::now()),
+        }
+    }
+
+    pub fn warn(&mut self, warning: impl Display) {
+        warn!(path = ?self.source, "{}: {}", self.action, warning);
+        emit(ProgressEvent::Warning {
+            path: Some(&self.source),
+            message: warning.to_string(),
+        });
+        self.warnings.push(warning.to_string());
+    }
+
+    /// Record the action as done, with `destination` being what it wrote (which can be
+    /// the source, for files rewritten in place)
+    pub fn finish(mut self, destination: &Path) {
+        self.destination = Some(destination.to_path_buf());
+        self.bytes_after = file_size(destination);
+        self.stop();
+    }
+
+    /// Record the action as failed
+    pub fn fail(mut self, error: impl Display) {
+        self.errors.push(error.to_string());
+        self.stop();
+    }
+
+    fn stop(mut self) {
+        if let Some(started) = self.started.take() {
+            self.duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+        }
+        match self.errors.last() {
+            Some(error) => {
+                error!(path = ?self.source, "{} failed: {}", self.action, error);
+                emit(ProgressEvent::Error {
+                    path: Some(&self.source),
+                    message: error.clone(),
+                })
+            }
+            None => emit(ProgressEvent::FileProcessed { action: &self }),
+        }
+        record(self);
+    }
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+}
+
+#[cfg(test)]
+mod file_action_tests {
+    use super::*;
+
+    #[test]
+    fn it_measures_files() {
+        let sdf = Path::new("tests")
+            .join("index")
+            .join("cube")
+            .join("model.sdf");
+        let action = FileAction::start("convert", &sdf);
+
+        assert_eq!(action.bytes_before, Some(fs::metadata(&sdf).unwrap().len()));
+        assert_eq!(
+            FileAction::start("write", &Path::new("tests").join("index")).bytes_before,
+            None
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Set of functions to record every file a run touches and save them as a JSON report
+
+mod file_action;
+mod recorder;
+mod write_report;
+
+pub use self::file_action::FileAction;
+pub use self::recorder::{record, Recorder};
+pub use self::write_report::write_report;
@@ -0,0 +1,65 @@
+//! Collect the actions of a whole run, from whichever stage does them
+
+use std::sync::{Arc, Mutex};
+
+use crate::context::RunContext;
+use crate::report::FileAction;
+
+/// The actions recorded so far for a report. Clones share them, so a run can record into
+/// a clone while the caller keeps the original to take them from.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    actions: Arc<Mutex<Vec<FileAction>>>,
+}
+
+impl Recorder {
+    pub fn push(&self, action: FileAction) {
+        self.actions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(action);
+    }
+
+    /// Take the actions recorded so far, leaving the recorder empty
+    pub fn take(&self) -> Vec<FileAction> {
+        std::mem::take(&mut *self.actions.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Keep an action for the report of the run going on, if it's making one
+pub fn record(action: FileAction) {
+    if let Some(recorder) = &RunContext::current().recorder {
+        recorder.push(action);
+    }
+}
+
+#[cfg(test)]
+mod recorder_tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn it_records_in_its_context() {
+        let source = Path::new("it_records_in_its_context");
+        let recorder = Recorder::default();
+        let context = RunContext {
+            recorder: Some(recorder.clone()),
+            ..RunContext::default()
+        };
+
+        FileAction::start("before", source).finish(source);
+        {
+            let _context = context.enter();
+            FileAction::start("during", source).finish(source);
+        }
+        FileAction::start("after", source).finish(source);
+
+        let actions: Vec<String> = recorder
+            .take()
+            .into_iter()
+            .map(|action| action.action)
+            .collect();
+        assert_eq!(actions, vec!["during"]);
+        assert!(recorder.take().is_empty());
+    }
+}
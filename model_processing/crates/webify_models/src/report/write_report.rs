@@ -0,0 +1,145 @@
+//! Save the recorded actions as a JSON report
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::report::FileAction;
+
+/// Sums over every action, for dashboards that only chart the totals
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Totals {
+    files: usize,
+    bytes_before: u64,
+    bytes_after: u64,
+    warnings: usize,
+    errors: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct RunReport<'a> {
+    arguments: &'a [String],
+    root: &'a Path,
+    /// Seconds since the Unix epoch
+    started_at: u64,
+    duration_ms: f64,
+    totals: Totals,
+    files: Vec<FileAction>,
+    /// What the run stopped on, when it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Write the report of a run that started at `started` on the `root` models directory,
+/// with paths relative to `root`, and the `error` it stopped on if it failed
+pub fn write_report(
+    report_path: &Path,
+    root: &Path,
+    arguments: &[String],
+    started: SystemTime,
+    mut actions: Vec<FileAction>,
+    error: Option<String>,
+) -> std::result::Result<(), std::io::Error> {
+    let mut totals = Totals::default();
+    for action in actions.iter_mut() {
+        totals.files += 1;
+        totals.bytes_before += action.bytes_before.unwrap_or_default();
+        totals.bytes_after += action.bytes_after.unwrap_or_default();
+        totals.warnings += action.warnings.len();
+        totals.errors += action.errors.len();
+
+        action.source = relative_to(&action.source, root);
+        action.destination = action.destination.as_ref().map(|d| relative_to(d, root));
+    }
+
+    let report = RunReport {
+        arguments,
+        root,
+        started_at: started
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        duration_ms: started.elapsed().map_or(0.0, |d| d.as_secs_f64() * 1000.0),
+        totals,
+        files: actions,
+        error,
+    };
+    fs::write(report_path, serde_json::to_string_pretty(&report)?)
+}
+
+fn relative_to(path: &Path, root: &Path) -> PathBuf {
+    match path.strip_prefix(root) {
+        Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod write_report_tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_relative_paths_and_totals() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests")
+            .join("report")
+            .join("test_run_it_writes_relative_paths_and_totals");
+        fs::create_dir_all(&test_dir)?;
+        let root = Path::new("tests").join("index");
+        let dae = root.join("cube").join("meshes").join("cube.dae");
+
+        let mut rewrite = FileAction::start("rewrite", &dae);
+        rewrite.destination = Some(dae.clone());
+        rewrite.bytes_after = Some(100);
+        rewrite.warn("No <unit>, assuming meters");
+        let mut convert = FileAction::start("convert", &root.join("cube").join("missing.tga"));
+        convert.errors.push(String::from("No such file"));
+
+        let report_path = test_dir.join("report.json");
+        let arguments = vec![String::from("webify_models"), String::from("--report")];
+        write_report(
+            &report_path,
+            &root,
+            &arguments,
+            SystemTime::now(),
+            vec![rewrite, convert],
+            None,
+        )?;
+
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path)?)?;
+        assert_eq!(report["totals"]["files"], 2);
+        assert_eq!(report["totals"]["bytes_before"], 4246);
+        assert_eq!(report["totals"]["bytes_after"], 100);
+        assert_eq!(report["totals"]["warnings"], 1);
+        assert_eq!(report["totals"]["errors"], 1);
+        assert_eq!(report["files"][0]["action"], "rewrite");
+        assert_eq!(
+            report["files"][0]["source"],
+            Path::new("cube")
+                .join("meshes")
+                .join("cube.dae")
+                .to_string_lossy()
+                .as_ref()
+        );
+        assert_eq!(report["files"][1]["destination"], serde_json::Value::Null);
+        assert!(report.get("error").is_none());
+
+        write_report(
+            &report_path,
+            &root,
+            &arguments,
+            SystemTime::now(),
+            vec![],
+            Some(String::from("meshes stage failed")),
+        )?;
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path)?)?;
+        assert_eq!(report["error"], "meshes stage failed");
+
+        fs::remove_dir_all(test_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Parse the `Range` header of a request
+
+/// The part of a file a request asks for
+#[derive(Debug, PartialEq)]
+pub enum ByteRange {
+    /// No range, or one we don't support (like several ranges), so the whole file
+    Full,
+    /// From the first to the last byte, both included
+    Partial(u64, u64),
+    /// A range starting past the end of the file
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range for a
+/// file of `length` bytes. Malformed ranges are ignored, as HTTP requires.
+pub fn parse_range(header: Option<&str>, length: u64) -> ByteRange {
+    let spec = match header.and_then(|h| h.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return ByteRange::Full,
+    };
+
+    let range = match (start.parse::<u64>(), end.parse::<u64>()) {
+        (Ok(start), Ok(end)) if start <= end => (start, end.min(length.saturating_sub(1))),
+        (Ok(start), Err(_)) if end.is_empty() => (start, length.saturating_sub(1)),
+        (Err(_), Ok(suffix)) if start.is_empty() => {
+            if suffix == 0 {
+                return ByteRange::Unsatisfiable;
+            }
+            (length.saturating_sub(suffix), length.saturating_sub(1))
+        }
+        _ => return ByteRange::Full,
+    };
+
+    if range.0 >= length {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Partial(range.0, range.1)
+    }
+}
+
+#[cfg(test)]
+mod parse_range_tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_ranges() {
+        assert_eq!(
+            parse_range(Some("bytes=0-99"), 1000),
+            ByteRange::Partial(0, 99)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=500-"), 1000),
+            ByteRange::Partial(500, 999)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-100"), 1000),
+            ByteRange::Partial(900, 999)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=900-2000"), 1000),
+            ByteRange::Partial(900, 999)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-2000"), 1000),
+            ByteRange::Partial(0, 999)
+        );
+    }
+
+    #[test]
+    fn it_rejects_ranges_past_the_end() {
+        assert_eq!(
+            parse_range(Some("bytes=1000-"), 1000),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-0"), 1000),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(parse_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn it_ignores_other_ranges() {
+        assert_eq!(parse_range(None, 1000), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 1000), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=9-1"), 1000), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 1000), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=a-b"), 1000), ByteRange::Full);
+    }
+}
@@ -0,0 +1,67 @@
+//! Pick the `Content-Type` of a file from its extension
+
+use std::path::Path;
+
+const MIME_TYPES: [(&str, &str); 27] = [
+    ("html", "text/html; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("md", "text/markdown; charset=utf-8"),
+    ("wasm", "application/wasm"),
+    // Models
+    ("dae", "model/vnd.collada+xml"),
+    ("gltf", "model/gltf+json"),
+    ("glb", "model/gltf-binary"),
+    ("obj", "model/obj"),
+    ("stl", "model/stl"),
+    ("sdf", "application/xml"),
+    ("world", "application/xml"),
+    ("config", "application/xml"),
+    ("xml", "application/xml"),
+    ("material", "text/plain; charset=utf-8"),
+    ("program", "text/plain; charset=utf-8"),
+    // Images
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("ktx2", "image/ktx2"),
+    ("svg", "image/svg+xml"),
+    ("tga", "image/x-tga"),
+    ("tif", "image/tiff"),
+];
+
+/// The MIME type of a file, `application/octet-stream` when the extension is unknown
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    MIME_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+        .unwrap_or("application/octet-stream")
+}
+
+#[cfg(test)]
+mod mime_type_tests {
+    use super::*;
+
+    #[test]
+    fn it_knows_model_assets() {
+        assert_eq!(mime_type(Path::new("a/b.dae")), "model/vnd.collada+xml");
+        assert_eq!(mime_type(Path::new("a/b.GLB")), "model/gltf-binary");
+        assert_eq!(mime_type(Path::new("b.ktx2")), "image/ktx2");
+        assert_eq!(mime_type(Path::new("b.webp")), "image/webp");
+        assert_eq!(
+            mime_type(Path::new("scripts/b.material")),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(mime_type(Path::new("b")), "application/octet-stream");
+    }
+}
@@ -0,0 +1,13 @@
+//! Set of functions to serve an assets directory over HTTP for previewing it locally
+
+mod byte_range;
+mod mime_type;
+mod plan_response;
+mod process;
+mod resolve_request_path;
+
+pub use self::byte_range::{parse_range, ByteRange};
+pub use self::mime_type::mime_type;
+pub use self::plan_response::{plan_response, Body};
+pub use self::process::process;
+pub use self::resolve_request_path::resolve_request_path;
@@ -0,0 +1,326 @@
+//! Work out how to answer a request, without touching the connection
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::serve::{mime_type, parse_range, resolve_request_path, ByteRange};
+
+/// Pre-compressed siblings, in order of preference, with their `Content-Encoding`
+const ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gz", "gzip")];
+
+/// Headers sent with every response, so the client can be served from another origin
+const CORS_HEADERS: [(&str, &str); 4] = [
+    ("Access-Control-Allow-Origin", "*"),
+    ("Access-Control-Allow-Methods", "GET, HEAD, OPTIONS"),
+    ("Access-Control-Allow-Headers", "*"),
+    (
+        "Access-Control-Expose-Headers",
+        "Content-Length, Content-Range, Content-Encoding, Accept-Ranges",
+    ),
+];
+
+/// What to send back
+#[derive(Debug, PartialEq)]
+pub enum Body {
+    Empty,
+    Text(String),
+    /// `length` bytes of the file, from `start`
+    File {
+        path: PathBuf,
+        start: u64,
+        length: u64,
+    },
+}
+
+/// A response to send
+#[derive(Debug)]
+pub struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Body,
+}
+
+impl Reply {
+    fn new(status: u16, body: Body) -> Reply {
+        let headers = CORS_HEADERS
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        Reply {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Reply {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Answer a request for `url` from the files in `root`. Directories serve their
+/// `index.html`, and a `.br` or `.gz` sibling of the file is sent instead when the client
+/// accepts it. `HEAD` is answered like `GET`, the server leaves the body out.
+pub fn plan_response(
+    root: &Path,
+    method: &str,
+    url: &str,
+    accept_encoding: Option<&str>,
+    range: Option<&str>,
+) -> Reply {
+    match method {
+        "OPTIONS" => return Reply::new(204, Body::Empty),
+        "GET" | "HEAD" => {}
+        _ => {
+            return Reply::new(405, Body::Text(String::from("405: Method Not Allowed")))
+                .header("Allow", "GET, HEAD, OPTIONS")
+        }
+    }
+
+    let mut path = match resolve_request_path(root, url) {
+        Some(path) => path,
+        None => return not_found(),
+    };
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    if !path.is_file() {
+        return not_found();
+    }
+
+    let accepted = accepted_codings(accept_encoding.unwrap_or_default());
+    let encoded = ENCODINGS
+        .iter()
+        .filter(|(_, coding)| accepted.contains(coding))
+        .map(|(extension, coding)| {
+            let mut encoded = path.clone().into_os_string();
+            encoded.push(".");
+            encoded.push(extension);
+            (PathBuf::from(encoded), *coding)
+        })
+        .find(|(encoded, _)| encoded.is_file());
+
+    let mut reply = Reply::new(200, Body::Empty)
+        .header("Content-Type", mime_type(&path))
+        .header("Accept-Ranges", "bytes")
+        .header("Vary", "Accept-Encoding");
+    let file = match encoded {
+        Some((encoded, coding)) => {
+            reply = reply.header("Content-Encoding", coding);
+            encoded
+        }
+        None => path,
+    };
+
+    let length = match fs::metadata(&file) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return not_found(),
+    };
+    match parse_range(range, length) {
+        ByteRange::Full => {
+            reply.body = Body::File {
+                path: file,
+                start: 0,
+                length,
+            }
+        }
+        ByteRange::Partial(start, end) => {
+            reply.status = 206;
+            reply = reply.header(
+                "Content-Range",
+                &format!("bytes {}-{}/{}", start, end, length),
+            );
+            reply.body = Body::File {
+                path: file,
+                start,
+                length: end - start + 1,
+            }
+        }
+        ByteRange::Unsatisfiable => {
+            reply.status = 416;
+            reply = reply.header("Content-Range", &format!("bytes */{}", length));
+        }
+    }
+
+    reply
+}
+
+/// The codings of an `Accept-Encoding` header, leaving out those with a `q` of 0 or one
+/// that isn't a number
+fn accepted_codings(accept_encoding: &str) -> Vec<&str> {
+    accept_encoding
+        .split(',')
+        .filter_map(|coding| {
+            let mut parameters = coding.split(';');
+            let name = parameters.next().unwrap_or_default().trim();
+            let q = parameters
+                .filter_map(|parameter| {
+                    let mut pair = parameter.splitn(2, '=');
+                    let key = pair.next().unwrap_or_default().trim();
+                    if key.eq_ignore_ascii_case("q") {
+                        Some(pair.next().unwrap_or_default().trim().parse::<f32>())
+                    } else {
+                        None
+                    }
+                })
+                .next()
+                .unwrap_or(Ok(1.0));
+
+            match q {
+                Ok(q) if q > 0.0 => Some(name),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn not_found() -> Reply {
+    Reply::new(404, Body::Text(String::from("404: File Not Found!")))
+}
+
+#[cfg(test)]
+mod plan_response_tests {
+    use super::*;
+
+    fn header<'a>(reply: &'a Reply, name: &str) -> Option<&'a str> {
+        reply
+            .headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn root() -> PathBuf {
+        Path::new("tests").join("serve")
+    }
+
+    #[test]
+    fn it_serves_files_with_their_mime_type() {
+        let reply = plan_response(&root(), "GET", "/box/meshes/box.dae", None, None);
+
+        assert_eq!(reply.status, 200);
+        assert_eq!(
+            header(&reply, "Content-Type"),
+            Some("model/vnd.collada+xml")
+        );
+        assert_eq!(header(&reply, "Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(header(&reply, "Content-Encoding"), None);
+        assert_eq!(
+            reply.body,
+            Body::File {
+                path: root().join("box").join("meshes").join("box.dae"),
+                start: 0,
+                length: 40,
+            }
+        );
+    }
+
+    #[test]
+    fn it_serves_pre_compressed_variants() {
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            Some("gzip, deflate, br"),
+            None,
+        );
+        assert_eq!(header(&reply, "Content-Encoding"), Some("br"));
+        assert_eq!(
+            header(&reply, "Content-Type"),
+            Some("model/vnd.collada+xml")
+        );
+
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            Some("gzip, br;q=0"),
+            None,
+        );
+        assert_eq!(header(&reply, "Content-Encoding"), Some("gzip"));
+        assert!(matches!(reply.body, Body::File { path, .. } if path.ends_with("box.dae.gz")));
+    }
+
+    #[test]
+    fn it_refuses_codings_with_a_zero_q() {
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            Some("gzip;q=0.5, br;q=0.0"),
+            None,
+        );
+        assert_eq!(header(&reply, "Content-Encoding"), Some("gzip"));
+
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            Some("gzip; q=0, br ; Q = 0.000"),
+            None,
+        );
+        assert_eq!(header(&reply, "Content-Encoding"), None);
+        assert_eq!(accepted_codings("br;q=0.0, gzip; q=0"), Vec::<&str>::new());
+        assert_eq!(accepted_codings("br;q=1 , gzip"), vec!["br", "gzip"]);
+    }
+
+    #[test]
+    fn it_serves_ranges() {
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            None,
+            Some("bytes=10-"),
+        );
+        assert_eq!(reply.status, 206);
+        assert_eq!(header(&reply, "Content-Range"), Some("bytes 10-39/40"));
+        assert!(matches!(
+            reply.body,
+            Body::File {
+                start: 10,
+                length: 30,
+                ..
+            }
+        ));
+
+        let reply = plan_response(
+            &root(),
+            "GET",
+            "/box/meshes/box.dae",
+            None,
+            Some("bytes=40-"),
+        );
+        assert_eq!(reply.status, 416);
+        assert_eq!(header(&reply, "Content-Range"), Some("bytes */40"));
+    }
+
+    #[test]
+    fn it_answers_other_requests() {
+        assert_eq!(
+            plan_response(&root(), "GET", "/", None, None).body,
+            Body::File {
+                path: root().join("index.html"),
+                start: 0,
+                length: 14,
+            }
+        );
+        assert_eq!(
+            plan_response(&root(), "GET", "/missing.dae", None, None).status,
+            404
+        );
+        assert_eq!(
+            plan_response(&root(), "GET", "/../../Cargo.toml", None, None).status,
+            404
+        );
+        assert_eq!(
+            plan_response(&root(), "OPTIONS", "/", None, None).status,
+            204
+        );
+        assert_eq!(plan_response(&root(), "POST", "/", None, None).status, 405);
+    }
+}
@@ -0,0 +1,113 @@
+//! Orchestrator to serve an assets directory over HTTP
+
+use std::{
+    fs::File,
+    io::{Cursor, Error, Read, Seek, SeekFrom},
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    sync::Arc,
+    thread,
+};
+
+use console::style;
+use tiny_http::{Header, Request, Response, Server};
+use tracing::{info, info_span};
+
+use crate::context::RunContext;
+use crate::progress::{print_error, print_message};
+use crate::serve::{plan_response, Body};
+
+/// Number of requests answered at the same time
+const WORKERS: usize = 4;
+
+/// Orchestrator to serve the files of `dir` on `host` and `port`, like the static part of
+/// gzbridge's `server.js`, until the process is stopped
+pub fn process(dir: &Path, host: IpAddr, port: u16) -> std::result::Result<(), Error> {
+    let stage = info_span!("stage", name = "serve");
+    let _stage = stage.enter();
+    let address = SocketAddr::new(host, port);
+    let server = Arc::new(Server::http(address).map_err(Error::other)?);
+    print_message(format!(
+        "{} {} on http://{}",
+        style("Serving").bold(),
+        style(dir.display()).dim(),
+        address
+    ));
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let dir = dir.to_path_buf();
+            // Contexts and spans are per thread, so the workers are given the ones of the
+            // server
+            let context = RunContext::current().as_ref().clone();
+            let stage = stage.clone();
+            thread::spawn(move || {
+                let _context = context.enter();
+                let _stage = stage.enter();
+                for request in server.incoming_requests() {
+                    if let Err(e) = respond(&dir, request) {
+                        print_error(format!("Failed: {}", e), None);
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| Error::other("A server thread panicked"))?;
+    }
+
+    Ok(())
+}
+
+/// Answer a single request and log it
+fn respond(dir: &Path, request: Request) -> std::result::Result<(), Error> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str().to_string())
+    };
+    let reply = plan_response(
+        dir,
+        request.method().as_str(),
+        request.url(),
+        header("Accept-Encoding").as_deref(),
+        header("Range").as_deref(),
+    );
+
+    info!("{} {} {}", request.method(), request.url(), reply.status);
+
+    let (reader, length): (Box<dyn Read + Send>, usize) = match reply.body {
+        Body::Empty => (Box::new(std::io::empty()), 0),
+        Body::Text(text) => {
+            let length = text.len();
+            (Box::new(Cursor::new(text.into_bytes())), length)
+        }
+        Body::File {
+            path,
+            start,
+            length,
+        } => {
+            let mut file = File::open(path)?;
+            file.seek(SeekFrom::Start(start))?;
+            (Box::new(file.take(length)), length as usize)
+        }
+    };
+    let headers = reply
+        .headers
+        .iter()
+        .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+        .collect();
+
+    request.respond(Response::new(
+        reply.status.into(),
+        headers,
+        reader,
+        Some(length),
+        None,
+    ))
+}
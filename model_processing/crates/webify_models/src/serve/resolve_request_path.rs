@@ -0,0 +1,54 @@
+//! Map the URL of a request to a file in the served directory
+
+use std::path::{Path, PathBuf};
+
+use percent_encoding::percent_decode_str;
+
+/// The file a URL points to inside `root`, or `None` when it tries to leave `root`.
+/// The query string and fragment are dropped and `%XX` escapes are decoded.
+pub fn resolve_request_path(root: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = percent_decode_str(path).decode_utf8_lossy();
+
+    let mut resolved = root.to_path_buf();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            _ if segment.contains('\\') || segment.contains('\0') => return None,
+            _ => resolved.push(segment),
+        }
+    }
+
+    Some(resolved)
+}
+
+#[cfg(test)]
+mod resolve_request_path_tests {
+    use super::*;
+
+    #[test]
+    fn it_resolves_urls() {
+        let root = Path::new("assets");
+        assert_eq!(
+            resolve_request_path(root, "/box/meshes/box.dae?v=2"),
+            Some(root.join("box").join("meshes").join("box.dae"))
+        );
+        assert_eq!(
+            resolve_request_path(root, "/my%20model/model.sdf"),
+            Some(root.join("my model").join("model.sdf"))
+        );
+        assert_eq!(resolve_request_path(root, "/"), Some(root.to_path_buf()));
+    }
+
+    #[test]
+    fn it_stays_inside_the_root() {
+        let root = Path::new("assets");
+        assert_eq!(resolve_request_path(root, "/../secret"), None);
+        assert_eq!(
+            resolve_request_path(root, "/box/%2e%2e/%2e%2e/secret"),
+            None
+        );
+        assert_eq!(resolve_request_path(root, "/box/..%5c..%5csecret"), None);
+    }
+}
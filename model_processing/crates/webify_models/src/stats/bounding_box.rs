@@ -0,0 +1,61 @@
+//! Axis-aligned bounding box, in the units of the mesh's scene
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BoundingBox {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl BoundingBox {
+    /// Smallest box containing all of the points, or None when there are none
+    pub fn from_points<I: IntoIterator<Item = [f64; 3]>>(points: I) -> Option<BoundingBox> {
+        points.into_iter().fold(None, |bounds, p| {
+            Some(match bounds {
+                None => BoundingBox { min: p, max: p },
+                Some(b) => b.merge(&BoundingBox { min: p, max: p }),
+            })
+        })
+    }
+
+    /// Smallest box containing both boxes
+    pub fn merge(&self, other: &BoundingBox) -> BoundingBox {
+        let mut merged = *self;
+        for i in 0..3 {
+            merged.min[i] = merged.min[i].min(other.min[i]);
+            merged.max[i] = merged.max[i].max(other.max[i]);
+        }
+
+        merged
+    }
+
+    pub fn size(&self) -> [f64; 3] {
+        [
+            self.max[0] - self.min[0],
+            self.max[1] - self.min[1],
+            self.max[2] - self.min[2],
+        ]
+    }
+}
+
+#[cfg(test)]
+mod bounding_box_tests {
+    use super::*;
+
+    #[test]
+    fn it_bounds_points() {
+        let bounds =
+            BoundingBox::from_points(vec![[1.0, -2.0, 0.0], [-1.0, 2.0, 3.0], [0.0, 0.0, 0.0]])
+                .unwrap();
+
+        assert_eq!(bounds.min, [-1.0, -2.0, 0.0]);
+        assert_eq!(bounds.max, [1.0, 2.0, 3.0]);
+        assert_eq!(bounds.size(), [2.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn it_has_no_bounds_without_points() {
+        assert_eq!(BoundingBox::from_points(Vec::new()), None);
+    }
+}
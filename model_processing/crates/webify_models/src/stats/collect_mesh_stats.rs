@@ -0,0 +1,158 @@
+//! Count what the web client will have to load and draw for a single DAE mesh
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::collada::{read_dae, read_geometries, read_images, read_scene};
+use crate::stats::BoundingBox;
+
+/// Statistics for one DAE mesh, counting every instance placed in its scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshStats {
+    pub vertices: usize,
+    pub triangles: usize,
+    /// Primitive groups, each one a draw call in the web client
+    pub draw_calls: usize,
+    /// Ids of the materials bound to the instanced geometry
+    pub materials: BTreeSet<String>,
+    /// Texture files referenced by the mesh that exist on disk
+    pub textures: BTreeSet<PathBuf>,
+    pub bounds: Option<BoundingBox>,
+}
+
+/// Parse the mesh and gather its statistics. Textures are looked up relative to the
+/// mesh first, then in the model's `materials/textures` directory.
+pub fn collect_mesh_stats(mesh: &Path, model_dir: &Path) -> Result<MeshStats, Error> {
+    let root = read_dae(mesh)?;
+    let geometries: HashMap<String, _> = read_geometries(&root)?
+        .into_iter()
+        .map(|g| (g.id.clone(), g))
+        .collect();
+
+    let mut stats = MeshStats {
+        vertices: 0,
+        triangles: 0,
+        draw_calls: 0,
+        materials: BTreeSet::new(),
+        textures: BTreeSet::new(),
+        bounds: None,
+    };
+
+    for instance in read_scene(&root)? {
+        let geometry = match geometries.get(&instance.geometry) {
+            Some(g) => g,
+            None => continue,
+        };
+
+        stats.vertices += geometry.positions.len();
+        for primitive in geometry.primitives.iter() {
+            stats.triangles += primitive.triangles.len();
+            stats.draw_calls += 1;
+            if let Some(symbol) = &primitive.material {
+                let material = instance.materials.get(symbol).unwrap_or(symbol);
+                stats.materials.insert(material.clone());
+            }
+        }
+
+        let instance_bounds = BoundingBox::from_points(
+            geometry
+                .positions
+                .iter()
+                .map(|p| instance.transform.transform_point(*p)),
+        );
+        stats.bounds = match (stats.bounds, instance_bounds) {
+            (Some(a), Some(b)) => Some(a.merge(&b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    for image in read_images(&root) {
+        if let Some(texture) = resolve_mesh_texture(&image, mesh, model_dir) {
+            stats.textures.insert(texture);
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Find the file an `<init_from>` points at, if it exists
+pub fn resolve_mesh_texture(image: &str, mesh: &Path, model_dir: &Path) -> Option<PathBuf> {
+    let image = image.trim_start_matches("file://");
+    let relative = mesh.parent().unwrap_or(model_dir).join(image);
+    if relative.is_file() {
+        return Some(relative);
+    }
+
+    let file_name = Path::new(image).file_name()?;
+    let in_textures = model_dir.join("materials").join("textures").join(file_name);
+    if in_textures.is_file() {
+        return Some(in_textures);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod collect_mesh_stats_tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_the_cube() -> Result<(), Error> {
+        let model_dir = Path::new("tests").join("collada").join("cube");
+        let mesh = model_dir.join("meshes").join("cube.dae");
+        let stats = collect_mesh_stats(&mesh, &model_dir)?;
+
+        assert_eq!(stats.vertices, 8);
+        assert_eq!(stats.triangles, 12);
+        assert_eq!(stats.draw_calls, 1);
+        assert_eq!(
+            stats.materials.into_iter().collect::<Vec<String>>(),
+            vec!["Material-material"]
+        );
+        assert_eq!(
+            stats.textures.into_iter().collect::<Vec<PathBuf>>(),
+            vec![mesh
+                .parent()
+                .unwrap()
+                .join("../materials/textures/cube_diffuse.png")]
+        );
+
+        let bounds = stats.bounds.unwrap();
+        assert_eq!(bounds.min, [-1.0, 9.0, -1.0]);
+        assert_eq!(bounds.max, [1.0, 11.0, 1.0]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod resolve_mesh_texture_tests {
+    use super::*;
+
+    #[test]
+    fn it_falls_back_to_the_textures_dir() {
+        let model_dir = Path::new("tests").join("collada").join("cube");
+        let mesh = model_dir.join("meshes").join("cube.dae");
+
+        assert_eq!(
+            resolve_mesh_texture("cube_diffuse.png", &mesh, &model_dir),
+            Some(
+                model_dir
+                    .join("materials")
+                    .join("textures")
+                    .join("cube_diffuse.png")
+            )
+        );
+    }
+
+    #[test]
+    fn it_skips_missing_textures() {
+        let model_dir = Path::new("tests").join("collada").join("cube");
+        let mesh = model_dir.join("meshes").join("cube.dae");
+
+        assert_eq!(resolve_mesh_texture("missing.png", &mesh, &model_dir), None);
+    }
+}
@@ -0,0 +1,14 @@
+//! Per-model mesh statistics, to decide which models need coarsening before they
+//! go into web worlds
+
+mod bounding_box;
+mod collect_mesh_stats;
+mod model_stats;
+mod print_stats_table;
+mod process;
+
+pub use self::bounding_box::BoundingBox;
+pub use self::collect_mesh_stats::{collect_mesh_stats, resolve_mesh_texture, MeshStats};
+pub use self::model_stats::ModelStats;
+pub use self::print_stats_table::{format_bytes, print_stats_table};
+pub use self::process::process;
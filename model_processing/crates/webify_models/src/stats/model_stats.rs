@@ -0,0 +1,94 @@
+//! Statistics of a whole model, summed over its meshes
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::stats::{BoundingBox, MeshStats};
+
+/// Bytes per texel once a texture is decoded to RGBA8 on the GPU
+const BYTES_PER_TEXEL: u64 = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelStats {
+    pub name: String,
+    pub meshes: usize,
+    pub vertices: usize,
+    pub triangles: usize,
+    pub draw_calls: usize,
+    pub materials: usize,
+    /// Decoded RGBA8 size of every distinct texture, without mipmaps
+    pub texture_memory: u64,
+    pub bounding_box: Option<BoundingBox>,
+    /// Meshes that couldn't be parsed, and why
+    pub errors: Vec<String>,
+}
+
+impl ModelStats {
+    pub fn new(name: &str) -> ModelStats {
+        ModelStats {
+            name: name.to_string(),
+            meshes: 0,
+            vertices: 0,
+            triangles: 0,
+            draw_calls: 0,
+            materials: 0,
+            texture_memory: 0,
+            bounding_box: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sum the statistics of the model's meshes. Materials and textures shared by
+    /// several meshes of the model are only counted once.
+    pub fn from_meshes(name: &str, meshes: &[MeshStats]) -> ModelStats {
+        let mut stats = ModelStats::new(name);
+        let mut materials = BTreeSet::new();
+        let mut textures = BTreeSet::new();
+
+        for mesh in meshes {
+            stats.meshes += 1;
+            stats.vertices += mesh.vertices;
+            stats.triangles += mesh.triangles;
+            stats.draw_calls += mesh.draw_calls;
+            materials.extend(mesh.materials.iter());
+            textures.extend(mesh.textures.iter());
+            stats.bounding_box = match (stats.bounding_box, mesh.bounds) {
+                (Some(a), Some(b)) => Some(a.merge(&b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        stats.materials = materials.len();
+        stats.texture_memory = textures
+            .iter()
+            .filter_map(|texture| image::image_dimensions(texture).ok())
+            .map(|(width, height)| width as u64 * height as u64 * BYTES_PER_TEXEL)
+            .sum();
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod model_stats_tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::stats::collect_mesh_stats;
+
+    #[test]
+    fn it_sums_meshes_and_dedupes_textures() -> std::result::Result<(), std::io::Error> {
+        let model_dir = Path::new("tests").join("collada").join("cube");
+        let mesh = collect_mesh_stats(&model_dir.join("meshes").join("cube.dae"), &model_dir)?;
+        let stats = ModelStats::from_meshes("cube", &[mesh.clone(), mesh]);
+
+        assert_eq!(stats.meshes, 2);
+        assert_eq!(stats.triangles, 24);
+        assert_eq!(stats.materials, 1);
+        // One 8x8 texture, shared by both meshes
+        assert_eq!(stats.texture_memory, 8 * 8 * 4);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Print model statistics as an aligned table on the terminal
+
+use console::style;
+
+use crate::progress::{print_message, print_warning};
+use crate::stats::ModelStats;
+
+/// Print one row per model, followed by any mesh that couldn't be parsed
+pub fn print_stats_table(models: &[ModelStats]) {
+    let name_width = models
+        .iter()
+        .map(|m| m.name.len())
+        .chain(std::iter::once(5))
+        .max()
+        .unwrap_or(5);
+
+    print_message(
+        style(format!(
+            "{:<name_width$} {:>6} {:>10} {:>10} {:>6} {:>9} {:>10}  {}",
+            "Model",
+            "Meshes",
+            "Vertices",
+            "Triangles",
+            "Draws",
+            "Materials",
+            "Textures",
+            "Size (x y z)",
+            name_width = name_width
+        ))
+        .bold(),
+    );
+    for model in models {
+        print_message(format!(
+            "{:<name_width$} {:>6} {:>10} {:>10} {:>6} {:>9} {:>10}  {}",
+            model.name,
+            model.meshes,
+            model.vertices,
+            model.triangles,
+            model.draw_calls,
+            model.materials,
+            format_bytes(model.texture_memory),
+            match model.bounding_box {
+                Some(b) => {
+                    let [x, y, z] = b.size();
+                    format!("{:.3} {:.3} {:.3}", x, y, z)
+                }
+                None => String::from("-"),
+            },
+            name_width = name_width
+        ));
+    }
+
+    for model in models {
+        for error in model.errors.iter() {
+            print_warning(format!("{} {}", model.name, error), None);
+        }
+    }
+}
+
+/// Format a byte count with a binary unit, e.g. `1.5 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, units[0])
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
+
+#[cfg(test)]
+mod format_bytes_tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_bytes() {
+        assert_eq!(format_bytes(256), "256 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,138 @@
+//! Orchestrator to gather mesh statistics for every model in a directory
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::mesh_update::scan_dir_for_meshes;
+use crate::progress::create_progress_bar;
+use crate::stats::{collect_mesh_stats, print_stats_table, MeshStats, ModelStats};
+
+/// Orchestrator to gather mesh statistics for every model, print them as a table and
+/// optionally save them as JSON
+pub fn process(dir: &Path, json_path: Option<&Path>) -> std::result::Result<(), std::io::Error> {
+    let meshes = scan_dir_for_meshes(dir)?;
+    let stats_bar = create_progress_bar(meshes.len() as u64);
+    stats_bar.set_prefix("Mesh Stats");
+
+    // Meshes are grouped by the model they're in
+    let mut models: BTreeMap<String, (Vec<MeshStats>, Vec<String>)> = BTreeMap::new();
+    for mesh in meshes {
+        stats_bar.inc(1);
+        stats_bar.set_message(&format!("Parsing {:?}...", &mesh));
+
+        let (model_name, model_dir) = get_model(&mesh, dir);
+        let entry = models.entry(model_name).or_default();
+        match collect_mesh_stats(&mesh, &model_dir) {
+            Ok(stats) => entry.0.push(stats),
+            Err(e) => entry.1.push(format!("{:?}: {}", mesh, e)),
+        }
+    }
+    stats_bar.finish_with_message("Meshes parsed!");
+
+    let models: Vec<ModelStats> = models
+        .into_iter()
+        .map(|(name, (meshes, errors))| {
+            let mut model = ModelStats::from_meshes(&name, &meshes);
+            model.errors = errors;
+            model
+        })
+        .collect();
+
+    print_stats_table(&models);
+    if let Some(json_path) = json_path {
+        fs::write(json_path, serde_json::to_string_pretty(&models)?)?;
+    }
+
+    Ok(())
+}
+
+/// Name and directory of the model a mesh belongs to: the closest directory with a
+/// `model.config`, which is `dir` itself when given a single model, or else the top-level
+/// directory the mesh is in
+fn get_model(mesh: &Path, dir: &Path) -> (String, PathBuf) {
+    let model_dir = mesh
+        .ancestors()
+        .skip(1)
+        .take_while(|ancestor| ancestor.starts_with(dir))
+        .find(|ancestor| ancestor.join("model.config").is_file());
+    if let Some(model_dir) = model_dir {
+        // `.` and the like only have a name once canonicalized
+        let name = match model_dir.file_name() {
+            Some(name) => PathBuf::from(name),
+            None => model_dir.canonicalize().unwrap_or_default(),
+        };
+        let name = name.file_name().unwrap_or_default().to_string_lossy();
+
+        return (name.to_string(), model_dir.to_path_buf());
+    }
+
+    let model_name = mesh
+        .strip_prefix(dir)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    (model_name.clone(), dir.join(model_name))
+}
+
+#[cfg(test)]
+mod get_model_tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_the_model_name() {
+        let dir = Path::new("some").join("path");
+        let mesh = dir.join("cube").join("meshes").join("cube.dae");
+
+        assert_eq!(
+            get_model(&mesh, &dir),
+            (String::from("cube"), dir.join("cube"))
+        );
+    }
+
+    #[test]
+    fn it_finds_the_model_config() {
+        let dir = Path::new("tests").join("check").join("models");
+        let mesh = dir.join("cube").join("meshes").join("cube.dae");
+
+        assert_eq!(
+            get_model(&mesh, &dir),
+            (String::from("cube"), dir.join("cube"))
+        );
+        // Even when given the model itself, rather than the models directory
+        assert_eq!(
+            get_model(&mesh, &dir.join("cube")),
+            (String::from("cube"), dir.join("cube"))
+        );
+    }
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_json() -> std::result::Result<(), std::io::Error> {
+        let json_path = Path::new("tests")
+            .join("collada")
+            .join("test_run_stats_it_writes_json.json");
+        process(&Path::new("tests").join("collada"), Some(&json_path))?;
+
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+        let cube = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|model| model["name"] == "cube")
+            .unwrap();
+        assert_eq!(cube["triangles"], 12);
+        assert_eq!(cube["bounding_box"]["max"][1], 11.0);
+
+        fs::remove_file(json_path)?;
+        Ok(())
+    }
+}
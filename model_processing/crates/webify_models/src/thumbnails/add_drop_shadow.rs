@@ -0,0 +1,41 @@
+//! Put a blurred black shadow behind an image, like ImageMagick's `-shadow 100x<sigma>+0+0`
+//! merged under the original
+
+use image::{imageops, Rgba, RgbaImage};
+
+/// Return the image over a black copy of its alpha blurred by `sigma`, on a canvas
+/// grown by `2 * sigma` on every side so the shadow isn't cut off
+pub fn add_drop_shadow(image: &RgbaImage, sigma: f32) -> RgbaImage {
+    let border = (2.0 * sigma).ceil() as u32;
+    let (width, height) = image.dimensions();
+
+    let mut shadow = RgbaImage::new(width + 2 * border, height + 2 * border);
+    for (x, y, pixel) in image.enumerate_pixels() {
+        shadow.put_pixel(x + border, y + border, Rgba([0, 0, 0, pixel.0[3]]));
+    }
+    let mut result = imageops::blur(&shadow, sigma);
+    imageops::overlay(&mut result, image, border, border);
+
+    result
+}
+
+#[cfg(test)]
+mod add_drop_shadow_tests {
+    use super::*;
+
+    #[test]
+    fn it_surrounds_the_image_with_a_shadow() {
+        let image = RgbaImage::from_pixel(10, 10, Rgba([255, 255, 255, 255]));
+        let result = add_drop_shadow(&image, 2.0);
+
+        assert_eq!(result.dimensions(), (18, 18));
+        // The original is untouched in the middle
+        assert_eq!(result.get_pixel(9, 9).0, [255, 255, 255, 255]);
+        // Right next to it, the shadow is black and partly transparent
+        let shadow = result.get_pixel(2, 9).0;
+        assert_eq!(&shadow[..3], &[0, 0, 0]);
+        assert!(shadow[3] > 0 && shadow[3] < 255);
+        // And it fades out towards the corners
+        assert!(result.get_pixel(0, 0).0[3] < shadow[3]);
+    }
+}
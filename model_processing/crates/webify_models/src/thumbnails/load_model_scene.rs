@@ -0,0 +1,408 @@
+//! Gather the triangles of a model's visuals, placed the way its SDF places them
+
+use std::{
+    collections::HashMap,
+    fs,
+    fs::File,
+    io::{BufReader, Error, ErrorKind},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use image::RgbaImage;
+use xmltree::Element;
+
+use crate::collada::{
+    child_elements, parse_floats, read_dae, read_geometries, read_materials, read_scene, Matrix,
+};
+use crate::materials::{read_sdf_material, OgreMaterial};
+use crate::mesh_update::normalizing_transform;
+use crate::model_config::read_model_config;
+use crate::thumbnails::{
+    box_triangles, cylinder_triangles, plane_triangles, sphere_triangles, Scene, Surface, Triangle,
+};
+use crate::walk::{walk_all_assets, AssetKind};
+
+/// Color of anything without a material, Gazebo's default grey
+const DEFAULT_COLOR: [f64; 4] = [0.7, 0.7, 0.7, 1.0];
+
+/// Load the visuals of the model's SDF file (the first one in `model.config`, or
+/// `model.sdf`). DAE meshes keep their own materials unless the visual has a
+/// `<material>`, whose OGRE script is looked up in the `materials.json` of the model
+/// and of `media`. Without an SDF file, every DAE mesh of the model is loaded as is.
+pub fn load_model_scene(model_dir: &Path) -> Result<Scene, Error> {
+    let assets_dir = model_dir.parent().unwrap_or(model_dir);
+    let mut loader = Loader {
+        model_dir,
+        assets_dir,
+        scripts: read_script_materials(&[
+            model_dir.join("materials.json"),
+            assets_dir.join("media").join("materials.json"),
+        ]),
+        textures: HashMap::new(),
+        scene: Scene::default(),
+    };
+
+    let sdf = read_model_config(model_dir)
+        .ok()
+        .and_then(|config| config.sdf.first().map(|sdf| model_dir.join(sdf)))
+        .filter(|sdf| sdf.is_file())
+        .unwrap_or_else(|| model_dir.join("model.sdf"));
+    if sdf.is_file() {
+        let root = Element::parse(BufReader::new(File::open(&sdf)?)).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Failed to parse {:?} as SDF: {}", sdf, e),
+            )
+        })?;
+        loader.walk(&root, Matrix::identity())?;
+    } else {
+        let meshes = walk_all_assets(model_dir)?
+            .into_iter()
+            .filter(|asset| asset.kind == AssetKind::Collada);
+        for mesh in meshes {
+            loader.add_mesh(&mesh.path, Matrix::identity(), None)?;
+        }
+    }
+
+    Ok(loader.scene)
+}
+
+struct Loader<'a> {
+    model_dir: &'a Path,
+    assets_dir: &'a Path,
+    /// OGRE materials by name, with textures relative to the assets directory
+    scripts: HashMap<String, OgreMaterial>,
+    textures: HashMap<PathBuf, Option<Rc<RgbaImage>>>,
+    scene: Scene,
+}
+
+impl<'a> Loader<'a> {
+    /// Follow models and links down to their visuals, composing their poses
+    fn walk(&mut self, element: &Element, parent: Matrix) -> Result<(), Error> {
+        for child in element.children.iter().filter_map(|c| c.as_element()) {
+            match child.name.as_str() {
+                "model" | "link" => self.walk(child, parent.multiply(&read_pose(child)))?,
+                "visual" => self.add_visual(child, parent.multiply(&read_pose(child)))?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_visual(&mut self, visual: &Element, transform: Matrix) -> Result<(), Error> {
+        let surface = child_elements(visual, "material")
+            .next()
+            .and_then(|material| self.sdf_surface(material))
+            .map(|surface| self.scene.add_surface(surface));
+        let geometry = match child_elements(visual, "geometry").next() {
+            Some(geometry) => geometry,
+            None => return Ok(()),
+        };
+        let floats = |element: &Element, name: &str| {
+            child_elements(element, name)
+                .next()
+                .and_then(|child| child.get_text())
+                .and_then(|text| parse_floats(&text).ok())
+                .unwrap_or_default()
+        };
+
+        let shape = geometry
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .next();
+        let triangles = match shape {
+            Some(mesh) if mesh.name == "mesh" => {
+                let uri = child_elements(mesh, "uri")
+                    .next()
+                    .and_then(|uri| uri.get_text())
+                    .unwrap_or_default();
+                let scale = match floats(mesh, "scale").as_slice() {
+                    [x, y, z] => Matrix::scale(*x, *y, *z),
+                    _ => Matrix::identity(),
+                };
+                let path = self.resolve_uri(uri.trim());
+                if path.extension().is_some_and(|ext| ext == "dae") {
+                    self.add_mesh(&path, transform.multiply(&scale), surface)?;
+                }
+                return Ok(());
+            }
+            Some(shape) if shape.name == "box" => match floats(shape, "size").as_slice() {
+                [x, y, z] => box_triangles([*x, *y, *z]),
+                _ => Vec::new(),
+            },
+            Some(shape) if shape.name == "sphere" => match floats(shape, "radius").as_slice() {
+                [radius] => sphere_triangles(*radius),
+                _ => Vec::new(),
+            },
+            Some(shape) if shape.name == "cylinder" => {
+                match (
+                    floats(shape, "radius").as_slice(),
+                    floats(shape, "length").as_slice(),
+                ) {
+                    ([radius], [length]) => cylinder_triangles(*radius, *length),
+                    _ => Vec::new(),
+                }
+            }
+            Some(shape) if shape.name == "plane" => match floats(shape, "size").as_slice() {
+                [x, y] => plane_triangles([*x, *y]),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        let surface = surface.unwrap_or_else(|| {
+            self.scene.add_surface(Surface {
+                color: DEFAULT_COLOR,
+                texture: None,
+            })
+        });
+        for positions in triangles {
+            self.scene.triangles.push(Triangle {
+                positions: [
+                    transform.transform_point(positions[0]),
+                    transform.transform_point(positions[1]),
+                    transform.transform_point(positions[2]),
+                ],
+                texcoords: None,
+                surface,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Add every instance of a DAE mesh, converted to meters/Z_UP. `surface` overrides
+    /// the mesh's own materials.
+    fn add_mesh(
+        &mut self,
+        mesh: &Path,
+        transform: Matrix,
+        surface: Option<usize>,
+    ) -> Result<(), Error> {
+        let root = read_dae(mesh)?;
+        let transform = transform.multiply(&normalizing_transform(&root)?);
+        let geometries: HashMap<String, _> = read_geometries(&root)?
+            .into_iter()
+            .map(|g| (g.id.clone(), g))
+            .collect();
+        let materials = read_materials(&root);
+        let mesh_dir = mesh.parent().unwrap_or(self.model_dir);
+
+        let mut surfaces: HashMap<String, usize> = HashMap::new();
+        for instance in read_scene(&root)? {
+            let geometry = match geometries.get(&instance.geometry) {
+                Some(g) => g,
+                None => continue,
+            };
+            let instance_transform = transform.multiply(&instance.transform);
+
+            for primitive in geometry.primitives.iter() {
+                let material_id = primitive
+                    .material
+                    .as_ref()
+                    .map(|symbol| instance.materials.get(symbol).unwrap_or(symbol).clone())
+                    .unwrap_or_default();
+                let primitive_surface = match surface {
+                    Some(surface) => surface,
+                    None => match surfaces.get(&material_id) {
+                        Some(surface) => *surface,
+                        None => {
+                            let material = materials.get(&material_id);
+                            let texture = material
+                                .and_then(|m| m.texture.as_deref())
+                                .and_then(|texture| self.load_mesh_texture(mesh_dir, texture));
+                            let color =
+                                material
+                                    .and_then(|m| m.diffuse)
+                                    .unwrap_or(if texture.is_some() {
+                                        [1.0, 1.0, 1.0, 1.0]
+                                    } else {
+                                        DEFAULT_COLOR
+                                    });
+                            let index = self.scene.add_surface(Surface { color, texture });
+                            surfaces.insert(material_id, index);
+                            index
+                        }
+                    },
+                };
+                let textured = self.scene.surfaces[primitive_surface].texture.is_some();
+
+                for corners in primitive.triangles.iter() {
+                    let texcoords = corners
+                        .iter()
+                        .map(|corner| corner.texcoord.map(|t| primitive.texcoords[t]))
+                        .collect::<Option<Vec<_>>>()
+                        .filter(|_| textured)
+                        .map(|t| [t[0], t[1], t[2]]);
+                    self.scene.triangles.push(Triangle {
+                        positions: [
+                            instance_transform
+                                .transform_point(geometry.positions[corners[0].position]),
+                            instance_transform
+                                .transform_point(geometry.positions[corners[1].position]),
+                            instance_transform
+                                .transform_point(geometry.positions[corners[2].position]),
+                        ],
+                        texcoords,
+                        surface: primitive_surface,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Surface of an SDF `<material>`, from its colors or its OGRE script
+    fn sdf_surface(&mut self, material: &Element) -> Option<Surface> {
+        let sdf = read_sdf_material(material);
+        let script = sdf
+            .script
+            .as_ref()
+            .and_then(|name| self.scripts.get(name))
+            .cloned();
+        let texture = script
+            .as_ref()
+            .and_then(|script| script.texture.as_ref())
+            .map(|texture| self.assets_dir.join(texture))
+            .and_then(|texture| self.load_texture(&texture));
+        let diffuse = sdf.diffuse.or(sdf.ambient).or_else(|| {
+            script
+                .as_ref()
+                .and_then(|s| s.diffuse.clone().or(s.ambient.clone()))
+        });
+
+        let color = match diffuse {
+            Some(c) if c.len() >= 3 => [c[0], c[1], c[2], c.get(3).copied().unwrap_or(1.0)],
+            _ if texture.is_some() => [1.0, 1.0, 1.0, 1.0],
+            _ => return None,
+        };
+
+        Some(Surface { color, texture })
+    }
+
+    /// Textures are looked up relative to the mesh, then in the model's
+    /// `materials/textures` where image processing moves them
+    fn load_mesh_texture(&mut self, mesh_dir: &Path, texture: &str) -> Option<Rc<RgbaImage>> {
+        let texture = texture.trim_start_matches("file://");
+        let relative = mesh_dir.join(texture);
+        let moved = Path::new(texture)
+            .file_name()
+            .map(|name| self.model_dir.join("materials").join("textures").join(name));
+
+        self.load_texture(&relative)
+            .or_else(|| moved.and_then(|moved| self.load_texture(&moved)))
+    }
+
+    fn load_texture(&mut self, path: &Path) -> Option<Rc<RgbaImage>> {
+        self.textures
+            .entry(path.to_path_buf())
+            .or_insert_with(|| image::open(path).ok().map(|i| Rc::new(i.to_rgba8())))
+            .clone()
+    }
+
+    /// `model://name/...` is relative to the assets directory, anything else to the model
+    fn resolve_uri(&self, uri: &str) -> PathBuf {
+        match uri.strip_prefix("model://") {
+            Some(path) => self.assets_dir.join(path),
+            None => self.model_dir.join(uri.trim_start_matches("file://")),
+        }
+    }
+}
+
+/// `<pose>x y z roll pitch yaw</pose>` of an element, with fixed-axis rotations
+fn read_pose(element: &Element) -> Matrix {
+    let pose = child_elements(element, "pose")
+        .next()
+        .and_then(|pose| pose.get_text())
+        .and_then(|text| parse_floats(&text).ok())
+        .unwrap_or_default();
+
+    match pose.as_slice() {
+        [x, y, z, roll, pitch, yaw] => Matrix::translation(*x, *y, *z)
+            .multiply(&Matrix::rotation([0.0, 0.0, 1.0], yaw.to_degrees()))
+            .multiply(&Matrix::rotation([0.0, 1.0, 0.0], pitch.to_degrees()))
+            .multiply(&Matrix::rotation([1.0, 0.0, 0.0], roll.to_degrees())),
+        _ => Matrix::identity(),
+    }
+}
+
+/// Merge the `materials.json` files that exist, the first one winning on conflicts
+fn read_script_materials(paths: &[PathBuf]) -> HashMap<String, OgreMaterial> {
+    let mut materials = HashMap::new();
+    for path in paths {
+        let parsed: Option<HashMap<String, OgreMaterial>> = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        for (name, material) in parsed.unwrap_or_default() {
+            materials.entry(name).or_insert(material);
+        }
+    }
+
+    materials
+}
+
+#[cfg(test)]
+mod load_model_scene_tests {
+    use super::*;
+
+    #[test]
+    fn it_places_sdf_visuals() -> Result<(), Error> {
+        let scene = load_model_scene(
+            &Path::new("tests")
+                .join("import")
+                .join("user_models")
+                .join("box"),
+        )?;
+
+        assert_eq!(scene.triangles.len(), 12);
+        assert_eq!(scene.surfaces[0].color, DEFAULT_COLOR);
+        let max_z = scene
+            .triangles
+            .iter()
+            .flat_map(|t| t.positions.iter())
+            .map(|p| p[2])
+            .fold(f64::MIN, f64::max);
+        assert_eq!(max_z, 1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_loads_textured_meshes() -> Result<(), Error> {
+        let scene = load_model_scene(&Path::new("tests").join("index").join("cube"))?;
+
+        assert_eq!(scene.triangles.len(), 12);
+        assert!(scene.triangles.iter().all(|t| t.texcoords.is_some()));
+        assert!(scene.surfaces[0].texture.is_some());
+        // Y_UP inches, moved up by 10 inches
+        let max_z = scene
+            .triangles
+            .iter()
+            .flat_map(|t| t.positions.iter())
+            .map(|p| p[2])
+            .fold(f64::MIN, f64::max);
+        assert!((max_z - 11.0 * 0.0254).abs() < 1e-9);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod read_pose_tests {
+    use super::*;
+
+    #[test]
+    fn it_rotates_then_translates() {
+        let element =
+            Element::parse("<visual><pose>1 0 0 0 0 1.5707963267948966</pose></visual>".as_bytes())
+                .unwrap();
+        let p = read_pose(&element).transform_point([1.0, 0.0, 0.0]);
+
+        assert!((p[0] - 1.0).abs() < 1e-12);
+        assert!((p[1] - 1.0).abs() < 1e-12);
+    }
+}
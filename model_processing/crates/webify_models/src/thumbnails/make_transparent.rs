@@ -0,0 +1,40 @@
+//! Chroma key a color out of an image, like ImageMagick's `-fuzz <n>% -transparent <color>`
+
+use image::RgbaImage;
+
+/// Make the pixels within `fuzz` (from 0 to 1) of `color` fully transparent. Like
+/// ImageMagick, the distance is the root mean square of the channel differences, as a
+/// fraction of the channel range.
+pub fn make_transparent(image: &mut RgbaImage, color: [u8; 3], fuzz: f64) {
+    let threshold = (fuzz * 255.0).powi(2) * 3.0;
+
+    for pixel in image.pixels_mut() {
+        let distance: f64 = (0..3)
+            .map(|channel| (pixel.0[channel] as f64 - color[channel] as f64).powi(2))
+            .sum();
+        if distance <= threshold {
+            pixel.0 = [0, 0, 0, 0];
+        }
+    }
+}
+
+#[cfg(test)]
+mod make_transparent_tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn it_keys_out_similar_colors() {
+        let mut image = RgbaImage::new(3, 1);
+        image.put_pixel(0, 0, Rgba([0, 255, 0, 255]));
+        // Off by 40% on red only: sqrt(0.4^2 / 3) is about 23%, within the fuzz
+        image.put_pixel(1, 0, Rgba([102, 255, 0, 255]));
+        image.put_pixel(2, 0, Rgba([255, 255, 0, 255]));
+
+        make_transparent(&mut image, [0, 255, 0], 0.3);
+
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(2, 0).0, [255, 255, 0, 255]);
+    }
+}
@@ -0,0 +1,22 @@
+//! Renders model thumbnails on the CPU, replacing `tools/gzthumbnails.sh` and its
+//! gzserver plugin
+
+mod add_drop_shadow;
+mod load_model_scene;
+mod make_transparent;
+mod postprocess;
+mod process;
+mod rasterize;
+mod scene;
+mod shapes;
+mod trim_transparent;
+
+pub use self::add_drop_shadow::add_drop_shadow;
+pub use self::load_model_scene::load_model_scene;
+pub use self::make_transparent::make_transparent;
+pub use self::postprocess::postprocess;
+pub use self::process::{process, SHADOW_SIGMA};
+pub use self::rasterize::rasterize;
+pub use self::scene::{Scene, Surface, Triangle};
+pub use self::shapes::{box_triangles, cylinder_triangles, plane_triangles, sphere_triangles};
+pub use self::trim_transparent::trim_transparent;
@@ -0,0 +1,127 @@
+//! Orchestrator to turn the green background thumbnails gzserver saves into final ones
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+
+use crate::progress::create_progress_bar;
+use crate::report::FileAction;
+use crate::thumbnails::{add_drop_shadow, make_transparent, trim_transparent, SHADOW_SIGMA};
+
+/// Background of `tools/green.world`
+const BACKGROUND: [u8; 3] = [0, 255, 0];
+/// How far from the background a color can be and still be keyed out
+const FUZZ: f64 = 0.3;
+
+/// Orchestrator to apply the ImageMagick steps of `tools/gzthumbnails.sh` to every
+/// model's thumbnail: key out the green background, trim the transparent borders and
+/// add a drop shadow. gzserver's `thumbnails/1.png` is used (or `0.png` when there is
+/// none) and the result is saved as `thumbnails/0.png`, after which the other views
+/// gzserver saves (`1.png` to `5.png`) are removed like the script does.
+pub fn postprocess(dir: &Path) -> std::result::Result<(), std::io::Error> {
+    let mut thumbnails_dirs: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path().join("thumbnails")))
+        .collect::<std::result::Result<_, _>>()?;
+    thumbnails_dirs.retain(|thumbnails_dir| thumbnails_dir.is_dir());
+    thumbnails_dirs.sort();
+
+    let postprocess_bar = create_progress_bar(thumbnails_dirs.len() as u64);
+    postprocess_bar.set_prefix("Thumbnail Post-processing");
+
+    let mut processed = 0;
+    for thumbnails_dir in thumbnails_dirs {
+        postprocess_bar.inc(1);
+
+        let source = [thumbnails_dir.join("1.png"), thumbnails_dir.join("0.png")]
+            .iter()
+            .find(|source| source.is_file())
+            .cloned();
+        let source = match source {
+            Some(source) => source,
+            None => continue,
+        };
+        postprocess_bar.set_message(&format!("Processing {:?}...", &source));
+
+        let mut image = match image::open(&source) {
+            Ok(image) => image.to_rgba8(),
+            Err(e) => {
+                postprocess_bar.println(format!("{} {:?}: {}", style("Skipped").red(), source, e));
+                continue;
+            }
+        };
+        make_transparent(&mut image, BACKGROUND, FUZZ);
+        let image = add_drop_shadow(&trim_transparent(&image), SHADOW_SIGMA);
+        let thumbnail = thumbnails_dir.join("0.png");
+        let postprocess_action = FileAction::start("postprocess thumbnail", &source);
+        match image.save(&thumbnail).map_err(std::io::Error::other) {
+            Ok(()) => postprocess_action.finish(&thumbnail),
+            Err(e) => {
+                postprocess_action.fail(&e);
+                return Err(e);
+            }
+        }
+
+        for view in 1..=5 {
+            let extra = thumbnails_dir.join(format!("{}.png", view));
+            if extra.is_file() {
+                let remove_action = FileAction::start("remove", &extra);
+                match fs::remove_file(&extra) {
+                    Ok(()) => remove_action.finish(&extra),
+                    Err(e) => {
+                        remove_action.fail(&e);
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        processed += 1;
+    }
+    postprocess_bar.finish_with_message(&format!("Post-processed {} thumbnail(s)", processed));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod postprocess_tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn it_replaces_green_screen_thumbnails() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests")
+            .join("thumbnails")
+            .join("test_run_it_replaces_green_screen_thumbnails");
+        let thumbnails_dir = dir.join("box").join("thumbnails");
+        fs::create_dir_all(&thumbnails_dir)?;
+
+        // A 20x10 red box on a slightly noisy green background
+        let gzserver = RgbaImage::from_fn(100, 100, |x, y| {
+            if (40..60).contains(&x) && (45..55).contains(&y) {
+                Rgba([200, 30, 30, 255])
+            } else {
+                Rgba([10, 240, (x % 20) as u8, 255])
+            }
+        });
+        for view in 1..=5 {
+            gzserver
+                .save(thumbnails_dir.join(format!("{}.png", view)))
+                .map_err(std::io::Error::other)?;
+        }
+
+        postprocess(&dir)?;
+
+        let thumbnail = image::open(thumbnails_dir.join("0.png"))
+            .map_err(std::io::Error::other)?
+            .to_rgba8();
+        assert_eq!(thumbnail.dimensions(), (20 + 40, 10 + 40));
+        assert_eq!(thumbnail.get_pixel(30, 25).0, [200, 30, 30, 255]);
+        assert_eq!(&thumbnail.get_pixel(15, 25).0[..3], &[0, 0, 0]);
+        assert!(!thumbnails_dir.join("1.png").exists());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
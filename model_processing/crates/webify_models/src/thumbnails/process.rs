@@ -0,0 +1,103 @@
+//! Orchestrator to render a thumbnail for every model in a directory
+
+use std::{fs, path::Path};
+
+use console::style;
+
+use crate::model_config::list_model_dirs;
+use crate::progress::create_progress_bar;
+use crate::report::FileAction;
+use crate::thumbnails::{add_drop_shadow, load_model_scene, rasterize, trim_transparent};
+
+/// Width and height the model is rendered at, before trimming and adding the shadow
+const THUMBNAIL_SIZE: u32 = 256;
+/// Blur of the drop shadow, the same as `tools/gzthumbnails.sh`
+pub const SHADOW_SIGMA: f32 = 10.0;
+
+/// Orchestrator to render every model (every directory with a `model.config`) into its
+/// `thumbnails/0.png`, with a transparent background and a drop shadow
+pub fn process(dir: &Path) -> std::result::Result<(), std::io::Error> {
+    let model_dirs = list_model_dirs(dir)?;
+
+    let thumbnails_bar = create_progress_bar(model_dirs.len() as u64);
+    thumbnails_bar.set_prefix("Thumbnails");
+
+    let mut rendered = 0;
+    for model_dir in model_dirs {
+        thumbnails_bar.inc(1);
+        thumbnails_bar.set_message(&format!("Rendering {:?}...", &model_dir));
+
+        let scene = match load_model_scene(&model_dir) {
+            Ok(scene) if !scene.triangles.is_empty() => scene,
+            Ok(_) => {
+                thumbnails_bar.println(format!(
+                    "{} {:?}: nothing to draw",
+                    style("Skipped").yellow(),
+                    model_dir
+                ));
+                continue;
+            }
+            Err(e) => {
+                thumbnails_bar.println(format!(
+                    "{} {:?}: {}",
+                    style("Skipped").red(),
+                    model_dir,
+                    e
+                ));
+                continue;
+            }
+        };
+
+        let image = add_drop_shadow(
+            &trim_transparent(&rasterize(&scene, THUMBNAIL_SIZE)),
+            SHADOW_SIGMA,
+        );
+        let thumbnails_dir = model_dir.join("thumbnails");
+        fs::create_dir_all(&thumbnails_dir)?;
+        let thumbnail = thumbnails_dir.join("0.png");
+        let render_action = FileAction::start("render thumbnail", &thumbnail);
+        match image.save(&thumbnail).map_err(std::io::Error::other) {
+            Ok(()) => render_action.finish(&thumbnail),
+            Err(e) => {
+                render_action.fail(&e);
+                return Err(e);
+            }
+        }
+        rendered += 1;
+    }
+    thumbnails_bar.finish_with_message(&format!("Rendered {} thumbnail(s)", rendered));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::import::copy_dir;
+
+    #[test]
+    fn it_writes_thumbnails() -> std::result::Result<(), std::io::Error> {
+        let dir = Path::new("tests")
+            .join("thumbnails")
+            .join("test_run_it_writes_thumbnails");
+        copy_dir(
+            &Path::new("tests").join("import").join("gazebo_models"),
+            &dir,
+        )?;
+
+        process(&dir)?;
+
+        for model in &["box", "sphere"] {
+            let thumbnail = image::open(dir.join(model).join("thumbnails").join("0.png"))
+                .map_err(std::io::Error::other)?
+                .to_rgba8();
+            let (width, height) = thumbnail.dimensions();
+            assert!(width <= THUMBNAIL_SIZE + 40 && height <= THUMBNAIL_SIZE + 40);
+            assert_eq!(thumbnail.get_pixel(0, 0).0[3], 0);
+            assert_eq!(thumbnail.get_pixel(width / 2, height / 2).0[3], 255);
+        }
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Draw a scene with a z-buffered, flat shaded, orthographic CPU rasterizer
+
+use image::{Rgba, RgbaImage};
+
+use crate::thumbnails::{Scene, Surface};
+
+/// Yaw and pitch of the camera in `tools/green.world`, which looks at the model from
+/// the front right and slightly above
+const CAMERA_YAW: f64 = 2.35619;
+const CAMERA_PITCH: f64 = 0.275643;
+/// Direction of Gazebo's default sun
+const SUN_DIRECTION: [f64; 3] = [-0.5, 0.1, -0.9];
+/// Ambient light of `tools/green.world`, plus a light from the camera so that sides
+/// facing away from the sun aren't flat
+const AMBIENT: f64 = 0.2;
+const SUN: f64 = 0.6;
+const HEADLIGHT: f64 = 0.3;
+/// Fraction of the image the model fills
+const FILL: f64 = 0.9;
+/// Pixels rendered per output pixel along each axis, for anti-aliasing
+const SUPERSAMPLING: u32 = 2;
+
+/// Render the scene in a `size` square with a transparent background, framed to fit
+pub fn rasterize(scene: &Scene, size: u32) -> RgbaImage {
+    let full_size = size * SUPERSAMPLING;
+    let camera = Camera::new();
+
+    let projected: Vec<[[f64; 3]; 3]> = scene
+        .triangles
+        .iter()
+        .map(|t| {
+            [
+                camera.project(t.positions[0]),
+                camera.project(t.positions[1]),
+                camera.project(t.positions[2]),
+            ]
+        })
+        .collect();
+    let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for p in projected.iter().flatten() {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    let extent = (max[0] - min[0]).max(max[1] - min[1]);
+    let scale = if extent > 0.0 && extent.is_finite() {
+        FILL * full_size as f64 / extent
+    } else {
+        1.0
+    };
+    let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+    let half = full_size as f64 / 2.0;
+    let to_screen = |p: [f64; 3]| {
+        [
+            half + (p[0] - center[0]) * scale,
+            half - (p[1] - center[1]) * scale,
+            p[2],
+        ]
+    };
+
+    let mut image = RgbaImage::new(full_size, full_size);
+    let mut depth = vec![f64::INFINITY; (full_size * full_size) as usize];
+    for (triangle, projected) in scene.triangles.iter().zip(projected) {
+        let surface = &scene.surfaces[triangle.surface];
+        let brightness = camera.shade(triangle.positions);
+        let screen = [
+            to_screen(projected[0]),
+            to_screen(projected[1]),
+            to_screen(projected[2]),
+        ];
+        let area = edge(screen[0], screen[1], screen[2]);
+        if area == 0.0 || !area.is_finite() {
+            continue;
+        }
+
+        let x_range = pixel_range(screen.iter().map(|p| p[0]), full_size);
+        let y_range = pixel_range(screen.iter().map(|p| p[1]), full_size);
+        for y in y_range.clone() {
+            for x in x_range.clone() {
+                let p = [x as f64 + 0.5, y as f64 + 0.5, 0.0];
+                let weights = [
+                    edge(screen[1], screen[2], p) / area,
+                    edge(screen[2], screen[0], p) / area,
+                    edge(screen[0], screen[1], p) / area,
+                ];
+                if weights.iter().any(|&w| w < 0.0) {
+                    continue;
+                }
+
+                let z: f64 = (0..3).map(|i| weights[i] * screen[i][2]).sum();
+                let index = (y * full_size + x) as usize;
+                if z >= depth[index] {
+                    continue;
+                }
+
+                let texcoord = triangle.texcoords.map(|t| {
+                    [
+                        (0..3).map(|i| weights[i] * t[i][0]).sum::<f64>(),
+                        (0..3).map(|i| weights[i] * t[i][1]).sum::<f64>(),
+                    ]
+                });
+                let color = sample(surface, texcoord);
+                // Cut out instead of blending, so that triangles can be drawn in any order
+                if color[3] < 0.5 {
+                    continue;
+                }
+
+                depth[index] = z;
+                image.put_pixel(
+                    x,
+                    y,
+                    Rgba([
+                        to_byte(color[0] * brightness),
+                        to_byte(color[1] * brightness),
+                        to_byte(color[2] * brightness),
+                        255,
+                    ]),
+                );
+            }
+        }
+    }
+
+    downsample(&image, SUPERSAMPLING)
+}
+
+/// Orthographic camera looking along `forward`
+struct Camera {
+    right: [f64; 3],
+    up: [f64; 3],
+    forward: [f64; 3],
+}
+
+impl Camera {
+    fn new() -> Camera {
+        let (sin_yaw, cos_yaw) = CAMERA_YAW.sin_cos();
+        let (sin_pitch, cos_pitch) = CAMERA_PITCH.sin_cos();
+        let forward = [cos_pitch * cos_yaw, cos_pitch * sin_yaw, -sin_pitch];
+        let right = [sin_yaw, -cos_yaw, 0.0];
+
+        Camera {
+            right,
+            up: normalize(cross(right, forward)),
+            forward,
+        }
+    }
+
+    /// Screen x and y, and the depth along the view direction
+    fn project(&self, p: [f64; 3]) -> [f64; 3] {
+        [dot(p, self.right), dot(p, self.up), dot(p, self.forward)]
+    }
+
+    /// Brightness of a flat triangle, lit from both sides
+    fn shade(&self, positions: [[f64; 3]; 3]) -> f64 {
+        let mut normal = normalize(cross(
+            sub(positions[1], positions[0]),
+            sub(positions[2], positions[0]),
+        ));
+        if dot(normal, self.forward) > 0.0 {
+            normal = [-normal[0], -normal[1], -normal[2]];
+        }
+        let sun = normalize(SUN_DIRECTION);
+        let diffuse = (-dot(normal, sun)).max(0.0);
+        let headlight = (-dot(normal, self.forward)).max(0.0);
+
+        (AMBIENT + SUN * diffuse + HEADLIGHT * headlight).min(1.0)
+    }
+}
+
+/// Surface color at a texture coordinate, textures repeating and with `v` going up
+fn sample(surface: &Surface, texcoord: Option<[f64; 2]>) -> [f64; 4] {
+    let mut color = surface.color;
+    if let (Some(texture), Some([u, v])) = (&surface.texture, texcoord) {
+        let (width, height) = texture.dimensions();
+        let x = ((u - u.floor()) * width as f64) as u32;
+        let y = ((1.0 - (v - v.floor())) * height as f64) as u32;
+        let texel = texture.get_pixel(x.min(width - 1), y.min(height - 1));
+        for (channel, value) in color.iter_mut().zip(texel.0.iter()) {
+            *channel *= *value as f64 / 255.0;
+        }
+    }
+
+    color
+}
+
+/// Average blocks of `factor` x `factor` pixels, weighting colors by their alpha so that
+/// the transparent background doesn't darken the edges
+fn downsample(image: &RgbaImage, factor: u32) -> RgbaImage {
+    let (width, height) = (image.width() / factor, image.height() / factor);
+    RgbaImage::from_fn(width, height, |x, y| {
+        let mut sum = [0.0; 4];
+        for dy in 0..factor {
+            for dx in 0..factor {
+                let pixel = image.get_pixel(x * factor + dx, y * factor + dy).0;
+                let alpha = pixel[3] as f64;
+                for channel in 0..3 {
+                    sum[channel] += pixel[channel] as f64 * alpha;
+                }
+                sum[3] += alpha;
+            }
+        }
+        if sum[3] == 0.0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+
+        Rgba([
+            (sum[0] / sum[3]).round() as u8,
+            (sum[1] / sum[3]).round() as u8,
+            (sum[2] / sum[3]).round() as u8,
+            (sum[3] / (factor * factor) as f64).round() as u8,
+        ])
+    })
+}
+
+/// Pixels whose centers may be covered by the coordinates, clamped to the image
+fn pixel_range<I: Iterator<Item = f64>>(values: I, size: u32) -> std::ops::Range<u32> {
+    let (min, max) = values.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let start = (min - 0.5).ceil().max(0.0) as u32;
+    let end = ((max - 0.5).floor() + 1.0).clamp(0.0, size as f64) as u32;
+
+    start..end.max(start)
+}
+
+/// Twice the signed area of the triangle abc, in screen space
+fn edge(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn to_byte(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let length = dot(v, v).sqrt();
+    if length == 0.0 {
+        return v;
+    }
+
+    [v[0] / length, v[1] / length, v[2] / length]
+}
+
+#[cfg(test)]
+mod rasterize_tests {
+    use super::*;
+    use crate::thumbnails::{box_triangles, Triangle};
+
+    fn cube(color: [f64; 4]) -> Scene {
+        let mut scene = Scene::default();
+        let surface = scene.add_surface(Surface {
+            color,
+            texture: None,
+        });
+        scene.triangles = box_triangles([1.0, 1.0, 1.0])
+            .into_iter()
+            .map(|positions| Triangle {
+                positions,
+                texcoords: None,
+                surface,
+            })
+            .collect();
+
+        scene
+    }
+
+    #[test]
+    fn it_frames_the_model_on_a_transparent_background() {
+        let image = rasterize(&cube([1.0, 0.0, 0.0, 1.0]), 64);
+
+        assert_eq!(image.dimensions(), (64, 64));
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+        let center = image.get_pixel(32, 32).0;
+        assert_eq!(center[3], 255);
+        assert!(center[0] > 0 && center[1] == 0 && center[2] == 0);
+        // Filled to about 90% of the width
+        let covered = (0..64).filter(|&x| image.get_pixel(x, 32).0[3] > 0).count();
+        assert!((56..=60).contains(&covered), "{} columns covered", covered);
+    }
+
+    #[test]
+    fn it_shades_faces_differently() {
+        let image = rasterize(&cube([1.0, 1.0, 1.0, 1.0]), 64);
+        let mut shades: Vec<u8> = image
+            .pixels()
+            .filter(|p| p.0[3] == 255)
+            .map(|p| p.0[0])
+            .collect();
+        shades.sort_unstable();
+        shades.dedup();
+
+        // Top, left and right faces, plus blended edges
+        assert!(shades.len() >= 3);
+    }
+
+    #[test]
+    fn it_draws_nothing_for_an_empty_scene() {
+        let image = rasterize(&Scene::default(), 16);
+
+        assert!(image.pixels().all(|p| p.0[3] == 0));
+    }
+}
@@ -0,0 +1,34 @@
+//! Triangles of a model in meters/Z_UP world space, ready to be rasterized
+
+use std::rc::Rc;
+
+use image::RgbaImage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    pub positions: [[f64; 3]; 3],
+    pub texcoords: Option<[[f64; 2]; 3]>,
+    /// Index in the scene's surfaces
+    pub surface: usize,
+}
+
+/// How triangles are colored: a diffuse color, multiplied by the texture if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct Surface {
+    pub color: [f64; 4],
+    pub texture: Option<Rc<RgbaImage>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scene {
+    pub triangles: Vec<Triangle>,
+    pub surfaces: Vec<Surface>,
+}
+
+impl Scene {
+    /// Add a surface and return its index
+    pub fn add_surface(&mut self, surface: Surface) -> usize {
+        self.surfaces.push(surface);
+        self.surfaces.len() - 1
+    }
+}
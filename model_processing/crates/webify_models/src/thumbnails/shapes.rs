@@ -0,0 +1,116 @@
+//! Tessellate the SDF geometry primitives, centered on the origin
+
+use std::f64::consts::PI;
+
+/// Segments around spheres and cylinders
+const SEGMENTS: usize = 24;
+
+pub fn box_triangles(size: [f64; 3]) -> Vec<[[f64; 3]; 3]> {
+    let [x, y, z] = [size[0] / 2.0, size[1] / 2.0, size[2] / 2.0];
+    let corner = |i: usize| {
+        [
+            if i & 1 == 0 { -x } else { x },
+            if i & 2 == 0 { -y } else { y },
+            if i & 4 == 0 { -z } else { z },
+        ]
+    };
+    // Corner indices of each face, as bits zyx
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+
+    faces
+        .iter()
+        .flat_map(|f| quad(corner(f[0]), corner(f[1]), corner(f[2]), corner(f[3])))
+        .collect()
+}
+
+pub fn sphere_triangles(radius: f64) -> Vec<[[f64; 3]; 3]> {
+    let rings = SEGMENTS / 2;
+    let point = |ring: usize, segment: usize| {
+        let theta = PI * ring as f64 / rings as f64;
+        let phi = 2.0 * PI * segment as f64 / SEGMENTS as f64;
+        [
+            radius * theta.sin() * phi.cos(),
+            radius * theta.sin() * phi.sin(),
+            radius * theta.cos(),
+        ]
+    };
+
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..SEGMENTS {
+            triangles.extend(quad(
+                point(ring, segment),
+                point(ring + 1, segment),
+                point(ring + 1, segment + 1),
+                point(ring, segment + 1),
+            ));
+        }
+    }
+
+    triangles
+}
+
+pub fn cylinder_triangles(radius: f64, length: f64) -> Vec<[[f64; 3]; 3]> {
+    let z = length / 2.0;
+    let point = |segment: usize, z: f64| {
+        let phi = 2.0 * PI * segment as f64 / SEGMENTS as f64;
+        [radius * phi.cos(), radius * phi.sin(), z]
+    };
+
+    let mut triangles = Vec::new();
+    for segment in 0..SEGMENTS {
+        triangles.extend(quad(
+            point(segment, -z),
+            point(segment + 1, -z),
+            point(segment + 1, z),
+            point(segment, z),
+        ));
+        triangles.push([[0.0, 0.0, z], point(segment, z), point(segment + 1, z)]);
+        triangles.push([[0.0, 0.0, -z], point(segment + 1, -z), point(segment, -z)]);
+    }
+
+    triangles
+}
+
+/// Plane facing +Z
+pub fn plane_triangles(size: [f64; 2]) -> Vec<[[f64; 3]; 3]> {
+    let [x, y] = [size[0] / 2.0, size[1] / 2.0];
+
+    quad([-x, -y, 0.0], [x, -y, 0.0], [x, y, 0.0], [-x, y, 0.0]).to_vec()
+}
+
+fn quad(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> [[[f64; 3]; 3]; 2] {
+    [[a, b, c], [a, c, d]]
+}
+
+#[cfg(test)]
+mod shapes_tests {
+    use super::*;
+
+    #[test]
+    fn it_tessellates_closed_shapes() {
+        assert_eq!(box_triangles([1.0, 2.0, 3.0]).len(), 12);
+        assert_eq!(sphere_triangles(1.0).len(), SEGMENTS * SEGMENTS);
+        assert_eq!(cylinder_triangles(1.0, 1.0).len(), SEGMENTS * 4);
+        assert_eq!(plane_triangles([1.0, 1.0]).len(), 2);
+    }
+
+    #[test]
+    fn it_centers_boxes() {
+        let points: Vec<[f64; 3]> = box_triangles([2.0, 4.0, 6.0])
+            .into_iter()
+            .flatten()
+            .collect();
+
+        assert!(points.contains(&[-1.0, -2.0, -3.0]));
+        assert!(points.contains(&[1.0, 2.0, 3.0]));
+        assert!(points.iter().all(|p| p[2].abs() == 3.0));
+    }
+}
@@ -0,0 +1,44 @@
+//! Crop the fully transparent borders of an image, like ImageMagick's `-trim`
+
+use image::{imageops, RgbaImage};
+
+/// Crop the image to the bounds of its pixels that aren't fully transparent. Images
+/// with no such pixels are returned as they are.
+pub fn trim_transparent(image: &RgbaImage) -> RgbaImage {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel.0[3] > 0 {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+    if min_x > max_x {
+        return image.clone();
+    }
+
+    imageops::crop_imm(image, min_x, min_y, max_x - min_x + 1, max_y - min_y + 1).to_image()
+}
+
+#[cfg(test)]
+mod trim_transparent_tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn it_crops_to_the_opaque_pixels() {
+        let mut image = RgbaImage::new(10, 10);
+        image.put_pixel(2, 3, Rgba([255, 0, 0, 255]));
+        image.put_pixel(5, 4, Rgba([0, 0, 0, 1]));
+
+        let trimmed = trim_transparent(&image);
+        assert_eq!(trimmed.dimensions(), (4, 2));
+        assert_eq!(trimmed.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn it_keeps_empty_images() {
+        assert_eq!(trim_transparent(&RgbaImage::new(3, 2)).dimensions(), (3, 2));
+    }
+}
@@ -0,0 +1,44 @@
+//! Structure that represents a problem found in a mesh
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueKind {
+    /// The file isn't COLLADA we can make sense of
+    Unreadable,
+    /// A URL pointing at an element that doesn't exist
+    DanglingReference,
+    IndexOutOfRange,
+    /// NaN or infinite vertex positions
+    InvalidPosition,
+    /// Triangles with no area
+    DegenerateTriangle,
+    MissingNormals,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// Element path inside the DAE, e.g. `<geometry id="Cube-mesh"> <triangles> #0`
+    pub location: String,
+    pub message: String,
+}
+
+impl Issue {
+    /// Whether `repair_mesh` knows how to fix this kind of issue
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self.kind,
+            IssueKind::IndexOutOfRange
+                | IssueKind::InvalidPosition
+                | IssueKind::DegenerateTriangle
+                | IssueKind::MissingNormals
+        )
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
@@ -0,0 +1,11 @@
+//! Detect (and optionally repair) broken DAE meshes before they reach the browser loader
+
+mod issue;
+mod process;
+mod repair_mesh;
+mod validate_mesh;
+
+pub use self::issue::{Issue, IssueKind};
+pub use self::process::process;
+pub use self::repair_mesh::repair_mesh;
+pub use self::validate_mesh::validate_mesh;
@@ -0,0 +1,73 @@
+//! Orchestrator to validate, and optionally repair, every mesh in a directory
+
+use std::path::{Path, PathBuf};
+
+use tracing::{info, info_span};
+
+use crate::logging::model_span;
+use crate::mesh_update::scan_dir_for_meshes;
+use crate::progress::{create_progress_bar, print_warning};
+use crate::report::FileAction;
+use crate::validate::{repair_mesh, validate_mesh, Issue};
+
+/// Orchestrator to validate every mesh and log the issues found with their location.
+/// With `repair`, meshes with repairable issues are fixed in place and validated again.
+/// Returns the number of issues left.
+pub fn process(dir: &Path, repair: bool) -> std::result::Result<usize, std::io::Error> {
+    let _stage = info_span!("stage", name = "validate").entered();
+    let meshes = scan_dir_for_meshes(dir)?;
+    let validate_bar = create_progress_bar(meshes.len() as u64);
+    validate_bar.set_prefix("Mesh Validation");
+
+    let mut results: Vec<(PathBuf, Vec<Issue>)> = Vec::new();
+    for mesh in meshes {
+        let _model = model_span(dir, &mesh).entered();
+        validate_bar.inc(1);
+        validate_bar.set_message(&format!("Validating {:?}...", &mesh));
+        let mut issues = validate_mesh(&mesh)?;
+
+        if repair && issues.iter().any(Issue::is_repairable) {
+            validate_bar.set_message(&format!("Repairing {:?}...", &mesh));
+            let repair_action = FileAction::start("repair", &mesh);
+            let repairs = match repair_mesh(&mesh) {
+                Ok(repairs) => repairs,
+                Err(e) => {
+                    repair_action.fail(&e);
+                    return Err(e);
+                }
+            };
+            // Meshes are only rewritten when something was repaired
+            if !repairs.is_empty() {
+                repair_action.finish(&mesh);
+            }
+            info!(
+                ?mesh,
+                "Repaired: welded {} vertices, dropped {} triangles, computed normals for {} primitives",
+                repairs.welded_vertices,
+                repairs.dropped_triangles,
+                repairs.recomputed_normals
+            );
+            issues = validate_mesh(&mesh)?;
+        }
+
+        if !issues.is_empty() {
+            results.push((mesh, issues));
+        }
+    }
+
+    let issue_count = results.iter().map(|(_, issues)| issues.len()).sum();
+    if issue_count == 0 {
+        validate_bar.finish_with_message("Meshes valid!");
+    } else {
+        validate_bar.finish_with_message(&format!("{} issue(s) found", issue_count));
+    }
+
+    for (mesh, issues) in results {
+        let _model = model_span(dir, &mesh).entered();
+        for issue in issues {
+            print_warning(format!("{:?} {}", issue.kind, issue), Some(&mesh));
+        }
+    }
+
+    Ok(issue_count)
+}
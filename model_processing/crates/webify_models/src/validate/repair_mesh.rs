@@ -0,0 +1,530 @@
+//! Repair a DAE mesh in place: weld duplicate vertices, drop degenerate faces and
+//! recompute missing normals
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::{Element, XMLNode};
+
+use crate::collada::{
+    child_elements, find_elements_mut, format_floats, parse_floats, read_dae, read_inputs,
+    read_polygons, triangulate, write_dae, PRIMITIVE_ELEMENTS,
+};
+use crate::validate::validate_mesh::is_degenerate;
+
+/// What `repair_mesh` changed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Repairs {
+    /// Duplicate vertex positions merged into one
+    pub welded_vertices: usize,
+    /// Degenerate triangles, or triangles with out of range indices or invalid positions
+    pub dropped_triangles: usize,
+    /// Primitives that were given computed normals
+    pub recomputed_normals: usize,
+}
+
+impl Repairs {
+    pub fn is_empty(&self) -> bool {
+        *self == Repairs::default()
+    }
+
+    fn add(&mut self, other: Repairs) {
+        self.welded_vertices += other.welded_vertices;
+        self.dropped_triangles += other.dropped_triangles;
+        self.recomputed_normals += other.recomputed_normals;
+    }
+}
+
+/// Repair every geometry of the specified DAE mesh. The file is only rewritten when
+/// something was repaired. Repaired primitives are rewritten as `<triangles>`.
+pub fn repair_mesh(mesh: &Path) -> std::result::Result<Repairs, std::io::Error> {
+    let mut root = read_dae(mesh)?;
+    let mut repairs = Repairs::default();
+    let mut result = Ok(());
+
+    find_elements_mut(&mut root, "mesh", &mut |m| match repair_geometry(m) {
+        Ok(r) => repairs.add(r),
+        Err(e) => result = Err(e),
+    });
+    result?;
+
+    if !repairs.is_empty() {
+        write_dae(mesh, &root)?;
+    }
+
+    Ok(repairs)
+}
+
+/// A `<source>` read back as numbers, with its accessor stride
+struct SourceData {
+    values: Vec<f64>,
+    stride: usize,
+}
+
+impl SourceData {
+    fn read(source: &Element) -> Result<SourceData, Error> {
+        let values = match source.get_child("float_array") {
+            Some(a) => parse_floats(&a.get_text().unwrap_or_default())?,
+            None => Vec::new(),
+        };
+        let stride = source
+            .get_child("technique_common")
+            .and_then(|t| t.get_child("accessor"))
+            .and_then(|a| a.attributes.get("stride"))
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(1)
+            .max(1);
+
+        Ok(SourceData { values, stride })
+    }
+
+    fn len(&self) -> usize {
+        self.values.len() / self.stride
+    }
+}
+
+fn repair_geometry(mesh: &mut Element) -> Result<Repairs, Error> {
+    let mut repairs = Repairs::default();
+
+    let vertices = match mesh.get_child("vertices") {
+        Some(v) => v,
+        None => return Ok(repairs),
+    };
+    let vertices_id = vertices.attributes.get("id").cloned().unwrap_or_default();
+    let vertex_inputs = read_inputs(vertices);
+    let position_id = match vertex_inputs.iter().find(|i| i.semantic == "POSITION") {
+        Some(input) => input.source.clone(),
+        None => return Ok(repairs),
+    };
+
+    let mut sources = HashMap::new();
+    for source in child_elements(mesh, "source") {
+        if let Some(id) = source.attributes.get("id") {
+            sources.insert(id.clone(), SourceData::read(source)?);
+        }
+    }
+    let positions: Vec<[f64; 3]> = match sources.get(&position_id) {
+        Some(source) if source.stride >= 3 => source
+            .values
+            .chunks_exact(source.stride)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect(),
+        _ => return Ok(repairs),
+    };
+
+    // Welding renumbers positions, which is only safe when nothing else is indexed
+    // through <vertices> and the positions are plain XYZ triples
+    let can_weld = vertex_inputs.len() == 1 && sources[&position_id].stride == 3;
+    let (remap, welded) = if can_weld {
+        weld(&positions)
+    } else {
+        ((0..positions.len()).map(Some).collect(), positions.clone())
+    };
+    if can_weld {
+        let invalid = positions.iter().filter(|p| !is_finite(p)).count();
+        repairs.welded_vertices = positions.len() - welded.len() - invalid;
+    }
+    let positions_changed = welded.len() != positions.len();
+
+    let has_vertex_normals = vertex_inputs.iter().any(|i| i.semantic == "NORMAL");
+    let normals_id = unique_id(&sources, &format!("{}-normals", position_id));
+    let mut normal_triangles: Vec<[usize; 3]> = Vec::new();
+
+    for child in mesh.children.iter_mut() {
+        let element = match child {
+            XMLNode::Element(e) if PRIMITIVE_ELEMENTS.contains(&e.name.as_str()) => e,
+            _ => continue,
+        };
+        let inputs = read_inputs(element);
+        let stride = inputs.iter().map(|i| i.offset + 1).max().unwrap_or(1);
+        let vertex_offset = match inputs
+            .iter()
+            .find(|i| i.semantic == "VERTEX" && i.source == vertices_id)
+        {
+            Some(input) => input.offset,
+            None => continue,
+        };
+        let needs_normals = !has_vertex_normals && !inputs.iter().any(|i| i.semantic == "NORMAL");
+        let source_lengths: Vec<(usize, usize)> = inputs
+            .iter()
+            .filter(|i| i.semantic != "VERTEX")
+            .map(|i| (i.offset, sources.get(&i.source).map_or(0, |s| s.len())))
+            .collect();
+
+        let mut kept: Vec<[Vec<usize>; 3]> = Vec::new();
+        let mut original_count = 0;
+        for polygon in read_polygons(element, stride)? {
+            let corners: Vec<&[usize]> = polygon.chunks_exact(stride).collect();
+            for triangle in triangulate(&element.name, &corners) {
+                original_count += 1;
+                let remapped: Option<Vec<usize>> = triangle
+                    .iter()
+                    .map(|c| remap.get(c[vertex_offset]).copied().flatten())
+                    .collect();
+                let in_range = triangle.iter().all(|c| {
+                    source_lengths
+                        .iter()
+                        .all(|(offset, length)| c[*offset] < *length)
+                });
+                let indices = match remapped {
+                    Some(r) if in_range => [r[0], r[1], r[2]],
+                    _ => continue,
+                };
+                if is_degenerate(
+                    [welded[indices[0]], welded[indices[1]], welded[indices[2]]],
+                    indices,
+                ) {
+                    continue;
+                }
+
+                let corner = |i: usize| {
+                    let mut tuple = triangle[i].to_vec();
+                    tuple[vertex_offset] = indices[i];
+                    if needs_normals {
+                        tuple.push(indices[i]);
+                    }
+                    tuple
+                };
+                kept.push([corner(0), corner(1), corner(2)]);
+                if needs_normals {
+                    normal_triangles.push(indices);
+                }
+            }
+        }
+
+        let dropped = original_count - kept.len();
+        if dropped == 0 && !needs_normals && !positions_changed && element.name == "triangles" {
+            continue;
+        }
+        repairs.dropped_triangles += dropped;
+        if needs_normals {
+            repairs.recomputed_normals += 1;
+        }
+        *element = rebuild_as_triangles(
+            element,
+            &kept,
+            needs_normals.then_some((&normals_id, stride)),
+        );
+    }
+
+    if positions_changed {
+        let source = child_elements_mut(mesh, "source")
+            .find(|s| s.attributes.get("id") == Some(&position_id))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Position source disappeared"))?;
+        set_source_values(source, &welded.concat(), welded.len());
+    }
+
+    if repairs.recomputed_normals > 0 {
+        let normals = compute_normals(&welded, &normal_triangles);
+        let position_index = mesh
+            .children
+            .iter()
+            .position(|c| {
+                c.as_element()
+                    .is_some_and(|e| e.attributes.get("id") == Some(&position_id))
+            })
+            .unwrap_or(0);
+        let source = new_source(mesh, &normals_id, &normals.concat(), normals.len());
+        mesh.children
+            .insert(position_index + 1, XMLNode::Element(source));
+    }
+
+    Ok(repairs)
+}
+
+/// Merge positions with identical coordinates. Returns the new index of each old
+/// position (None for NaN/infinite positions, which are removed) and the welded positions.
+fn weld(positions: &[[f64; 3]]) -> (Vec<Option<usize>>, Vec<[f64; 3]>) {
+    let mut seen: HashMap<[u64; 3], usize> = HashMap::new();
+    let mut welded = Vec::new();
+    let remap = positions
+        .iter()
+        .map(|p| {
+            if !is_finite(p) {
+                return None;
+            }
+            // Adding zero folds -0 into 0 so they weld together
+            let key = [
+                (p[0] + 0.0).to_bits(),
+                (p[1] + 0.0).to_bits(),
+                (p[2] + 0.0).to_bits(),
+            ];
+            Some(*seen.entry(key).or_insert_with(|| {
+                welded.push(*p);
+                welded.len() - 1
+            }))
+        })
+        .collect();
+
+    (remap, welded)
+}
+
+fn is_finite(p: &[f64; 3]) -> bool {
+    p.iter().all(|v| v.is_finite())
+}
+
+/// Smooth per-vertex normals, weighting each face by its area
+fn compute_normals(positions: &[[f64; 3]], triangles: &[[usize; 3]]) -> Vec<[f64; 3]> {
+    let mut normals = vec![[0.0; 3]; positions.len()];
+    for t in triangles {
+        let [a, b, c] = [positions[t[0]], positions[t[1]], positions[t[2]]];
+        let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        // Unnormalized, so its length is twice the face area
+        let face = [
+            e1[1] * e2[2] - e1[2] * e2[1],
+            e1[2] * e2[0] - e1[0] * e2[2],
+            e1[0] * e2[1] - e1[1] * e2[0],
+        ];
+        for &corner in t.iter() {
+            for axis in 0..3 {
+                normals[corner][axis] += face[axis];
+            }
+        }
+    }
+
+    normals
+        .into_iter()
+        .map(|n| {
+            let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            if length == 0.0 {
+                [0.0, 0.0, 1.0]
+            } else {
+                [n[0] / length, n[1] / length, n[2] / length]
+            }
+        })
+        .collect()
+}
+
+/// Replace a primitive with a `<triangles>` holding the kept triangles, and a NORMAL
+/// input at offset `stride` when normals were computed
+fn rebuild_as_triangles(
+    original: &Element,
+    triangles: &[[Vec<usize>; 3]],
+    normals: Option<(&String, usize)>,
+) -> Element {
+    let mut element = new_element(original, "triangles");
+    if let Some(material) = original.attributes.get("material") {
+        element
+            .attributes
+            .insert(String::from("material"), material.clone());
+    }
+    element
+        .attributes
+        .insert(String::from("count"), triangles.len().to_string());
+
+    for input in child_elements(original, "input") {
+        element.children.push(XMLNode::Element(input.clone()));
+    }
+    if let Some((normals_id, offset)) = normals {
+        let mut input = new_element(original, "input");
+        input
+            .attributes
+            .insert(String::from("semantic"), String::from("NORMAL"));
+        input
+            .attributes
+            .insert(String::from("source"), format!("#{}", normals_id));
+        input
+            .attributes
+            .insert(String::from("offset"), offset.to_string());
+        element.children.push(XMLNode::Element(input));
+    }
+
+    let indices: Vec<String> = triangles
+        .iter()
+        .flat_map(|t| t.iter())
+        .flat_map(|corner| corner.iter())
+        .map(|i| i.to_string())
+        .collect();
+    let mut p = new_element(original, "p");
+    p.children.push(XMLNode::Text(indices.join(" ")));
+    element.children.push(XMLNode::Element(p));
+
+    element
+}
+
+/// Build an XYZ `<source>` with its `<float_array>` and accessor
+fn new_source(sibling: &Element, id: &str, values: &[f64], count: usize) -> Element {
+    let mut source = new_element(sibling, "source");
+    source.attributes.insert(String::from("id"), id.to_string());
+
+    let mut float_array = new_element(sibling, "float_array");
+    float_array
+        .attributes
+        .insert(String::from("id"), format!("{}-array", id));
+    source.children.push(XMLNode::Element(float_array));
+
+    let mut technique = new_element(sibling, "technique_common");
+    let mut accessor = new_element(sibling, "accessor");
+    accessor
+        .attributes
+        .insert(String::from("source"), format!("#{}-array", id));
+    accessor
+        .attributes
+        .insert(String::from("stride"), String::from("3"));
+    for axis in ["X", "Y", "Z"].iter() {
+        let mut param = new_element(sibling, "param");
+        param
+            .attributes
+            .insert(String::from("name"), axis.to_string());
+        param
+            .attributes
+            .insert(String::from("type"), String::from("float"));
+        accessor.children.push(XMLNode::Element(param));
+    }
+    technique.children.push(XMLNode::Element(accessor));
+    source.children.push(XMLNode::Element(technique));
+
+    set_source_values(&mut source, values, count);
+    source
+}
+
+/// Replace the values of a source, keeping the counts of its array and accessor in sync
+fn set_source_values(source: &mut Element, values: &[f64], count: usize) {
+    if let Some(float_array) = source.get_mut_child("float_array") {
+        float_array
+            .attributes
+            .insert(String::from("count"), values.len().to_string());
+        float_array.children = vec![XMLNode::Text(format_floats(values))];
+    }
+    if let Some(accessor) = source
+        .get_mut_child("technique_common")
+        .and_then(|t| t.get_mut_child("accessor"))
+    {
+        accessor
+            .attributes
+            .insert(String::from("count"), count.to_string());
+    }
+}
+
+fn new_element(sibling: &Element, name: &str) -> Element {
+    let mut element = Element::new(name);
+    element.namespace = sibling.namespace.clone();
+    element
+}
+
+fn unique_id(sources: &HashMap<String, SourceData>, base: &str) -> String {
+    let mut id = base.to_string();
+    while sources.contains_key(&id) {
+        id.push('_');
+    }
+    id
+}
+
+fn child_elements_mut<'a>(
+    element: &'a mut Element,
+    name: &'a str,
+) -> impl Iterator<Item = &'a mut Element> {
+    element.children.iter_mut().filter_map(move |c| match c {
+        XMLNode::Element(e) if e.name == name => Some(e),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod repair_mesh_tests {
+    use std::fs;
+
+    use super::*;
+    use crate::validate::{validate_mesh, IssueKind};
+
+    fn setup(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let example_path = Path::new("tests").join("validate").join("broken");
+        let destination_path = Path::new("tests").join("validate").join(test_run_id);
+
+        fs::create_dir_all(destination_path.join("meshes"))?;
+        fs::copy(
+            example_path.join("meshes").join("broken.dae"),
+            destination_path.join("meshes").join("broken.dae"),
+        )?;
+
+        Ok(())
+    }
+
+    fn teardown(test_run_id: &str) -> std::result::Result<(), std::io::Error> {
+        let destination_path = Path::new("tests").join("validate").join(test_run_id);
+        fs::remove_dir_all(destination_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_repairs_what_it_can() -> std::result::Result<(), std::io::Error> {
+        let test_run_id = "test_run_it_repairs_what_it_can";
+        setup(test_run_id)?;
+
+        let mesh = Path::new("tests")
+            .join("validate")
+            .join(test_run_id)
+            .join("meshes")
+            .join("broken.dae");
+        let repairs = repair_mesh(&mesh)?;
+
+        assert_eq!(
+            repairs,
+            Repairs {
+                welded_vertices: 1,
+                dropped_triangles: 3,
+                recomputed_normals: 1,
+            }
+        );
+        // Dangling references are left for a human to fix
+        let kinds: Vec<IssueKind> = validate_mesh(&mesh)?.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec![IssueKind::DanglingReference]);
+
+        // Nothing left to repair the second time around
+        assert!(repair_mesh(&mesh)?.is_empty());
+
+        teardown(test_run_id)?;
+        Ok(())
+    }
+
+    #[test]
+    fn it_leaves_clean_meshes_alone() -> std::result::Result<(), std::io::Error> {
+        let mesh = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+
+        assert!(repair_mesh(&mesh)?.is_empty());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod weld_tests {
+    use super::*;
+
+    #[test]
+    fn it_merges_duplicates_and_drops_nan() {
+        let (remap, welded) = weld(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [-0.0, 0.0, 0.0],
+            [f64::NAN, 0.0, 0.0],
+        ]);
+
+        assert_eq!(remap, vec![Some(0), Some(1), Some(0), None]);
+        assert_eq!(welded, vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]);
+    }
+}
+
+#[cfg(test)]
+mod compute_normals_tests {
+    use super::*;
+
+    #[test]
+    fn it_follows_the_winding() {
+        let normals = compute_normals(
+            &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            &[[0, 1, 2]],
+        );
+
+        assert_eq!(normals, vec![[0.0, 0.0, 1.0]; 3]);
+    }
+}
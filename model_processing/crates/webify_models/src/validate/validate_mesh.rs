@@ -0,0 +1,253 @@
+//! Check a DAE mesh for the problems that crash the browser loader
+
+use std::{
+    collections::HashSet,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use xmltree::Element;
+
+use crate::collada::{
+    child_elements, find_elements, read_dae, read_geometries_unchecked, Geometry,
+};
+use crate::validate::{Issue, IssueKind};
+
+/// Validate the specified DAE mesh, returning every issue found. Only I/O errors are
+/// returned as errors; a file that can't be parsed is reported as an issue.
+pub fn validate_mesh(mesh: &Path) -> std::result::Result<Vec<Issue>, std::io::Error> {
+    let root = match read_dae(mesh) {
+        Ok(root) => root,
+        Err(e) if e.kind() == ErrorKind::InvalidData => return Ok(vec![unreadable(e)]),
+        Err(e) => return Err(e),
+    };
+
+    let mut issues = find_dangling_references(&root);
+    match read_geometries_unchecked(&root) {
+        Ok(geometries) => {
+            for geometry in geometries.iter() {
+                issues.extend(check_geometry(geometry));
+            }
+        }
+        Err(e) => issues.push(unreadable(e)),
+    }
+
+    Ok(issues)
+}
+
+fn unreadable(error: Error) -> Issue {
+    Issue {
+        kind: IssueKind::Unreadable,
+        location: String::from("<COLLADA>"),
+        message: error.to_string(),
+    }
+}
+
+/// Find `<instance_geometry>` and `<instance_node>` URLs that point at nothing
+fn find_dangling_references(root: &Element) -> Vec<Issue> {
+    let ids: HashSet<&str> = find_elements(root, "geometry")
+        .into_iter()
+        .chain(find_elements(root, "node"))
+        .filter_map(|e| e.attributes.get("id").map(String::as_str))
+        .collect();
+
+    let mut issues = Vec::new();
+    for node in find_elements(root, "node") {
+        let node_id = node.attributes.get("id").map(String::as_str).unwrap_or("");
+        for name in ["instance_geometry", "instance_node"].iter() {
+            for instance in child_elements(node, name) {
+                let url = instance
+                    .attributes
+                    .get("url")
+                    .map(String::as_str)
+                    .unwrap_or("");
+                if !ids.contains(url.trim_start_matches('#')) {
+                    issues.push(Issue {
+                        kind: IssueKind::DanglingReference,
+                        location: format!("<node id={:?}> <{} url={:?}>", node_id, name, url),
+                        message: format!("{} does not match any element id", url),
+                    });
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Check positions and every primitive of a geometry. Problems repeated across
+/// many triangles are reported once per primitive, with a count and the first offender.
+fn check_geometry(geometry: &Geometry) -> Vec<Issue> {
+    let geometry_location = format!("<geometry id={:?}>", geometry.id);
+    let mut issues = Vec::new();
+
+    let invalid_positions: Vec<usize> = geometry
+        .positions
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !p.iter().all(|v| v.is_finite()))
+        .map(|(i, _)| i)
+        .collect();
+    if let Some(first) = invalid_positions.first() {
+        issues.push(Issue {
+            kind: IssueKind::InvalidPosition,
+            location: format!("{} <source> position {}", geometry_location, first),
+            message: format!(
+                "{} vertex position(s) are NaN or infinite",
+                invalid_positions.len()
+            ),
+        });
+    }
+
+    for (index, primitive) in geometry.primitives.iter().enumerate() {
+        let location = format!("{} <{}> #{}", geometry_location, primitive.element, index);
+        let mut out_of_range = Vec::new();
+        let mut degenerate = Vec::new();
+
+        for (t, triangle) in primitive.triangles.iter().enumerate() {
+            let in_range = triangle.iter().all(|c| {
+                c.position < geometry.positions.len()
+                    && c.normal.is_none_or(|n| n < primitive.normals.len())
+                    && c.texcoord.is_none_or(|t| t < primitive.texcoords.len())
+            });
+            if !in_range {
+                out_of_range.push(t);
+            } else if is_degenerate(
+                [
+                    geometry.positions[triangle[0].position],
+                    geometry.positions[triangle[1].position],
+                    geometry.positions[triangle[2].position],
+                ],
+                [
+                    triangle[0].position,
+                    triangle[1].position,
+                    triangle[2].position,
+                ],
+            ) {
+                degenerate.push(t);
+            }
+        }
+
+        if let Some(first) = out_of_range.first() {
+            issues.push(Issue {
+                kind: IssueKind::IndexOutOfRange,
+                location: format!("{}, triangle {}", location, first),
+                message: format!(
+                    "{} triangle(s) index past the end of their sources",
+                    out_of_range.len()
+                ),
+            });
+        }
+        if let Some(first) = degenerate.first() {
+            issues.push(Issue {
+                kind: IssueKind::DegenerateTriangle,
+                location: format!("{}, triangle {}", location, first),
+                message: format!("{} triangle(s) have no area", degenerate.len()),
+            });
+        }
+        if primitive.triangles.iter().any(|t| t[0].normal.is_none()) {
+            issues.push(Issue {
+                kind: IssueKind::MissingNormals,
+                location,
+                message: String::from("no NORMAL input, the web client will render it flat black"),
+            });
+        }
+    }
+
+    issues
+}
+
+/// A triangle is degenerate when it reuses a vertex or its corners are collinear
+pub fn is_degenerate(positions: [[f64; 3]; 3], indices: [usize; 3]) -> bool {
+    if indices[0] == indices[1] || indices[1] == indices[2] || indices[0] == indices[2] {
+        return true;
+    }
+
+    let [a, b, c] = positions;
+    let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let cross = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    let length = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+
+    // |e1 x e2| = |e1| |e2| sin(angle), so this is a tolerance on the angle
+    length(cross) <= f64::EPSILON * length(e1) * length(e2)
+}
+
+#[cfg(test)]
+mod validate_mesh_tests {
+    use super::*;
+
+    #[test]
+    fn it_passes_a_clean_mesh() -> std::result::Result<(), std::io::Error> {
+        let mesh = Path::new("tests")
+            .join("collada")
+            .join("cube")
+            .join("meshes")
+            .join("cube.dae");
+
+        assert_eq!(validate_mesh(&mesh)?, Vec::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_finds_every_kind_of_issue() -> std::result::Result<(), std::io::Error> {
+        let mesh = Path::new("tests")
+            .join("validate")
+            .join("broken")
+            .join("meshes")
+            .join("broken.dae");
+        let issues = validate_mesh(&mesh)?;
+        let kinds: Vec<IssueKind> = issues.iter().map(|i| i.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::DanglingReference,
+                IssueKind::InvalidPosition,
+                IssueKind::IndexOutOfRange,
+                IssueKind::DegenerateTriangle,
+                IssueKind::MissingNormals,
+            ]
+        );
+        assert_eq!(
+            issues[0].location,
+            r##"<node id="Broken"> <instance_geometry url="#Missing-mesh">"##
+        );
+        assert_eq!(
+            issues[2].location,
+            r#"<geometry id="Broken-mesh"> <triangles> #0, triangle 2"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_unparseable_files() -> std::result::Result<(), std::io::Error> {
+        let issues = validate_mesh(&Path::new("tests").join("README.md"))?;
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::Unreadable);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod is_degenerate_tests {
+    use super::*;
+
+    #[test]
+    fn it_flags_repeated_and_collinear_corners() {
+        let flat = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]];
+        let proper = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+        assert!(is_degenerate(flat, [0, 1, 2]));
+        assert!(is_degenerate(proper, [0, 1, 1]));
+        assert!(!is_degenerate(proper, [0, 1, 2]));
+    }
+}
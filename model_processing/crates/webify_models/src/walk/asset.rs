@@ -0,0 +1,179 @@
+//! Structure that represents a file found walking a models directory, and what it is
+
+use std::path::{Path, PathBuf};
+
+use crate::image_processing::scan_dir_for_images::TEXTURE_IMAGE_TYPES;
+
+/// Extensions of the meshes SDF can refer to, besides COLLADA
+const MESH_TYPES: [&str; 2] = ["obj", "stl"];
+
+/// Extensions of SDF worlds, and of the robot descriptions Gazebo converts to SDF
+const WORLD_TYPES: [&str; 2] = ["world", "urdf"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    /// An image with one of the `TEXTURE_IMAGE_TYPES`, matched exactly like the
+    /// converters do
+    Texture,
+    /// A COLLADA mesh, the only meshes the converters and the web client read
+    Collada,
+    /// Any other mesh SDF can refer to, like OBJ or STL
+    Mesh,
+    /// An OGRE material script, i.e. a `.material` in a `materials/scripts` directory
+    Material,
+    /// An SDF model
+    Sdf,
+    /// An SDF world or a URDF
+    World,
+    /// A `model.config`
+    Config,
+    Other,
+}
+
+impl AssetKind {
+    /// Work out what a file is from its name, and directory for material scripts
+    pub fn of(path: &Path) -> AssetKind {
+        let extension = extension(path);
+        let lowercase = extension.to_lowercase();
+
+        if path.file_name().is_some_and(|name| name == "model.config") {
+            AssetKind::Config
+        } else if TEXTURE_IMAGE_TYPES.contains(&extension.as_str()) {
+            AssetKind::Texture
+        } else if lowercase == "dae" {
+            AssetKind::Collada
+        } else if MESH_TYPES.contains(&lowercase.as_str()) {
+            AssetKind::Mesh
+        } else if is_material_script(path) {
+            AssetKind::Material
+        } else if lowercase == "sdf" {
+            AssetKind::Sdf
+        } else if WORLD_TYPES.contains(&lowercase.as_str()) {
+            AssetKind::World
+        } else {
+            AssetKind::Other
+        }
+    }
+
+    /// Whether it's a mesh of any format
+    pub fn is_mesh(self) -> bool {
+        matches!(self, AssetKind::Collada | AssetKind::Mesh)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub path: PathBuf,
+    pub kind: AssetKind,
+}
+
+impl Asset {
+    pub fn new(path: PathBuf) -> Asset {
+        Asset {
+            kind: AssetKind::of(&path),
+            path,
+        }
+    }
+
+    /// Extension of the file as is, with any invalid UTF-8 replaced, or empty without one
+    pub fn extension(&self) -> String {
+        extension(&self.path)
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Whether the file is a `.material` inside a `materials/scripts` directory
+pub fn is_material_script(path: &Path) -> bool {
+    let scripts_dir = path.parent();
+    let materials_dir = scripts_dir.and_then(Path::parent);
+
+    path.extension().is_some_and(|ext| ext == "material")
+        && scripts_dir
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == "scripts")
+        && materials_dir
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == "materials")
+}
+
+#[cfg(test)]
+mod asset_kind_tests {
+    use super::*;
+
+    #[test]
+    fn it_classifies_the_files() {
+        let model_dir = Path::new("box");
+
+        assert_eq!(
+            AssetKind::of(&model_dir.join("model.config")),
+            AssetKind::Config
+        );
+        assert_eq!(AssetKind::of(&model_dir.join("model.sdf")), AssetKind::Sdf);
+        assert_eq!(AssetKind::of(&model_dir.join("box.SDF")), AssetKind::Sdf);
+        assert_eq!(AssetKind::of(Path::new("empty.world")), AssetKind::World);
+        assert_eq!(
+            AssetKind::of(&model_dir.join("meshes").join("box.DAE")),
+            AssetKind::Collada
+        );
+        assert_eq!(
+            AssetKind::of(&model_dir.join("meshes").join("box.stl")),
+            AssetKind::Mesh
+        );
+        assert!(AssetKind::Collada.is_mesh() && AssetKind::Mesh.is_mesh());
+        assert_eq!(
+            AssetKind::of(&model_dir.join("materials").join("textures").join("box.jpg")),
+            AssetKind::Texture
+        );
+        // Texture types are matched exactly, the converters don't handle the others
+        assert_eq!(
+            AssetKind::of(&model_dir.join("materials").join("textures").join("box.JPG")),
+            AssetKind::Other
+        );
+        assert_eq!(
+            AssetKind::of(
+                &model_dir
+                    .join("materials")
+                    .join("scripts")
+                    .join("box.material")
+            ),
+            AssetKind::Material
+        );
+        assert_eq!(
+            AssetKind::of(&model_dir.join("README.md")),
+            AssetKind::Other
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_handles_non_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let asset = Asset::new(Path::new(OsStr::from_bytes(b"box_\xff.png")).to_path_buf());
+
+        assert_eq!(asset.kind, AssetKind::Texture);
+        assert_eq!(asset.extension(), "png");
+    }
+}
+
+#[cfg(test)]
+mod is_material_script_tests {
+    use super::*;
+
+    #[test]
+    fn it_only_accepts_scripts_in_materials_scripts() {
+        assert!(is_material_script(Path::new(
+            "box/materials/scripts/box.material"
+        )));
+        assert!(!is_material_script(Path::new("box/scripts/box.material")));
+        assert!(!is_material_script(Path::new(
+            "box/materials/scripts/box.program"
+        )));
+    }
+}
@@ -0,0 +1,7 @@
+//! The one walker every scanner uses to find the files of a models directory
+
+mod asset;
+mod walk_assets;
+
+pub use self::asset::{Asset, AssetKind};
+pub use self::walk_assets::{walk_all_assets, walk_assets};
@@ -0,0 +1,163 @@
+//! Walk a models directory once, finding and classifying every file
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::filter::{is_dir_excluded, is_excluded};
+use crate::progress::print_warning;
+use crate::walk::Asset;
+
+/// Directories nested deeper than this are left out, real models are nowhere near it
+const MAX_DEPTH: usize = 64;
+
+/// Find every file of `dir` that the scan filter doesn't exclude, sorted by path
+pub fn walk_assets(dir: &Path) -> std::result::Result<Vec<Asset>, std::io::Error> {
+    walk(dir, true)
+}
+
+/// Find every file of `dir`, even the ones the scan filter excludes, sorted by path
+pub fn walk_all_assets(dir: &Path) -> std::result::Result<Vec<Asset>, std::io::Error> {
+    walk(dir, false)
+}
+
+/// Symbolic links are followed, but a directory is never entered
+/// twice so links back up the tree don't loop, and broken links are skipped
+fn walk(dir: &Path, filtered: bool) -> std::result::Result<Vec<Asset>, std::io::Error> {
+    let mut assets = vec![];
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    visited.insert(fs::canonicalize(dir)?);
+
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((current, depth)) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            let metadata = match fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            if !metadata.is_dir() {
+                if !(filtered && is_excluded(dir, &path)) {
+                    assets.push(Asset::new(path));
+                }
+            } else if filtered && is_dir_excluded(dir, &path) {
+                continue;
+            } else if depth + 1 >= MAX_DEPTH {
+                print_warning(
+                    format!(
+                        "Skipping, it's nested more than {} directories deep",
+                        MAX_DEPTH
+                    ),
+                    Some(&path),
+                );
+            } else if visited.insert(fs::canonicalize(&path)?) {
+                pending.push((path, depth + 1));
+            }
+        }
+    }
+    assets.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(assets)
+}
+
+#[cfg(test)]
+mod walk_assets_tests {
+    use super::*;
+    use crate::context::RunContext;
+    use crate::filter::PathFilter;
+    use crate::walk::AssetKind;
+
+    #[test]
+    fn it_classifies_every_file_in_order() -> std::result::Result<(), std::io::Error> {
+        let model_dir = Path::new("tests").join("index").join("cube");
+
+        let assets = walk_assets(&model_dir)?;
+        assert_eq!(
+            assets
+                .iter()
+                .map(|asset| (asset.path.clone(), asset.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    model_dir
+                        .join("materials")
+                        .join("textures")
+                        .join("cube_diffuse.png"),
+                    AssetKind::Texture
+                ),
+                (
+                    model_dir.join("meshes").join("cube.dae"),
+                    AssetKind::Collada
+                ),
+                (model_dir.join("model.config"), AssetKind::Config),
+                (model_dir.join("model.sdf"), AssetKind::Sdf),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_keeps_the_excluded_files_when_asked() -> std::result::Result<(), std::io::Error> {
+        let model_dir = Path::new("tests").join("index").join("cube");
+
+        let assets = walk_all_assets(&model_dir)?;
+        assert_eq!(assets.len(), 5);
+        assert_eq!(assets[4].path, model_dir.join("thumbnails").join("0.png"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_only_includes_files() -> std::result::Result<(), std::io::Error> {
+        let model_dir = Path::new("tests").join("index").join("cube");
+        let context = RunContext {
+            filter: PathFilter::new(&[String::from("*.png")], &[])?,
+            ..RunContext::default()
+        };
+
+        let _context = context.enter_dir(&model_dir);
+        // The include doesn't keep the walk out of the directories the textures are in
+        assert_eq!(
+            walk_assets(&model_dir)?
+                .into_iter()
+                .map(|asset| asset.path)
+                .collect::<Vec<_>>(),
+            vec![model_dir
+                .join("materials")
+                .join("textures")
+                .join("cube_diffuse.png")]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_follows_links_without_looping() -> std::result::Result<(), std::io::Error> {
+        let test_dir = Path::new("tests").join("walk").join("test_run_links");
+        let meshes_dir = test_dir.join("box").join("meshes");
+        fs::create_dir_all(&meshes_dir)?;
+        fs::write(meshes_dir.join("box.dae"), "")?;
+        std::os::unix::fs::symlink("..", meshes_dir.join("parent"))?;
+        std::os::unix::fs::symlink("missing.dae", meshes_dir.join("broken.dae"))?;
+
+        let assets = walk_assets(&test_dir);
+        fs::remove_dir_all(&test_dir)?;
+
+        assert_eq!(
+            assets?
+                .into_iter()
+                .map(|asset| asset.path)
+                .collect::<Vec<_>>(),
+            vec![meshes_dir.join("box.dae")]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! Work out which models changed from the paths of filesystem events
+
+use std::{collections::BTreeSet, path::Path, path::PathBuf};
+
+use crate::filter::is_excluded;
+
+/// Names of the models, i.e. the top-level directories of `source_dir`, that the
+/// changed paths belong to. Hidden directories and files directly in `source_dir` are
+/// ignored, as are the paths the scan filter excludes, but paths that no longer exist
+/// count, so removed models are picked up.
+pub fn affected_models(source_dir: &Path, paths: &[PathBuf]) -> BTreeSet<String> {
+    paths
+        .iter()
+        .filter(|path| !is_excluded(source_dir, path))
+        .filter_map(|path| path.strip_prefix(source_dir).ok())
+        .filter(|relative| {
+            relative.components().count() > 1 || !source_dir.join(relative).is_file()
+        })
+        .filter_map(|relative| relative.components().next())
+        .map(|name| name.as_os_str().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .collect()
+}
+
+#[cfg(test)]
+mod affected_models_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_models_of_the_paths() {
+        let source_dir = Path::new("tests").join("import").join("gazebo_models");
+        let paths = vec![
+            source_dir.join("box").join("model.sdf"),
+            source_dir.join("box").join("meshes").join("box.dae"),
+            source_dir.join("sphere"),
+            source_dir.join("removed").join("model.sdf"),
+            source_dir.join("removed"),
+            source_dir.join(".git").join("index"),
+            Path::new("elsewhere").join("model.sdf"),
+        ];
+
+        let models: Vec<_> = affected_models(&source_dir, &paths).into_iter().collect();
+        assert_eq!(models, vec!["box", "removed", "sphere"]);
+    }
+}
@@ -0,0 +1,9 @@
+//! Set of functions to webify models into an output directory as their sources change
+
+mod affected_models;
+mod process;
+mod rebuild_models;
+
+pub use self::affected_models::affected_models;
+pub use self::process::process;
+pub use self::rebuild_models::rebuild_models;
@@ -0,0 +1,159 @@
+//! Orchestrator to webify models into an output directory, then again as they change
+
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    sync::mpsc::{channel, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use console::style;
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+use tracing::info_span;
+
+use crate::progress::{print_error, print_message, print_warning};
+use crate::watch::{affected_models, rebuild_models};
+
+/// How long the files must be left alone before a rebuild starts, so that saving a
+/// model from an editor, which touches several files, only rebuilds it once
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Orchestrator to webify every model of `source_dir` into `output_dir`, then watch
+/// `source_dir` and rebuild only the models whose files change. `convert` runs on a
+/// directory holding the models being rebuilt and `finish` on the whole output
+/// directory after each rebuild. Only returns if the watcher fails.
+pub fn process<C, F>(
+    source_dir: &Path,
+    output_dir: &Path,
+    convert: C,
+    finish: F,
+) -> std::result::Result<(), Error>
+where
+    C: Fn(&Path) -> std::result::Result<(), Error>,
+    F: Fn(&Path) -> std::result::Result<(), Error>,
+{
+    let _stage = info_span!("stage", name = "watch").entered();
+    let source_dir = source_dir.canonicalize()?;
+    fs::create_dir_all(output_dir)?;
+    if output_dir.canonicalize()?.starts_with(&source_dir) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The output directory can't be inside the watched directory.",
+        ));
+    }
+
+    rebuild(
+        &source_dir,
+        output_dir,
+        &list_models(&source_dir)?,
+        &convert,
+        &finish,
+    );
+
+    let (sender, receiver) = channel();
+    let mut watcher = watcher(sender, DEBOUNCE).map_err(|e| Error::other(e.to_string()))?;
+    watcher
+        .watch(&source_dir, RecursiveMode::Recursive)
+        .map_err(|e| Error::other(e.to_string()))?;
+    print_message(format!(
+        "\n{} {} for changes...",
+        style("Watching").bold(),
+        style(source_dir.display()).dim()
+    ));
+
+    loop {
+        let mut paths = vec![];
+        let mut rescan = false;
+        let mut event = receiver.recv().map_err(Error::other)?;
+        // Gather whatever else arrives in the meantime into the same rebuild
+        loop {
+            match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Chmod(path)
+                | DebouncedEvent::Remove(path) => paths.push(path),
+                DebouncedEvent::Rename(from, to) => paths.extend(vec![from, to]),
+                DebouncedEvent::Rescan => rescan = true,
+                DebouncedEvent::Error(e, path) => {
+                    print_warning(format!("Watch error: {}", e), path.as_deref())
+                }
+                DebouncedEvent::NoticeWrite(_) | DebouncedEvent::NoticeRemove(_) => {}
+            }
+
+            event = match receiver.recv_timeout(DEBOUNCE) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(e) => return Err(Error::other(e)),
+            };
+        }
+
+        let changed: Vec<String> = if rescan {
+            list_models(&source_dir)?
+        } else {
+            affected_models(&source_dir, &paths).into_iter().collect()
+        };
+        if !changed.is_empty() {
+            rebuild(&source_dir, output_dir, &changed, &convert, &finish);
+        }
+    }
+}
+
+/// Names of the top-level, non-hidden directories of `source_dir`
+fn list_models(source_dir: &Path) -> std::result::Result<Vec<String>, Error> {
+    let mut names = vec![];
+    for entry in fs::read_dir(source_dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() && !name.starts_with('.') {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+/// Rebuild the models and log how it went, without stopping the watch on errors, or when
+/// the conversion panics
+fn rebuild<C, F>(source_dir: &Path, output_dir: &Path, names: &[String], convert: &C, finish: &F)
+where
+    C: Fn(&Path) -> std::result::Result<(), Error>,
+    F: Fn(&Path) -> std::result::Result<(), Error>,
+{
+    let started = Instant::now();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rebuild_models(source_dir, output_dir, names, convert)
+            .and_then(|rebuilt| finish(output_dir).map(|_| rebuilt))
+    }))
+    .unwrap_or_else(|_| Err(Error::other("the conversion panicked")));
+
+    match result {
+        Ok(rebuilt) => print_message(format!(
+            "{} {} in {:.1}s",
+            style("Rebuilt").green().bold(),
+            describe(&rebuilt, names),
+            started.elapsed().as_secs_f64()
+        )),
+        Err(e) => print_error(
+            format!("Failed to rebuild {}: {}", names.join(", "), e),
+            None,
+        ),
+    }
+}
+
+/// List the rebuilt models, and the removed ones
+fn describe(rebuilt: &[String], names: &[String]) -> String {
+    let removed: Vec<&str> = names
+        .iter()
+        .filter(|name| !rebuilt.contains(name))
+        .map(String::as_str)
+        .collect();
+
+    match (rebuilt.is_empty(), removed.is_empty()) {
+        (_, true) => rebuilt.join(", "),
+        (true, false) => format!("removed {}", removed.join(", ")),
+        (false, false) => format!("{}, removed {}", rebuilt.join(", "), removed.join(", ")),
+    }
+}
@@ -0,0 +1,77 @@
+//! Webify fresh copies of some models into the output directory
+
+use std::{fs, path::Path};
+
+use crate::cache::{Staging, STAGING_DIR};
+
+/// Replace the named models in `output_dir` with fresh copies from `source_dir`, after
+/// running `convert` on a directory holding only them. Models no longer in `source_dir`
+/// are removed from `output_dir`. Returns the names of the models that were rebuilt.
+pub fn rebuild_models<C>(
+    source_dir: &Path,
+    output_dir: &Path,
+    names: &[String],
+    convert: C,
+) -> std::result::Result<Vec<String>, std::io::Error>
+where
+    C: FnOnce(&Path) -> std::result::Result<(), std::io::Error>,
+{
+    let mut staging = Staging::create(&output_dir.join(STAGING_DIR))?;
+
+    let mut rebuilt = vec![];
+    for name in names {
+        let target = output_dir.join(name);
+        if target.is_dir() {
+            fs::remove_dir_all(&target)?;
+        }
+
+        let source = source_dir.join(name);
+        if source.is_dir() {
+            staging.copy(&source, &target)?;
+            rebuilt.push(name.clone());
+        }
+    }
+
+    let converted = if rebuilt.is_empty() {
+        Ok(())
+    } else {
+        convert(staging.path())
+    };
+    staging.finish()?;
+    converted?;
+
+    Ok(rebuilt)
+}
+
+#[cfg(test)]
+mod rebuild_models_tests {
+    use super::*;
+
+    #[test]
+    fn it_rebuilds_only_the_named_models() -> std::result::Result<(), std::io::Error> {
+        let source_dir = Path::new("tests").join("import").join("gazebo_models");
+        let output_dir = Path::new("tests")
+            .join("watch")
+            .join("test_run_it_rebuilds_only_the_named_models");
+        fs::create_dir_all(output_dir.join("removed"))?;
+        fs::create_dir_all(output_dir.join("sphere"))?;
+
+        let names = vec![String::from("box"), String::from("removed")];
+        let rebuilt = rebuild_models(&source_dir, &output_dir, &names, |staging| {
+            assert!(staging.join("box").join("model.config").is_file());
+            assert!(!staging.join("sphere").exists());
+            fs::write(staging.join("box").join("webified.txt"), "yes")
+        })?;
+
+        assert_eq!(rebuilt, vec!["box"]);
+        assert!(output_dir.join("box").join("webified.txt").is_file());
+        assert!(!output_dir.join("removed").exists());
+        assert!(output_dir.join("sphere").is_dir());
+        assert!(!output_dir.join(STAGING_DIR).exists());
+        // The sources are left alone
+        assert!(!source_dir.join("box").join("webified.txt").exists());
+
+        fs::remove_dir_all(output_dir)?;
+        Ok(())
+    }
+}